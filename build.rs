@@ -24,7 +24,10 @@ fn main() -> Result<()> {
         "proto/terrainium/v1/common.proto",
         "proto/terrainium/v1/command.proto",
         "proto/terrainium/v1/deactivate.proto",
+        "proto/terrainium/v1/event.proto",
+        "proto/terrainium/v1/ping.proto",
         "proto/terrainium/v1/status.proto",
+        "proto/terrainium/v1/switch.proto",
     ];
     let mut config = prost_build::Config::new();
     config.enable_type_names();
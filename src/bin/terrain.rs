@@ -1,25 +1,36 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use anyhow::{Context as AnyhowContext, Result, bail};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
 use home::home_dir;
-use terrainium::client::args::{BiomeArg, ClientArgs, GetArgs, UpdateArgs, Verbs};
+use terrainium::client::args::{ClientArgs, GetArgs, UpdateArgs, Verbs};
+use terrainium::client::handlers::diff::DiffTarget;
 #[cfg(feature = "terrain-schema")]
 use terrainium::client::handlers::schema;
 use terrainium::client::handlers::{
-    construct, destruct, edit, enter, exit, generate, get, init, status, update,
+    completions, construct, destruct, diff, doctor, edit, enter, events, exit, explain, export,
+    generate, get, import, init, list, probe, prompt, status, switch, update, validate,
 };
 use terrainium::client::logging::init_logging;
 use terrainium::client::shell::update_rc;
 use terrainium::client::types::config::Config;
 use terrainium::client::types::context::Context;
-use terrainium::client::types::environment::Environment;
+use terrainium::client::types::template::Template;
 use terrainium::client::types::terrain::Terrain;
+use terrainium::common::constants::{TERRAIN_COMPLETE, TERRAIN_DIR};
 use terrainium::common::execute::Executor;
+use terrainium::common::types::command::Command;
 use terrainium::common::types::styles::warning;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // prints completion candidates and exits if requested by shell
+    CompleteEnv::with_factory(ClientArgs::command)
+        .var(TERRAIN_COMPLETE)
+        .complete();
+
     if cfg!(debug_assertions) {
         println!(
             "{}: you are running debug build of terrainium, which might cause some unwanted \
@@ -56,11 +67,74 @@ async fn main() -> Result<()> {
                     .context("failed to get the terrain status");
             }
 
+            if let Verbs::Events {
+                terrain_name,
+                session_id,
+                json,
+            } = verbs
+            {
+                return events::handle(terrain_name, session_id, json, None)
+                    .await
+                    .context("failed to stream events from daemon");
+            }
+
+            if let Verbs::Prompt {
+                format,
+                json,
+                no_daemon,
+                timeout,
+            } = verbs
+            {
+                return prompt::handle(format, json, no_daemon, timeout, None)
+                    .await
+                    .context("failed to print the prompt");
+            }
+
+            if let Verbs::Completions { shell } = verbs {
+                return completions::handle(shell).context("failed to generate completions");
+            }
+
+            if let Verbs::List { json, stale, prune } = verbs {
+                return list::handle(&home_dir, json, stale, prune)
+                    .context("failed to list the terrains");
+            }
+
             let current_dir = std::env::current_dir().context("failed to get current directory")?;
+
+            if let Verbs::Probe { default_biome } = verbs {
+                return probe::handle(
+                    &home_dir,
+                    &current_dir,
+                    std::env::var(TERRAIN_DIR).ok(),
+                    default_biome,
+                )
+                .context("failed to probe the terrain");
+            }
+
+            if let Verbs::Doctor { json, fix } = verbs {
+                return doctor::handle(&home_dir, &current_dir, Arc::new(Executor), json, fix)
+                    .await
+                    .context("failed to run the doctor");
+            }
+
+            let templates_dir = Context::templates_dir(&home_dir);
             let context = Context::new(&verbs, home_dir, current_dir, Arc::new(Executor))?;
 
-            if let Verbs::Init { example, edit, .. } = verbs {
-                return init::handle(context, example, edit)
+            if let Verbs::Init {
+                example,
+                edit,
+                template,
+                detect,
+                ..
+            } = verbs
+            {
+                let template = Template::select(
+                    &templates_dir,
+                    context.terrain_dir(),
+                    template.as_deref(),
+                    detect,
+                )?;
+                return init::handle(context, example, edit, template)
                     .context("failed to initialize new terrain");
             }
 
@@ -68,6 +142,23 @@ async fn main() -> Result<()> {
                 return edit::handle(context).context("failed to edit the terrain");
             }
 
+            if let Verbs::Validate {
+                fix: true, dry_run, ..
+            } = verbs
+            {
+                return validate::fix(context, dry_run).context("failed to fix the terrain");
+            }
+
+            if let Verbs::Validate {
+                format: Some(format),
+                fail_on,
+                ..
+            } = verbs
+            {
+                return validate::report(context, format, fail_on)
+                    .context("failed to validate the terrain");
+            }
+
             let (terrain, terrain_toml) = Terrain::get_validated_and_fixed_terrain(&context)?;
 
             match verbs {
@@ -78,24 +169,8 @@ async fn main() -> Result<()> {
                 Verbs::Generate { .. } => generate::handle(context, terrain)
                     .context("failed to generate scripts for the terrain")?,
 
-                Verbs::Validate { .. } => {
-                    // create environments to run environment validations inside `Environment::from`
-                    Environment::from(&terrain, BiomeArg::None, context.terrain_dir())?;
-                    let res: Result<Vec<Environment>> = terrain
-                        .biomes()
-                        .keys()
-                        .map(|biome_name| {
-                            // create environments to run environment validations
-                            Environment::from(
-                                &terrain,
-                                BiomeArg::Some(biome_name.to_string()),
-                                context.terrain_dir(),
-                            )
-                        })
-                        .collect();
-                    // propagate any errors found during creation of environment
-                    res?;
-                }
+                Verbs::Validate { fail_on, .. } => validate::handle(context, terrain, fail_on)
+                    .context("failed to validate the terrain")?,
 
                 Verbs::Get {
                     json,
@@ -107,6 +182,8 @@ async fn main() -> Result<()> {
                     constructors,
                     destructors,
                     auto_apply,
+                    reveal,
+                    explain,
                     ..
                 } => get::handle(
                     context,
@@ -121,16 +198,42 @@ async fn main() -> Result<()> {
                         constructors,
                         destructors,
                         auto_apply,
+                        reveal,
+                        explain,
                     },
                 )
                 .context("failed to get the terrain values")?,
 
+                Verbs::Explain {
+                    biome, env, alias, ..
+                } => explain::handle(context, terrain, biome, env, alias)
+                    .context("failed to explain the terrain values")?,
+
+                Verbs::Export {
+                    to,
+                    biome,
+                    keep_references,
+                    output,
+                    ..
+                } => export::handle(context, terrain, to, biome, keep_references, output)
+                    .context("failed to export the terrain")?,
+
                 Verbs::Update {
                     set_default,
                     biome,
                     new,
                     env,
                     alias,
+                    unset_env,
+                    unset_alias,
+                    delete_biome,
+                    rename_biome,
+                    add_constructor,
+                    add_destructor,
+                    cwd,
+                    remove_constructor,
+                    remove_destructor,
+                    background,
                     auto_apply,
                     backup,
                     ..
@@ -143,6 +246,16 @@ async fn main() -> Result<()> {
                         biome,
                         alias,
                         env,
+                        unset_env,
+                        unset_alias,
+                        delete_biome,
+                        rename_biome: rename_biome
+                            .map(|names| (names[0].clone(), names[1].clone())),
+                        add_constructor: add_constructor.map(|cmd| to_command(cmd, cwd.clone())),
+                        add_destructor: add_destructor.map(|cmd| to_command(cmd, cwd.clone())),
+                        remove_constructor,
+                        remove_destructor,
+                        background,
                         new,
                         backup,
                         auto_apply,
@@ -150,6 +263,11 @@ async fn main() -> Result<()> {
                 )
                 .context("failed to update the terrain values")?,
 
+                Verbs::Import { from, path, biome } => {
+                    import::handle(context, terrain_toml, from, &path, biome)
+                        .context("failed to import into the terrain")?
+                }
+
                 Verbs::Construct { biome } => construct::handle(context, biome, terrain, None)
                     .await
                     .context("failed to run the constructors for terrain")?,
@@ -164,11 +282,37 @@ async fn main() -> Result<()> {
                         .context("failed to enter the terrain")?
                 }
 
+                Verbs::Switch { biome } => switch::handle(context, terrain, biome, None)
+                    .await
+                    .context("failed to switch the biome")?,
+
+                Verbs::Diff {
+                    biomes,
+                    session,
+                    shell,
+                    json,
+                } => {
+                    let target = match (session, shell) {
+                        (Some(session_id), _) => DiffTarget::Session(session_id),
+                        (None, true) => DiffTarget::Shell,
+                        (None, false) => DiffTarget::Biomes(biomes[0].clone(), biomes[1].clone()),
+                    };
+                    diff::handle(context, terrain, target, json, None)
+                        .await
+                        .context("failed to diff the terrain")?
+                }
+
                 Verbs::Exit => exit::handle(context, terrain, None)
                     .await
                     .context("failed to exit the terrain")?,
 
-                Verbs::Status { .. } => {
+                Verbs::Status { .. }
+                | Verbs::Events { .. }
+                | Verbs::Probe { .. }
+                | Verbs::Prompt { .. }
+                | Verbs::Doctor { .. }
+                | Verbs::List { .. }
+                | Verbs::Completions { .. } => {
                     // no need to do anything as handled above
                 }
 
@@ -180,3 +324,9 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// first value is the executable, rest are its arguments
+fn to_command(mut values: Vec<String>, cwd: Option<PathBuf>) -> Command {
+    let exe = values.remove(0);
+    Command::new(exe, values, cwd)
+}
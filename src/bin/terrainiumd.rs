@@ -2,7 +2,10 @@ use std::process::exit;
 use std::sync::Arc;
 
 use anyhow::{Context, Result, bail};
-use clap::Parser;
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use terrainium::client::handlers::status::daemon_status;
+use terrainium::common::constants::TERRAIN_COMPLETE;
 use terrainium::common::execute::{Execute, Executor};
 use terrainium::common::types::command::Command;
 use terrainium::common::types::paths::{DaemonPaths, get_terrainiumd_paths};
@@ -162,9 +165,15 @@ async fn start() -> Result<()> {
                 Verbs::Stop => {
                     service.stop().context("failed to stop service")?;
                 }
-                Verbs::Status => {
+                Verbs::Status { daemon } => {
                     let status = service.status().context("failed to get service status")?;
                     println!("{status}");
+                    if daemon {
+                        let status = daemon_status(None)
+                            .await
+                            .context("failed to ping the daemon")?;
+                        println!("{status}");
+                    }
                 }
                 Verbs::Reload => {
                     service.reload().context("failed to reload the service")?;
@@ -198,6 +207,11 @@ async fn start() -> Result<()> {
 
 #[tokio::main]
 async fn main() {
+    // prints completion candidates and exits if requested by shell
+    CompleteEnv::with_factory(DaemonArgs::command)
+        .var(TERRAIN_COMPLETE)
+        .complete();
+
     match start().await {
         Ok(_) => {}
         Err(err) => {
@@ -1,4 +1,5 @@
 pub mod args;
+pub mod completions;
 pub mod handlers;
 pub mod logging;
 pub mod shell;
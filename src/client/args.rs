@@ -3,12 +3,15 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::bail;
-use clap::{Parser, Subcommand, ValueHint};
+use clap::{Parser, Subcommand, ValueEnum, ValueHint};
+use clap_complete::engine::ArgValueCandidates;
 use tracing::Level;
 
+use crate::client::completions;
 use crate::client::types::terrain::AutoApply;
-use crate::client::validation::{IdentifierType, validate_identifiers};
+use crate::client::validation::{IdentifierType, ValidationMessageLevel, validate_identifiers};
 use crate::common::constants::{NONE, SHELL, TERRAIN_NAME, UNSUPPORTED, ZSH, ZSHRC_PATH};
+use crate::common::types::command::Command;
 use crate::common::utils::VERSION_INFO;
 
 const DEFAULT_SELECTED: &str = "__default__";
@@ -74,6 +77,7 @@ pub struct Options {
     pub log_level: Level,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand, Debug)]
 pub enum Verbs {
     /// Initialize terrain in current directory
@@ -92,9 +96,25 @@ pub enum Verbs {
         central: bool,
 
         /// Creates terrain.toml with example terrain included.
-        #[arg(short = 'x', long)]
+        #[arg(short = 'x', long, conflicts_with_all = ["template", "detect"])]
         example: bool,
 
+        /// Creates terrain.toml from template.
+        ///
+        /// Built-in templates are rust, node, python-venv, go and docker-compose.
+        /// User templates are loaded from ~/.config/terrainium/templates/<name>.toml
+        /// and take precedence over built-in templates with the same name.
+        #[arg(short, long)]
+        template: Option<String>,
+
+        /// Detects project type from files in current directory (Cargo.toml,
+        /// package.json, pyproject.toml, go.mod, docker-compose.yml, .nvmrc,
+        /// .python-version) and pre-populates terrain.toml
+        ///
+        /// If used with `--template`, detected values take precedence.
+        #[arg(short, long)]
+        detect: bool,
+
         /// Opens terrain.toml in EDITOR after creation
         ///
         /// Launches editor defined in EDITOR environment variable.
@@ -123,13 +143,23 @@ pub enum Verbs {
         #[arg(short,
             long,
             value_name = "DEFAULT",
-            conflicts_with_all = ["biome", "new", "env", "alias", "auto_apply"])]
+            conflicts_with_all = ["biome", "new", "env", "alias", "auto_apply", "unset_env",
+                "unset_alias", "delete_biome", "rename_biome", "add_constructor", "add_destructor",
+                "remove_constructor", "remove_destructor"],
+            add = ArgValueCandidates::new(completions::biomes))]
         set_default: Option<String>,
 
         /// Updates specified biome
         ///
         /// If not specified default biome will be updated
-        #[arg(short, long, group = "biomes", default_value = DEFAULT_SELECTED, hide_default_value = true)]
+        #[arg(
+            short,
+            long,
+            group = "biomes",
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
         biome: BiomeArg,
 
         /// Creates a new biome
@@ -159,6 +189,99 @@ pub enum Verbs {
         #[arg(short, long, value_name = "ALIAS=\"alias value\"")]
         alias: Vec<Pair>,
 
+        /// Removes environment variable from specified biome in '--biome'
+        ///
+        /// Multiple environment variables can be removed by
+        /// --unset-env ENV_VAR1 --unset-env ENV_VAR2
+        #[arg(long, value_name = "ENV_VAR", add = ArgValueCandidates::new(completions::envs))]
+        unset_env: Vec<String>,
+
+        /// Removes alias from specified biome in '--biome'
+        ///
+        /// Multiple aliases can be removed by --unset-alias alias1 --unset-alias alias2
+        #[arg(long, value_name = "ALIAS", add = ArgValueCandidates::new(completions::aliases))]
+        unset_alias: Vec<String>,
+
+        /// Deletes specified biome
+        ///
+        /// If deleted biome is the default biome, default biome will be removed.
+        /// Will fail if any other biome extends deleted biome.
+        #[arg(long,
+            value_name = "BIOME",
+            conflicts_with_all = ["biome", "new", "env", "alias", "unset_env", "unset_alias",
+                "rename_biome", "add_constructor", "add_destructor", "remove_constructor",
+                "remove_destructor"],
+            add = ArgValueCandidates::new(completions::biomes))]
+        delete_biome: Option<String>,
+
+        /// Renames biome OLD to NEW
+        ///
+        /// Default biome and biomes that extend renamed biome are updated
+        /// to use the new name.
+        #[arg(long,
+            num_args = 2,
+            value_names = ["OLD", "NEW"],
+            conflicts_with_all = ["biome", "new", "env", "alias", "unset_env", "unset_alias",
+                "add_constructor", "add_destructor", "remove_constructor", "remove_destructor"],
+            add = ArgValueCandidates::new(completions::biomes))]
+        rename_biome: Option<Vec<String>>,
+
+        /// Adds a constructor to specified biome in '--new' or '--biome'
+        ///
+        /// First value is the executable and rest of the values are its arguments,
+        /// e.g. --add-constructor /bin/echo "entering terrain".
+        /// All values till the end are used as arguments, to pass options after
+        /// the arguments end them with ';', e.g. --add-constructor /bin/echo -n hi \; --background
+        ///
+        /// Constructor is added to foreground commands unless '--background' is used.
+        #[arg(long,
+            num_args = 1..,
+            allow_hyphen_values = true,
+            value_terminator = ";",
+            value_names = ["EXE", "ARGS"],
+            group = "add_commands")]
+        add_constructor: Option<Vec<String>>,
+
+        /// Adds a destructor to specified biome in '--new' or '--biome'
+        ///
+        /// First value is the executable and rest of the values are its arguments,
+        /// e.g. --add-destructor /bin/echo "exiting terrain".
+        /// All values till the end are used as arguments, to pass options after
+        /// the arguments end them with ';', e.g. --add-destructor /bin/echo -n hi \; --background
+        ///
+        /// Destructor is added to foreground commands unless '--background' is used.
+        #[arg(long,
+            num_args = 1..,
+            allow_hyphen_values = true,
+            value_terminator = ";",
+            value_names = ["EXE", "ARGS"],
+            group = "add_commands")]
+        add_destructor: Option<Vec<String>>,
+
+        /// Directory in which added constructor or destructor is run
+        ///
+        /// If not specified, command is run in terrain directory.
+        #[arg(long, requires = "add_commands")]
+        cwd: Option<PathBuf>,
+
+        /// Removes constructor at index N from specified biome in '--biome'
+        ///
+        /// Index starts from 0 and is the position of the constructor in
+        /// foreground constructors, or in background constructors if '--background' is used.
+        #[arg(long, value_name = "N", conflicts_with = "new")]
+        remove_constructor: Option<usize>,
+
+        /// Removes destructor at index N from specified biome in '--biome'
+        ///
+        /// Index starts from 0 and is the position of the destructor in
+        /// foreground destructors, or in background destructors if '--background' is used.
+        #[arg(long, value_name = "N", conflicts_with = "new")]
+        remove_destructor: Option<usize>,
+
+        /// Adds or removes background commands instead of foreground commands
+        #[arg(long)]
+        background: bool,
+
         /// Updates auto_apply value
         #[arg(long, value_enum)]
         auto_apply: Option<AutoApply>,
@@ -174,6 +297,65 @@ pub enum Verbs {
         active: bool,
     },
 
+    /// Imports environment from direnv, dotenv, docker compose or devcontainer
+    ///
+    /// Values are translated to environment variables, aliases and constructors
+    /// where it is unambiguous and written to terrain.toml of current directory.
+    /// Values that cannot be translated are reported and not imported.
+    Import {
+        /// Format of the file to import
+        #[arg(long, value_enum)]
+        from: ImportFormat,
+
+        /// Path of `.envrc`, `.env`, compose file or `devcontainer.json`
+        #[arg(value_hint = ValueHint::FilePath)]
+        path: PathBuf,
+
+        /// Biome to import values into, it is created if it does not exist.
+        ///
+        /// If not specified values are imported into main terrain.
+        #[arg(short, long, add = ArgValueCandidates::new(completions::biomes))]
+        biome: Option<String>,
+    },
+
+    /// Exports environment of the terrain for tools that cannot run terrainium
+    ///
+    /// Renders environment variables of the selected biome, aliases as recipes or
+    /// launch configurations and constructors as tasks where the format supports them.
+    /// Environment variables that reference secret providers are not exported.
+    Export {
+        /// Format to export the environment in
+        #[arg(long, value_enum)]
+        to: ExportFormat,
+
+        /// Biome to export.
+        /// If it is not specified default biome will be used.
+        ///
+        /// If "none" is used, main terrain will be used without applying any Biome.
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
+        biome: BiomeArg,
+
+        /// Keeps environment variable references (`${VAR}`) instead of resolving them
+        ///
+        /// Only supported by dotenv, direnv and justfile formats.
+        #[arg(long)]
+        keep_references: bool,
+
+        /// Writes to specified file instead of stdout
+        #[arg(short, long, value_hint = ValueHint::FilePath)]
+        output: Option<PathBuf>,
+
+        /// Exports the active terrain rather than current directory
+        #[arg(long)]
+        active: bool,
+    },
+
     /// Generates required shell scripts for terrainium to work
     ///
     /// MUST be executed if terrain.toml is updated by something other
@@ -185,10 +367,32 @@ pub enum Verbs {
     },
 
     /// Validates the terrain in current directory
+    ///
+    /// Other commands never modify terrain.toml, fixable values are only
+    /// fixed in memory. Use `--fix` to write the fixes to terrain.toml.
     Validate {
         /// Validates the active terrain rather than current directory
         #[arg(long)]
         active: bool,
+
+        /// Writes fixes for fixable values to terrain.toml and prints the diff
+        #[arg(long)]
+        fix: bool,
+
+        /// Only prints the diff of fixes without updating terrain.toml
+        #[arg(long, requires = "fix")]
+        dry_run: bool,
+
+        /// Prints validation results in specified format instead of log messages
+        ///
+        /// Results include level, message, biome, target, fix action and
+        /// location of the value in terrain.toml.
+        #[arg(long, value_enum, conflicts_with = "fix")]
+        format: Option<ValidationFormat>,
+
+        /// Minimum level of validation results that fails the command
+        #[arg(long, value_enum, value_name = "LEVEL", default_value = "error")]
+        fail_on: ValidationMessageLevel,
     },
 
     /// Fetch the values of the environment for current directory
@@ -199,7 +403,13 @@ pub enum Verbs {
         /// If it is not specified default biome will be used.
         ///
         /// If "none" is used, main terrain will be used without applying any Biome.
-        #[arg(short, long, default_value = DEFAULT_SELECTED, hide_default_value = true)]
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
         biome: BiomeArg,
 
         /// Fetches specified list of environment variables
@@ -208,7 +418,7 @@ pub enum Verbs {
         /// Single instance of `-e` can be supplied with single environment variable to fetch.
         /// .i.e. if multiple values are needed use `-e ENV_VAR1 -e ENV_VAR2`.
         /// If value does not exist "!!!DOES_NOT_EXIST!!!" is returned.
-        #[arg(short, conflicts_with = "json")]
+        #[arg(short, conflicts_with = "json", add = ArgValueCandidates::new(completions::envs))]
         env: Vec<String>,
 
         /// Fetches specified list of aliases
@@ -217,7 +427,7 @@ pub enum Verbs {
         /// Single instance of `-a` can be supplied with single alias to fetch.
         /// .i.e. if multiple values are needed use `-a alias1 -a alias2`.
         /// If value does not exist "!!!DOES_NOT_EXIST!!!" is returned.
-        #[arg(short, conflicts_with = "json")]
+        #[arg(short, conflicts_with = "json", add = ArgValueCandidates::new(completions::aliases))]
         alias: Vec<String>,
 
         /// Fetches all the constructors
@@ -248,6 +458,21 @@ pub enum Verbs {
         #[arg(short, long)]
         json: bool,
 
+        /// Resolves and prints the values of environment variables
+        /// that reference secret providers (`file:`, `cmd:`, `env-file:`)
+        ///
+        /// If not passed, the references are printed as is.
+        #[arg(long, conflicts_with = "auto_apply")]
+        reveal: bool,
+
+        /// Shows the layer (`terrain` or `biomes.<biome_name>`) from which each
+        /// environment variable, alias and command is taken
+        ///
+        /// Layers are merged in order: main terrain, biomes specified in `extends`
+        /// and selected biome.
+        #[arg(long, conflicts_with_all = ["json", "auto_apply"])]
+        explain: bool,
+
         /// Fetches the values for currently active terrain
         #[arg(long)]
         active: bool,
@@ -257,6 +482,44 @@ pub enum Verbs {
         debug: bool,
     },
 
+    /// Shows where the values of the environment come from
+    ///
+    /// Prints the final value, each layer the value passed through in the order
+    /// they are merged and the environment variable references (`${...}`)
+    /// substituted in the value.
+    /// If no arguments are provided explains all the values.
+    Explain {
+        /// Biome to use for environment.
+        /// If it is not specified default biome will be used.
+        ///
+        /// If "none" is used, main terrain will be used without applying any Biome.
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
+        biome: BiomeArg,
+
+        /// Explains specified list of environment variables
+        ///
+        /// If multiple values are needed use `-e ENV_VAR1 -e ENV_VAR2`.
+        #[arg(short, add = ArgValueCandidates::new(completions::envs))]
+        env: Vec<String>,
+
+        /// Explains specified list of aliases
+        ///
+        /// If multiple values are needed use `-a alias1 -a alias2`.
+        #[arg(short, add = ArgValueCandidates::new(completions::aliases))]
+        alias: Vec<String>,
+
+        /// Explains the values for currently active terrain, including the
+        /// environment variables added on activation
+        #[arg(long)]
+        active: bool,
+    },
+
     /// Activates the terrainium shell and runs constructors in new shell
     ///
     /// Creates a new shell session with environment variables, aliases from the
@@ -265,7 +528,13 @@ pub enum Verbs {
     /// Also triggers background constructors in daemon.
     Enter {
         /// Biome for which the terrain should be activated.
-        #[arg(short, long, default_value = DEFAULT_SELECTED, hide_default_value = true)]
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
         biome: BiomeArg,
 
         /// flag to indicate whether terrain has activated by auto_apply mechanism
@@ -278,7 +547,13 @@ pub enum Verbs {
     /// If terrain is not active, this command will fail
     Construct {
         /// Biome for which the constructors should be run.
-        #[arg(short, long, default_value = DEFAULT_SELECTED, hide_default_value = true)]
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
         biome: BiomeArg,
     },
 
@@ -287,7 +562,28 @@ pub enum Verbs {
     /// If terrain is not active, this command will fail
     Destruct {
         /// Biome for which the destructors should be run.
-        #[arg(short, long, default_value = DEFAULT_SELECTED, hide_default_value = true)]
+        #[arg(
+            short,
+            long,
+            default_value = DEFAULT_SELECTED,
+            hide_default_value = true,
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
+        biome: BiomeArg,
+    },
+
+    /// Switches the biome of the active terrain without exiting the shell
+    ///
+    /// Runs destructors of the current biome and removes its environment variables
+    /// and aliases, then applies the specified biome and runs its constructors.
+    /// Background commands are run in daemon.
+    ///
+    /// If terrain is not active, this command will fail
+    Switch {
+        /// Biome to switch to.
+        ///
+        /// If "none" is used, main terrain will be used without applying any Biome.
+        #[arg(add = ArgValueCandidates::new(completions::biomes))]
         biome: BiomeArg,
     },
 
@@ -299,6 +595,43 @@ pub enum Verbs {
     /// If terrain is not active, this command will fail
     Exit,
 
+    /// Shows the differences between environments of two biomes, a session or
+    /// the current shell
+    ///
+    /// Compares environment variables, aliases, constructors and destructors of
+    /// the biomes. Only environment variables are compared for session and shell.
+    Diff {
+        /// Biomes to compare, changes are shown from first biome to second biome
+        ///
+        /// If "none" is used, main terrain will be used without applying any Biome.
+        #[arg(
+            num_args = 2,
+            value_names = ["FROM", "TO"],
+            required_unless_present_any = ["session", "shell"],
+            conflicts_with_all = ["session", "shell"],
+            add = ArgValueCandidates::new(completions::biomes)
+        )]
+        biomes: Vec<BiomeArg>,
+
+        /// Compares environment variables recorded for the session with the values
+        /// set by current terrain.toml
+        #[arg(
+            long,
+            value_name = "SESSION_ID",
+            conflicts_with = "shell",
+            add = ArgValueCandidates::new(completions::session_ids)
+        )]
+        session: Option<String>,
+
+        /// Compares environment variables of current shell with the active biome
+        #[arg(long)]
+        shell: bool,
+
+        /// Prints the differences in json format
+        #[arg(short, long)]
+        json: bool,
+    },
+
     /// Fetches status of background constructors and destructors from terrainium
     /// daemon
     ///
@@ -319,7 +652,7 @@ pub enum Verbs {
         ///
         /// If not specified read from TERRAIN_SESSION_ID environment variable,
         /// which is set when terrain activates.
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(completions::session_ids))]
         session_id: Option<String>,
 
         /// Return last updated nth session
@@ -333,11 +666,175 @@ pub enum Verbs {
         json: bool,
     },
 
+    /// Streams events of sessions and background commands from terrainium daemon
+    ///
+    /// Prints an event whenever a session starts or ends and whenever status
+    /// of a background command changes, until interrupted.
+    Events {
+        /// Only print events of this terrain
+        #[arg(short, long)]
+        terrain_name: Option<String>,
+
+        /// Only print events of this session
+        #[arg(short, long, add = ArgValueCandidates::new(completions::session_ids))]
+        session_id: Option<String>,
+
+        /// Print each event as a json line
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Prints prompt segment of the active terrain
+    ///
+    /// Reads `TERRAIN_*` environment variables of the active session and
+    /// queries daemon for status of background constructors with a short
+    /// timeout, so it can be run on every prompt. Prints nothing if terrain
+    /// is not active.
+    Prompt {
+        /// Template of the prompt segment
+        ///
+        /// Placeholders `{name}`, `{biome}`, `{session_id}`, `{running}`,
+        /// `{failed}` and `{health}` are replaced. `{health}` is ` ●N` for
+        /// running and ` ✗N` for failed background constructors, omitted if zero.
+        #[arg(short, long, default_value = "{name}({biome}){health}")]
+        format: String,
+
+        /// Print prompt segments in json format
+        #[arg(short, long)]
+        json: bool,
+
+        /// Do not query daemon, only values of environment variables are printed
+        #[arg(long)]
+        no_daemon: bool,
+
+        /// Milliseconds to wait for daemon before printing prompt without health
+        #[arg(long, value_name = "MS", default_value_t = 50)]
+        timeout: u64,
+    },
+
+    /// Lists the terrains known on this machine
+    ///
+    /// Terrains are registered when they are initialized, entered or their
+    /// scripts are generated.
+    List {
+        /// Lists only the terrains whose directory or terrain.toml no longer exists
+        #[arg(long)]
+        stale: bool,
+
+        /// Removes central directories (registry entry, scripts and central
+        /// terrain.toml) of the stale terrains
+        #[arg(long, conflicts_with = "json")]
+        prune: bool,
+
+        /// Lists terrains in json format
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Prints auto-apply value for terrain of current directory
+    ///
+    /// Unlike `get --auto-apply`, terrain is not validated and probed values are
+    /// cached until terrain.toml is modified, so it is fast enough to be run on
+    /// every directory change by shell integration.
+    Probe {
+        /// Print default biome instead of auto-apply value
+        ///
+        /// Prints empty string if default biome is not set.
+        #[arg(long)]
+        default_biome: bool,
+    },
+
+    /// Checks shell integration, scripts of terrain in current directory and terrainiumd
+    ///
+    /// Reports if shell integration is sourced in rc file and up to date, if
+    /// compiled scripts are older than terrain.toml, if terrainiumd is installed,
+    /// running and responding on its socket, if versions of terrain and
+    /// terrainiumd match and if state directory of terrainiumd is writable.
+    Doctor {
+        /// Print results of the checks in json format
+        #[arg(short, long)]
+        json: bool,
+
+        /// Update rc file, shell integration script and regenerate scripts
+        /// if their checks fail
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Prints shell completion script for `terrain` and `terrainiumd`
+    ///
+    /// Biome names, environment variable and alias names of terrain in current
+    /// directory and recent session ids are completed when the completion is requested.
+    ///
+    /// Add `source <(terrain completions zsh)` to shell rc file to enable completions.
+    Completions {
+        /// Shell to print completion script for
+        #[arg(value_enum)]
+        shell: CompletionShell,
+    },
+
     /// Generate schema.json for terrain.toml, terrainium.toml, terrainiumd.toml.
     #[cfg(feature = "terrain-schema")]
     Schema,
 }
 
+/// Shells supported by `terrain completions`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CompletionShell {
+    Zsh,
+    Bash,
+    Fish,
+}
+
+/// Format of `terrain validate` output
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum ValidationFormat {
+    /// JSON array of validation results
+    Json,
+    /// SARIF 2.1.0 log, supported by editors and code scanning tools
+    Sarif,
+}
+
+/// Format of the file imported by `terrain import`
+#[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum ImportFormat {
+    /// `.envrc` of direnv
+    Direnv,
+    /// `.env` file
+    Dotenv,
+    /// `environment` of services in docker compose file
+    Compose,
+    /// `devcontainer.json` of dev containers
+    Devcontainer,
+}
+
+/// Format of the file rendered by `terrain export`
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ExportFormat {
+    /// `.env` file
+    Dotenv,
+    /// `.envrc` of direnv
+    Direnv,
+    /// env file for `docker run --env-file` and `env_file` of compose
+    DockerEnvFile,
+    /// `EnvironmentFile` of systemd units
+    SystemdEnv,
+    /// `launch.json` of VS Code
+    VscodeLaunch,
+    /// justfile with aliases and constructors as recipes
+    Justfile,
+}
+
+impl ExportFormat {
+    /// formats that can reference other environment variables in values
+    pub fn supports_references(&self) -> bool {
+        matches!(
+            self,
+            ExportFormat::Dotenv | ExportFormat::Direnv | ExportFormat::Justfile
+        )
+    }
+}
+
 /// Biome to select
 #[derive(Debug, Clone)]
 pub enum BiomeArg {
@@ -381,6 +878,8 @@ pub struct GetArgs {
     pub constructors: bool,
     pub destructors: bool,
     pub auto_apply: bool,
+    pub reveal: bool,
+    pub explain: bool,
 }
 
 impl GetArgs {
@@ -400,6 +899,15 @@ pub struct UpdateArgs {
     pub biome: BiomeArg,
     pub alias: Vec<Pair>,
     pub env: Vec<Pair>,
+    pub unset_env: Vec<String>,
+    pub unset_alias: Vec<String>,
+    pub delete_biome: Option<String>,
+    pub rename_biome: Option<(String, String)>,
+    pub add_constructor: Option<Command>,
+    pub add_destructor: Option<Command>,
+    pub remove_constructor: Option<usize>,
+    pub remove_destructor: Option<usize>,
+    pub background: bool,
     pub new: Option<String>,
     pub backup: bool,
     pub auto_apply: Option<AutoApply>,
@@ -415,18 +923,13 @@ impl FromStr for Pair {
     type Err = anyhow::Error;
 
     fn from_str(pair: &str) -> Result<Self, Self::Err> {
-        let pair: Vec<&str> = pair.split("=").collect();
-
-        if pair.len() != 2 {
+        // only first `=` separates key and value, so value can contain `=`
+        let Some((key, value)) = pair.split_once('=') else {
             bail!("pair of key values should be passed in format <KEY>=<VALUE>.");
-        }
+        };
 
-        let key = pair.first().unwrap().to_string();
-        let value = pair
-            .last()
-            .unwrap()
-            .trim_matches(|c| c == '\'' || c == '"')
-            .to_string();
+        let key = key.to_string();
+        let value = value.trim_matches(|c| c == '\'' || c == '"').to_string();
 
         let mut identifier = BTreeMap::new();
         identifier.insert(key.clone(), value.clone());
@@ -444,12 +947,13 @@ impl FromStr for Pair {
 
 #[cfg(test)]
 mod tests {
+    use std::path::PathBuf;
     use std::str::FromStr;
 
-    use clap::ValueEnum;
+    use clap::{Parser, ValueEnum};
     use pretty_assertions::assert_eq;
 
-    use crate::client::args::Pair;
+    use crate::client::args::{ClientArgs, Pair, Verbs};
     use crate::client::types::terrain::AutoApply;
     use crate::common::constants::NONE;
 
@@ -501,13 +1005,14 @@ mod tests {
     }
 
     #[test]
-    fn pair_from_str_throws_error_more_than_one_equals() {
-        let err = Pair::from_str("KEY=VALUE=SOMETHING_ELSE")
-            .expect_err("error to be thrown")
-            .to_string();
+    fn pair_from_str_splits_on_first_equals() {
+        let pair = Pair::from_str("JAVA_OPTS=\"-Dx=y -Da=b\"").expect("no error to be thrown");
         assert_eq!(
-            "pair of key values should be passed in format <KEY>=<VALUE>.",
-            err
+            Pair {
+                key: "JAVA_OPTS".to_string(),
+                value: "-Dx=y -Da=b".to_string()
+            },
+            pair
         );
     }
 
@@ -557,4 +1062,42 @@ mod tests {
             "invalid variant: none"
         );
     }
+
+    #[test]
+    fn update_add_constructor_with_args() {
+        let args = ClientArgs::try_parse_from([
+            "terrain",
+            "update",
+            "--add-constructor",
+            "/bin/echo",
+            "-n",
+            "added",
+            ";",
+            "--cwd",
+            "/tmp",
+            "--background",
+        ])
+        .expect("to be parsed");
+
+        let Some(Verbs::Update {
+            add_constructor,
+            cwd,
+            background,
+            ..
+        }) = args.command
+        else {
+            panic!("expected update verb");
+        };
+
+        assert_eq!(
+            add_constructor,
+            Some(vec![
+                "/bin/echo".to_string(),
+                "-n".to_string(),
+                "added".to_string()
+            ])
+        );
+        assert_eq!(cwd, Some(PathBuf::from("/tmp")));
+        assert!(background);
+    }
 }
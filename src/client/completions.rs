@@ -0,0 +1,202 @@
+use std::collections::BTreeSet;
+use std::fs::read_to_string;
+use std::path::Path;
+
+use clap_complete::engine::CompletionCandidate;
+
+use crate::client::types::biome::Biome;
+use crate::client::types::context::get_terrain_dir;
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::{NONE, TERRAIN_NAME};
+use crate::common::types::paths::get_terrainiumd_paths;
+use crate::daemon::types::history::History;
+
+/// values of the terrain in current directory completed by shell integration,
+/// called by the shell on every completion so failures result in no candidates
+fn current_terrain() -> Option<Terrain> {
+    let home_dir = home::home_dir()?;
+    let current_dir = std::env::current_dir().ok()?;
+    terrain(&home_dir, &current_dir)
+}
+
+/// terrain with included files and parent terrain, without validation
+fn terrain(home_dir: &Path, current_dir: &Path) -> Option<Terrain> {
+    let (terrain_dir, toml_path) = get_terrain_dir(home_dir, current_dir)?;
+    let toml = read_to_string(&toml_path).ok()?;
+    Terrain::from_toml(toml)
+        .and_then(|terrain| terrain.with_includes(&toml_path, &terrain_dir))
+        .and_then(|terrain| terrain.with_parent(&terrain_dir))
+        .ok()
+}
+
+/// biome names for `--biome` with `none` to select main terrain
+pub fn biomes() -> Vec<CompletionCandidate> {
+    current_terrain()
+        .map(|terrain| biome_candidates(&terrain))
+        .unwrap_or_default()
+}
+
+fn biome_candidates(terrain: &Terrain) -> Vec<CompletionCandidate> {
+    let mut candidates: Vec<CompletionCandidate> = terrain
+        .selectable_biomes()
+        .into_iter()
+        .map(|name| {
+            let help = if terrain.default_biome().as_ref() == Some(&name) {
+                "default biome"
+            } else if terrain.biomes().contains_key(&name) {
+                "biome"
+            } else {
+                "parent terrain biome"
+            };
+            CompletionCandidate::new(name).help(Some(help.into()))
+        })
+        .collect();
+    candidates.push(CompletionCandidate::new(NONE).help(Some("main terrain".into())));
+    candidates
+}
+
+/// environment variable names defined by main terrain or any of the biomes
+pub fn envs() -> Vec<CompletionCandidate> {
+    current_terrain()
+        .map(|terrain| names(&terrain, |biome| biome.envs().keys()))
+        .unwrap_or_default()
+}
+
+/// alias names defined by main terrain or any of the biomes
+pub fn aliases() -> Vec<CompletionCandidate> {
+    current_terrain()
+        .map(|terrain| names(&terrain, |biome| biome.aliases().keys()))
+        .unwrap_or_default()
+}
+
+fn names<'a, I: Iterator<Item = &'a String>>(
+    terrain: &'a Terrain,
+    keys: impl Fn(&'a Biome) -> I,
+) -> Vec<CompletionCandidate> {
+    let names: BTreeSet<&String> = std::iter::once(terrain.terrain())
+        .chain(terrain.biomes().values())
+        .flat_map(keys)
+        .collect();
+    names.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// recent session ids from the history of daemon, most recent first
+///
+/// terrain is selected from `TERRAIN_NAME` in active terrain, otherwise terrain
+/// of current directory is used.
+pub fn session_ids() -> Vec<CompletionCandidate> {
+    let terrain_name = match std::env::var(TERRAIN_NAME) {
+        Ok(name) => name,
+        Err(_) => match current_terrain() {
+            Some(terrain) => terrain.name().clone(),
+            None => return vec![],
+        },
+    };
+    session_candidates(get_terrainiumd_paths().dir_str(), &terrain_name)
+}
+
+fn session_candidates(state_dir: &str, terrain_name: &str) -> Vec<CompletionCandidate> {
+    let Ok(history) = read_to_string(History::get_path(state_dir, terrain_name)) else {
+        return vec![];
+    };
+    history
+        .lines()
+        .map(str::trim)
+        .filter(|session_id| !session_id.is_empty())
+        .enumerate()
+        .map(|(recent, session_id)| {
+            CompletionCandidate::new(session_id)
+                .help(Some(format!("recent {recent}").into()))
+                .display_order(Some(recent))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use anyhow::Result;
+    use clap_complete::engine::CompletionCandidate;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::common::constants::TERRAIN_TOML;
+
+    fn values(candidates: Vec<CompletionCandidate>) -> Vec<String> {
+        candidates
+            .iter()
+            .map(|candidate| candidate.get_value().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completes_values_of_terrain_in_current_dir() -> Result<()> {
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        write(
+            terrain_dir.path().join(TERRAIN_TOML),
+            r#"
+name = "completions"
+default_biome = "dev"
+auto_apply = "off"
+
+[terrain]
+envs = { EDITOR = "vim" }
+aliases = { build = "cargo build" }
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.dev]
+envs = { RUST_LOG = "debug" }
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.release]
+envs = {}
+aliases = { build = "cargo build --release" }
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#,
+        )?;
+        let nested = terrain_dir.path().join("src");
+        create_dir_all(&nested)?;
+
+        let terrain = super::terrain(home_dir.path(), &nested).expect("terrain to be found");
+
+        assert_eq!(
+            values(super::biome_candidates(&terrain)),
+            vec!["dev", "release", "none"]
+        );
+        assert_eq!(
+            values(super::names(&terrain, |biome| biome.envs().keys())),
+            vec!["EDITOR", "RUST_LOG"]
+        );
+        assert_eq!(
+            values(super::names(&terrain, |biome| biome.aliases().keys())),
+            vec!["build"]
+        );
+
+        assert!(super::terrain(home_dir.path(), home_dir.path()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn completes_sessions_from_history() -> Result<()> {
+        let state_dir = tempdir()?;
+        create_dir_all(state_dir.path().join("terrainium"))?;
+        write(
+            state_dir.path().join("terrainium").join("history"),
+            "session-2\nsession-1\n\n",
+        )?;
+
+        let state_dir = state_dir.path().to_str().unwrap();
+        assert_eq!(
+            values(super::session_candidates(state_dir, "terrainium")),
+            vec!["session-2", "session-1"]
+        );
+        assert!(super::session_candidates(state_dir, "missing").is_empty());
+        Ok(())
+    }
+}
@@ -1,12 +1,24 @@
 mod background;
+pub mod completions;
 pub mod construct;
 pub mod destruct;
+pub mod diff;
+pub mod doctor;
 pub mod edit;
 pub mod enter;
+pub mod events;
 pub mod exit;
+pub mod explain;
+pub mod export;
 pub mod generate;
 pub mod get;
+pub mod import;
 pub mod init;
+pub mod list;
+pub mod probe;
+pub mod prompt;
 pub mod schema;
 pub mod status;
+pub mod switch;
 pub mod update;
+pub mod validate;
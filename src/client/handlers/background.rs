@@ -1,15 +1,26 @@
-use anyhow::{Context as AnyhowContext, Result};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as AnyhowContext, Result, anyhow};
+use home::home_dir;
+use tracing::{debug, info, warn};
 
 use crate::client::args::BiomeArg;
+use crate::client::handlers::status::daemon_status;
 #[mockall_double::double]
 use crate::client::types::client::Client;
 use crate::client::types::context::Context;
 use crate::client::types::environment::Environment;
+use crate::client::types::offline::OfflineQueue;
 use crate::client::types::proto::ProtoRequest;
 use crate::client::types::terrain::Terrain;
 use crate::common::constants::{CONSTRUCTORS, DESTRUCTORS};
 use crate::common::types::paths::get_terrainiumd_paths;
 use crate::common::types::pb;
+use crate::daemon::service::ServiceProvider;
+
+const PING_TIMEOUT: Duration = Duration::from_secs(1);
+const AUTO_START_TIMEOUT: Duration = Duration::from_secs(5);
+const AUTO_START_INTERVAL: Duration = Duration::from_millis(100);
 
 pub async fn handle(
     context: Context,
@@ -51,9 +62,11 @@ pub async fn handle(
     Ok(())
 }
 
+/// request to run background commands of the environment, secrets are resolved only
+/// if there are background commands to run
 pub(crate) fn execute_request(
     context: &Context,
-    environment: Environment,
+    mut environment: Environment,
     is_constructor: bool,
     timestamp: String,
 ) -> Result<Option<pb::Execute>> {
@@ -71,6 +84,8 @@ pub(crate) fn execute_request(
         return Ok(None);
     }
 
+    environment.resolve_secrets(context.executor(), context.terrain_dir())?;
+
     Ok(Some(pb::Execute {
         session_id: context.session_id(),
         terrain_name: environment.name().to_string(),
@@ -79,7 +94,162 @@ pub(crate) fn execute_request(
         toml_path: context.toml_path().to_string_lossy().to_string(),
         is_constructor,
         timestamp,
-        envs: environment.envs(),
+        envs: environment.exports(),
         commands,
+        // values resolved by secret providers are passed to commands but not stored by daemon
+        secrets: environment.resolved_secrets().clone(),
     }))
 }
+
+/// sends session request to daemon, replaying requests recorded while it was
+/// unreachable first
+///
+/// if daemon is unreachable it is started through its service when
+/// `auto_start_daemon` is set, otherwise request is recorded without background
+/// commands and the shell keeps working offline
+pub(crate) async fn send_or_record(
+    context: &Context,
+    request: ProtoRequest,
+    client: Option<Client>,
+) -> Result<()> {
+    if let Some(mut client) = client {
+        client.request(request).await?;
+        return Ok(());
+    }
+
+    let home_dir = home_dir().context("failed to get home directory")?;
+    let queue = OfflineQueue::new(&home_dir);
+
+    if let Err(err) = reachable(context).await {
+        queue.record(request)?;
+        warn!(
+            "terrainiumd is not reachable ({err:#}), continuing offline without background commands. session will be sent to the daemon once it is reachable."
+        );
+        return Ok(());
+    }
+
+    let socket = get_terrainiumd_paths().socket();
+    let replayed = queue
+        .replay(|| Client::new(socket.clone()))
+        .await
+        .context("failed to replay offline requests")?;
+    if replayed > 0 {
+        info!("sent {replayed} request(s) recorded while terrainiumd was unreachable");
+    }
+
+    Client::new(socket).await?.request(request).await?;
+    Ok(())
+}
+
+/// pings daemon, starting it first if it is not reachable and
+/// `auto_start_daemon` is set
+async fn reachable(context: &Context) -> Result<()> {
+    let err = match ping().await {
+        Ok(()) => return Ok(()),
+        Err(err) => err,
+    };
+    if !context.config().auto_start_daemon() {
+        return Err(err);
+    }
+
+    info!("terrainiumd is not reachable ({err:#}), starting it");
+    ServiceProvider::get(context.executor().clone())
+        .and_then(|service| service.start())
+        .context("failed to start terrainiumd")?;
+
+    let started = Instant::now();
+    loop {
+        match ping().await {
+            Ok(()) => return Ok(()),
+            Err(err) if started.elapsed() >= AUTO_START_TIMEOUT => {
+                return Err(err.context(format!(
+                    "terrainiumd did not start in {AUTO_START_TIMEOUT:?}"
+                )));
+            }
+            Err(_) => tokio::time::sleep(AUTO_START_INTERVAL).await,
+        }
+    }
+}
+
+/// daemon is unreachable only if it cannot be connected to or does not respond in time
+async fn ping() -> Result<()> {
+    let client = tokio::time::timeout(PING_TIMEOUT, Client::new(get_terrainiumd_paths().socket()))
+        .await
+        .map_err(|_| anyhow!("did not accept connection in {PING_TIMEOUT:?}"))??;
+    responds(client).await
+}
+
+/// error response means daemon is running, e.g. daemon that does not support
+/// `Ping` yet, so request is still sent to it
+async fn responds(client: Client) -> Result<()> {
+    match tokio::time::timeout(PING_TIMEOUT, daemon_status(Some(client))).await {
+        Err(_) => Err(anyhow!("did not respond in {PING_TIMEOUT:?}")),
+        Ok(Err(err)) => {
+            debug!("terrainiumd responded to ping with error ({err:#})");
+            Ok(())
+        }
+        Ok(Ok(_)) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs::{Permissions, set_permissions, write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::args::BiomeArg;
+    use crate::client::test_utils::assertions::client::ExpectClient;
+    use crate::client::types::context::Context;
+    use crate::client::types::environment::Environment;
+    use crate::client::types::proto::ProtoRequest;
+    use crate::client::types::terrain::Terrain;
+    use crate::common::constants::TEST_TIMESTAMP;
+    use crate::common::execute::MockExecutor;
+    use crate::common::types::pb;
+
+    #[test]
+    fn execute_request_sends_secrets_separately() {
+        let terrain_dir = tempdir().unwrap();
+        let secret_file = terrain_dir.path().join("token");
+        write(&secret_file, "secret-value\n").unwrap();
+        set_permissions(&secret_file, Permissions::from_mode(0o600)).unwrap();
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::default(),
+        );
+
+        let mut terrain = Terrain::example();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("TOKEN", "file:token")]);
+        let environment =
+            Environment::from(&terrain, BiomeArg::Default, terrain_dir.path()).unwrap();
+
+        let request =
+            super::execute_request(&context, environment, true, TEST_TIMESTAMP.to_string())
+                .unwrap()
+                .expect("request to be created");
+
+        assert!(request.envs.contains_key("EDITOR"));
+        assert!(!request.envs.contains_key("TOKEN"));
+        assert_eq!(
+            request.secrets,
+            BTreeMap::from([("TOKEN".to_string(), "secret-value".to_string())])
+        );
+    }
+
+    #[tokio::test]
+    async fn daemon_with_error_response_to_ping_is_reachable() {
+        let client = ExpectClient::send(ProtoRequest::Ping(pb::Ping {}))
+            .with_returning_error("error response from daemon: invalid request type");
+
+        super::responds(client).await.unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use std::io::{Write, stdout};
+
+use anyhow::{Context, Result};
+use clap_complete::env::{Bash, EnvCompleter, Fish, Zsh};
+
+use crate::client::args::CompletionShell;
+use crate::common::constants::{TERRAIN, TERRAIN_COMPLETE, TERRAINIUMD};
+
+pub fn handle(shell: CompletionShell) -> Result<()> {
+    let script = registration(shell)?;
+    stdout()
+        .write_all(script.as_bytes())
+        .context("failed to print completion script")
+}
+
+/// script that registers `terrain` and `terrainiumd` to be called with `TERRAIN_COMPLETE`
+/// environment variable set, which prints the candidates instead of running the command
+fn registration(shell: CompletionShell) -> Result<String> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Zsh => &Zsh,
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Fish => &Fish,
+    };
+
+    let mut script = vec![];
+    for bin in [TERRAIN, TERRAINIUMD] {
+        completer
+            .write_registration(TERRAIN_COMPLETE, bin, bin, bin, &mut script)
+            .context(format!("failed to generate completion script for {bin}"))?;
+    }
+    String::from_utf8(script).context("completion script is not valid utf-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use clap::CommandFactory;
+
+    use crate::client::args::{ClientArgs, CompletionShell};
+
+    #[test]
+    fn registers_terrain_and_terrainiumd() -> Result<()> {
+        let zsh = super::registration(CompletionShell::Zsh)?;
+        assert!(zsh.contains("compdef _clap_dynamic_completer_terrain terrain\n"));
+        assert!(zsh.contains("compdef _clap_dynamic_completer_terrainiumd terrainiumd\n"));
+        assert!(zsh.contains("TERRAIN_COMPLETE=\"zsh\""));
+
+        let fish = super::registration(CompletionShell::Fish)?;
+        assert!(fish.contains("complete --keep-order --exclusive --command terrain "));
+        assert!(fish.contains("complete --keep-order --exclusive --command terrainiumd "));
+
+        let bash = super::registration(CompletionShell::Bash)?;
+        assert!(bash.contains("-F _clap_complete_terrain terrain\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn arguments_with_completers_are_valid() {
+        ClientArgs::command().debug_assert();
+    }
+}
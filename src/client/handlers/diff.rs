@@ -0,0 +1,553 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+use clap::builder::styling::AnsiColor;
+use serde::Serialize;
+
+use crate::client::args::BiomeArg;
+use crate::client::handlers::status::terrain_state;
+#[mockall_double::double]
+use crate::client::types::client::Client;
+use crate::client::types::commands::Commands;
+use crate::client::types::context::Context;
+use crate::client::types::environment::Environment;
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::{
+    FPATH, TERRAIN_AUTO_APPLY, TERRAIN_INIT_FN, TERRAIN_INIT_SCRIPT, TERRAIN_SELECTED_BIOME,
+};
+use crate::common::types::command::Command;
+use crate::common::types::styles::colored;
+
+/// environment variables set by shell integration instead of terrain.toml
+const SHELL_ENVS: [&str; 3] = [FPATH, TERRAIN_INIT_FN, TERRAIN_INIT_SCRIPT];
+
+/// environments to compare
+pub enum DiffTarget {
+    /// merged environments of the biomes
+    Biomes(BiomeArg, BiomeArg),
+    /// environment variables recorded for the session against current terrain.toml
+    Session(String),
+    /// environment variables of current shell against active biome
+    Shell,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub(crate) enum Change {
+    Added { value: String },
+    Removed { value: String },
+    Changed { from: String, to: String },
+}
+
+impl Change {
+    fn line(&self, key: Option<&str>) -> String {
+        let key = |value: &str| match key {
+            Some(key) => format!("{key}=\"{value}\""),
+            None => value.to_string(),
+        };
+        match self {
+            Change::Added { value } => colored(&format!("+ {}", key(value)), AnsiColor::Green),
+            Change::Removed { value } => colored(&format!("- {}", key(value)), AnsiColor::Red),
+            Change::Changed { from, to } => {
+                colored(&format!("~ {} -> \"{to}\"", key(from)), AnsiColor::Yellow)
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub(crate) struct CommandsDiff {
+    foreground: Vec<Change>,
+    background: Vec<Change>,
+}
+
+impl CommandsDiff {
+    fn new(from: &Commands, to: &Commands) -> Self {
+        Self {
+            foreground: command_changes(from.foreground(), to.foreground()),
+            background: command_changes(from.background(), to.background()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.foreground.is_empty() && self.background.is_empty()
+    }
+}
+
+/// changes required to go from environment `from` to environment `to`
+#[derive(Serialize, Debug, Default, PartialEq)]
+pub(crate) struct EnvironmentDiff {
+    from: String,
+    to: String,
+    envs: BTreeMap<String, Change>,
+    aliases: BTreeMap<String, Change>,
+    constructors: CommandsDiff,
+    destructors: CommandsDiff,
+}
+
+impl EnvironmentDiff {
+    fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+            && self.aliases.is_empty()
+            && self.constructors.is_empty()
+            && self.destructors.is_empty()
+    }
+}
+
+impl Display for EnvironmentDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no differences between {} and {}", self.from, self.to);
+        }
+        writeln!(f, "Changes from {} to {}:", self.from, self.to)?;
+
+        let pairs = |header: &str, changes: &BTreeMap<String, Change>| -> String {
+            if changes.is_empty() {
+                return String::new();
+            }
+            let body: String = changes
+                .iter()
+                .map(|(key, change)| format!("{: <4}{}\n", "", change.line(Some(key))))
+                .collect();
+            format!("{header}\n{body}")
+        };
+        let commands = |header: &str, diff: &CommandsDiff| -> String {
+            if diff.is_empty() {
+                return String::new();
+            }
+            let cmds = |commands_type: &str, changes: &Vec<Change>| -> String {
+                if changes.is_empty() {
+                    return String::new();
+                }
+                let body: String = changes
+                    .iter()
+                    .map(|change| format!("{: <8}{}\n", "", change.line(None)))
+                    .collect();
+                format!("{: <4}{commands_type}:\n{body}", "")
+            };
+            format!(
+                "{header}\n{}{}",
+                cmds("foreground", &diff.foreground),
+                cmds("background", &diff.background)
+            )
+        };
+
+        write!(
+            f,
+            "{}{}{}{}",
+            pairs("Environment Variables:", &self.envs),
+            pairs("Aliases:", &self.aliases),
+            commands("Constructors:", &self.constructors),
+            commands("Destructors:", &self.destructors),
+        )
+    }
+}
+
+pub async fn handle(
+    context: Context,
+    terrain: Terrain,
+    target: DiffTarget,
+    json: bool,
+    client: Option<Client>,
+) -> Result<()> {
+    let diff = match target {
+        DiffTarget::Biomes(from, to) => biomes(&context, &terrain, from, to)?,
+        DiffTarget::Session(session_id) => session(&context, &terrain, session_id, client).await?,
+        DiffTarget::Shell => shell(&context, &terrain, std::env::vars().collect())?,
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diff).context("failed to convert diff to json")?
+        );
+    } else {
+        print!("{diff}");
+    }
+    Ok(())
+}
+
+fn biomes(
+    context: &Context,
+    terrain: &Terrain,
+    from: BiomeArg,
+    to: BiomeArg,
+) -> Result<EnvironmentDiff> {
+    let from = Environment::from(terrain, from, context.terrain_dir())
+        .context("failed to generate environment")?;
+    let to = Environment::from(terrain, to, context.terrain_dir())
+        .context("failed to generate environment")?;
+
+    Ok(EnvironmentDiff {
+        from: from.selected_biome().clone(),
+        to: to.selected_biome().clone(),
+        envs: changes(from.envs_ref(), to.envs_ref()),
+        aliases: changes(from.aliases_ref(), to.aliases_ref()),
+        constructors: CommandsDiff::new(from.constructors_ref(), to.constructors_ref()),
+        destructors: CommandsDiff::new(from.destructors_ref(), to.destructors_ref()),
+    })
+}
+
+/// compares environment variables recorded by daemon for the session with environment
+/// variables current terrain.toml would set for the same session
+///
+/// environment variables set by shell integration and resolved by secret providers
+/// are not compared.
+async fn session(
+    context: &Context,
+    terrain: &Terrain,
+    session_id: String,
+    client: Option<Client>,
+) -> Result<EnvironmentDiff> {
+    let state = terrain_state(terrain.name().clone(), Some(session_id), None, client)
+        .await
+        .context("failed to get the session state")?;
+    let recorded = state.envs();
+
+    let mut environment = Environment::from(
+        terrain,
+        BiomeArg::from_str(state.biome_name())?,
+        context.terrain_dir(),
+    )
+    .context("failed to generate environment")?;
+    environment.add_activation_envs(
+        state.session_id().to_string(),
+        context.terrain_dir(),
+        recorded.contains_key(TERRAIN_AUTO_APPLY),
+    );
+
+    let compared = |envs: &BTreeMap<String, String>| -> BTreeMap<String, String> {
+        envs.iter()
+            .filter(|(key, _)| !SHELL_ENVS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    };
+
+    Ok(EnvironmentDiff {
+        from: format!("session '{}'", state.session_id()),
+        to: format!("terrain.toml ({})", environment.selected_biome()),
+        envs: changes(&compared(&recorded), &compared(&environment.exports())),
+        ..Default::default()
+    })
+}
+
+/// compares environment variables of the shell with the active biome, variables
+/// that are not set by terrain are not compared
+fn shell(
+    context: &Context,
+    terrain: &Terrain,
+    shell_envs: BTreeMap<String, String>,
+) -> Result<EnvironmentDiff> {
+    let Some(selected_biome) = shell_envs.get(TERRAIN_SELECTED_BIOME) else {
+        bail!("terrain is not active in current shell");
+    };
+    let environment = Environment::from(
+        terrain,
+        BiomeArg::from_str(selected_biome)?,
+        context.terrain_dir(),
+    )
+    .context("failed to generate environment")?;
+
+    // values resolved by secret providers are not compared
+    let expected = environment.exports();
+    let current = shell_envs
+        .into_iter()
+        .filter(|(key, _)| expected.contains_key(key))
+        .collect();
+
+    Ok(EnvironmentDiff {
+        from: "shell".to_string(),
+        to: environment.selected_biome().clone(),
+        envs: changes(&current, &expected),
+        ..Default::default()
+    })
+}
+
+fn changes(
+    from: &BTreeMap<String, String>,
+    to: &BTreeMap<String, String>,
+) -> BTreeMap<String, Change> {
+    let removed = from
+        .iter()
+        .filter(|(key, _)| !to.contains_key(*key))
+        .map(|(key, value)| {
+            let value = value.clone();
+            (key.clone(), Change::Removed { value })
+        });
+    let added_or_changed = to.iter().filter_map(|(key, value)| {
+        let change = match from.get(key) {
+            None => Change::Added {
+                value: value.clone(),
+            },
+            Some(previous) if previous != value => Change::Changed {
+                from: previous.clone(),
+                to: value.clone(),
+            },
+            Some(_) => return None,
+        };
+        Some((key.clone(), change))
+    });
+    removed.chain(added_or_changed).collect()
+}
+
+/// commands are compared in order, so reordered commands are shown as removed and added
+fn command_changes(from: &[Command], to: &[Command]) -> Vec<Change> {
+    let from: Vec<String> = from.iter().map(ToString::to_string).collect();
+    let to: Vec<String> = to.iter().map(ToString::to_string).collect();
+
+    diff::slice(&from, &to)
+        .into_iter()
+        .filter_map(|result| match result {
+            diff::Result::Left(command) => Some(Change::Removed {
+                value: command.clone(),
+            }),
+            diff::Result::Right(command) => Some(Change::Added {
+                value: command.clone(),
+            }),
+            diff::Result::Both(..) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{Change, CommandsDiff, EnvironmentDiff};
+    use crate::client::args::BiomeArg;
+    use crate::client::test_utils::assertions::client::ExpectClient;
+    use crate::client::types::context::Context;
+    use crate::client::types::proto::{ProtoRequest, ProtoResponse};
+    use crate::client::types::terrain::{AutoApply, Terrain};
+    use crate::common::constants::{
+        EXAMPLE_BIOME, NONE, TERRAIN_SELECTED_BIOME, TERRAIN_TOML, TEST_TIMESTAMP,
+    };
+    use crate::common::execute::MockExecutor;
+    use crate::common::test_utils::{
+        RequestFor, TEST_SESSION_ID, TEST_TERRAIN_DIR, TEST_TERRAIN_NAME,
+        expected_activation_env_vars, expected_env_vars_example_biome, expected_status_request,
+        expected_zsh_env_vars,
+    };
+    use crate::common::types::pb;
+
+    fn changed(from: &str, to: &str) -> Change {
+        Change::Changed {
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn diff_between_biomes() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let diff = super::biomes(
+            &context,
+            &Terrain::example(),
+            BiomeArg::None,
+            BiomeArg::Some(EXAMPLE_BIOME.to_string()),
+        )?;
+
+        assert_eq!(diff.from, NONE);
+        assert_eq!(diff.to, EXAMPLE_BIOME);
+        assert_eq!(
+            diff.envs,
+            BTreeMap::from([
+                ("EDITOR".to_string(), changed("vim", "nvim")),
+                (
+                    "ENV_VAR".to_string(),
+                    changed("env_val", "overridden_env_val")
+                ),
+                (
+                    "NESTED_POINTER".to_string(),
+                    changed(
+                        "env_val-env_val-${NULL}",
+                        "overridden_env_val-overridden_env_val-${NULL}"
+                    )
+                ),
+                (
+                    "POINTER_ENV_VAR".to_string(),
+                    changed("env_val", "overridden_env_val")
+                ),
+            ])
+        );
+        assert_eq!(
+            diff.aliases,
+            BTreeMap::from([(
+                "tenter".to_string(),
+                changed("terrain enter", "terrain enter --biome example_biome")
+            )])
+        );
+        assert_eq!(
+            diff.constructors.foreground,
+            vec![Change::Added {
+                value: "`/bin/echo entering biome example_biome` in terrain directory".to_string()
+            }]
+        );
+        assert_eq!(diff.constructors.background.len(), 1);
+
+        // biome compared with itself has no differences
+        let diff = super::biomes(
+            &context,
+            &Terrain::example(),
+            BiomeArg::Default,
+            BiomeArg::Some(EXAMPLE_BIOME.to_string()),
+        )?;
+        assert_eq!(
+            diff.to_string(),
+            "no differences between example_biome and example_biome\n"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn diff_session_with_terrain() -> Result<()> {
+        let context = Context::build(
+            Path::new(TEST_TERRAIN_DIR),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+
+        let mut recorded = expected_env_vars_example_biome();
+        recorded.insert("EDITOR".to_string(), "vim".to_string());
+        recorded.insert("REMOVED".to_string(), "value".to_string());
+        recorded.append(&mut expected_activation_env_vars(
+            EXAMPLE_BIOME,
+            false,
+            &AutoApply::Off,
+            TEST_TERRAIN_DIR,
+        ));
+        recorded.append(&mut expected_zsh_env_vars(EXAMPLE_BIOME));
+
+        let client = ExpectClient::send(ProtoRequest::Status(expected_status_request(
+            RequestFor::SessionId(TEST_SESSION_ID.to_string()),
+            "",
+        )))
+        .with_expected_response(ProtoResponse::Status(Box::from(pb::StatusResponse {
+            session_id: TEST_SESSION_ID.to_string(),
+            terrain_name: TEST_TERRAIN_NAME.to_string(),
+            biome_name: EXAMPLE_BIOME.to_string(),
+            terrain_dir: TEST_TERRAIN_DIR.to_string(),
+            toml_path: Path::new(TEST_TERRAIN_DIR)
+                .join(TERRAIN_TOML)
+                .to_string_lossy()
+                .to_string(),
+            is_background: false,
+            start_timestamp: TEST_TIMESTAMP.to_string(),
+            end_timestamp: TEST_TIMESTAMP.to_string(),
+            envs: recorded,
+            constructors: Default::default(),
+            destructors: Default::default(),
+            biome_switches: vec![],
+        })))
+        .successfully();
+
+        let diff = super::session(
+            &context,
+            &Terrain::example(),
+            TEST_SESSION_ID.to_string(),
+            Some(client),
+        )
+        .await?;
+
+        // activation and shell environment variables are not reported
+        assert_eq!(
+            diff,
+            EnvironmentDiff {
+                from: format!("session '{TEST_SESSION_ID}'"),
+                to: format!("terrain.toml ({EXAMPLE_BIOME})"),
+                envs: BTreeMap::from([
+                    ("EDITOR".to_string(), changed("vim", "nvim")),
+                    (
+                        "REMOVED".to_string(),
+                        Change::Removed {
+                            value: "value".to_string()
+                        }
+                    ),
+                ]),
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn diff_shell_with_active_biome() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let mut shell_envs = expected_env_vars_example_biome();
+        shell_envs.insert("EDITOR".to_string(), "vim".to_string());
+        shell_envs.remove("PAGER");
+        shell_envs.insert("HOME".to_string(), "/home/user".to_string());
+        shell_envs.insert(
+            TERRAIN_SELECTED_BIOME.to_string(),
+            EXAMPLE_BIOME.to_string(),
+        );
+
+        let diff = super::shell(&context, &Terrain::example(), shell_envs)?;
+
+        // variables not set by terrain are not compared
+        assert_eq!(
+            diff.envs,
+            BTreeMap::from([
+                ("EDITOR".to_string(), changed("vim", "nvim")),
+                (
+                    "PAGER".to_string(),
+                    Change::Added {
+                        value: "less".to_string()
+                    }
+                ),
+            ])
+        );
+
+        let error = super::shell(&context, &Terrain::example(), BTreeMap::new())
+            .expect_err("to fail without active terrain");
+        assert_eq!(error.to_string(), "terrain is not active in current shell");
+
+        Ok(())
+    }
+
+    #[test]
+    fn renders_diff() {
+        let diff = EnvironmentDiff {
+            from: "a".to_string(),
+            to: "b".to_string(),
+            envs: BTreeMap::from([("EDITOR".to_string(), changed("vim", "nvim"))]),
+            aliases: BTreeMap::from([(
+                "tenter".to_string(),
+                Change::Removed {
+                    value: "terrain enter".to_string(),
+                },
+            )]),
+            constructors: CommandsDiff {
+                foreground: vec![],
+                background: vec![Change::Added {
+                    value: "`sleep 1` in terrain directory".to_string(),
+                }],
+            },
+            destructors: CommandsDiff::default(),
+        };
+
+        assert_eq!(
+            diff.to_string(),
+            "Changes from a to b:\n\
+             Environment Variables:\n    \u{1b}[33m~ EDITOR=\"vim\" -> \"nvim\"\u{1b}[0m\n\
+             Aliases:\n    \u{1b}[31m- tenter=\"terrain enter\"\u{1b}[0m\n\
+             Constructors:\n    background:\n        \u{1b}[32m+ `sleep 1` in terrain \
+             directory\u{1b}[0m\n"
+        );
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap()["envs"]["EDITOR"],
+            serde_json::json!({"change": "changed", "from": "vim", "to": "nvim"})
+        );
+    }
+}
@@ -0,0 +1,521 @@
+use std::fs::{exists, read_to_string, remove_file, write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+
+use crate::client::args::Verbs;
+use crate::client::handlers::generate;
+use crate::client::handlers::status::daemon_status;
+use crate::client::shell::zsh::ZSH_INIT_SCRIPT_NAME;
+use crate::client::shell::{Shell, Zsh, get_shell};
+use crate::client::types::context::{
+    Context, SCRIPTS_DIR_NAME, get_central_dir_location, get_terrain_dir,
+};
+use crate::client::types::doctor::{Check, CheckStatus};
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::{NONE, SHELL_INTEGRATION_SCRIPTS_DIR, TERRAINIUMD};
+use crate::common::execute::Execute;
+#[mockall_double::double]
+use crate::common::execute::Executor;
+use crate::common::types::command::Command;
+use crate::common::types::daemon_status::DaemonStatus;
+use crate::common::types::paths::get_terrainiumd_paths;
+use crate::common::utils::VERSION_INFO;
+use crate::daemon::service::ServiceProvider;
+
+const SHELL: &str = "shell";
+const RC: &str = "rc";
+const INTEGRATION_SCRIPT: &str = "integration-script";
+const SCRIPTS: &str = "scripts";
+const SERVICE: &str = "service";
+const SOCKET: &str = "socket";
+const VERSION: &str = "version";
+const STATE_DIR: &str = "state-dir";
+
+const FIX_HINT: &str = "run `terrain doctor --fix`";
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub async fn handle(
+    home_dir: &Path,
+    current_dir: &Path,
+    executor: Arc<Executor>,
+    json: bool,
+    fix: bool,
+) -> Result<()> {
+    let checks = doctor(home_dir, current_dir, executor, fix).await;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&checks).context("failed to serialize checks")?
+        );
+    } else {
+        checks.iter().for_each(|check| print!("{check}"));
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        bail!("{failed} doctor check(s) failed");
+    }
+    Ok(())
+}
+
+async fn doctor(
+    home_dir: &Path,
+    current_dir: &Path,
+    executor: Arc<Executor>,
+    fix: bool,
+) -> Vec<Check> {
+    let mut checks = vec![];
+
+    match get_shell(home_dir, executor.clone()) {
+        Ok(shell) => {
+            checks.push(Check::pass(SHELL, "zsh is supported".to_string()));
+            checks.push(rc(&shell, home_dir, fix));
+            checks.push(integration_script(&shell, home_dir, fix));
+        }
+        Err(err) => checks.push(Check::fail(
+            SHELL,
+            format!("{err:#}"),
+            "shell integration is only available for zsh",
+        )),
+    }
+
+    // scripts are only checked if there is terrain in current directory
+    if let Some(check) = scripts(home_dir, current_dir, &executor, fix) {
+        checks.push(check);
+    }
+
+    let paths = get_terrainiumd_paths();
+    checks.push(service(executor.clone()));
+    let (check, daemon) = socket(paths.socket()).await;
+    checks.push(check);
+    checks.push(version(&executor, daemon.as_ref()));
+    checks.push(state_dir(paths.dir()));
+    checks
+}
+
+/// checks if shell integration script is sourced in rc file
+fn rc(shell: &Zsh, home_dir: &Path, fix: bool) -> Check {
+    let rc_path = shell.get_default_rc(home_dir);
+    let sourced =
+        read_to_string(&rc_path).is_ok_and(|rc| rc.contains(Zsh::get_init_rc_contents().trim()));
+
+    let check = if sourced {
+        Check::pass(RC, format!("shell integration is sourced in {rc_path:?}"))
+    } else {
+        Check::fail(
+            RC,
+            format!("shell integration is not sourced in {rc_path:?}"),
+            "run `terrain --update-rc` or `terrain doctor --fix`",
+        )
+    };
+    check.fix_with(fix, || shell.update_rc(home_dir, rc_path.clone()))
+}
+
+/// checks if shell integration script in config directory is same as the one
+/// of this version, and that it is compiled
+fn integration_script(shell: &Zsh, home_dir: &Path, fix: bool) -> Check {
+    let dir = Context::config_dir(home_dir).join(SHELL_INTEGRATION_SCRIPTS_DIR);
+    let script = dir.join(ZSH_INIT_SCRIPT_NAME);
+    let compiled = script.with_extension("zwc");
+
+    let problem = match read_to_string(&script) {
+        Err(_) => Some("is missing"),
+        Ok(contents) if contents != shell.get_integration_script() => Some("is outdated"),
+        Ok(_) if !is_up_to_date(&compiled, std::slice::from_ref(&script)) => {
+            Some("is not compiled")
+        }
+        Ok(_) => None,
+    };
+
+    let check = match problem {
+        None => Check::pass(
+            INTEGRATION_SCRIPT,
+            format!("{script:?} is up to date and compiled"),
+        ),
+        Some(problem) => Check::warn(
+            INTEGRATION_SCRIPT,
+            format!("{script:?} {problem}"),
+            FIX_HINT,
+        ),
+    };
+    check.fix_with(fix, || shell.setup_integration(dir.clone()))
+}
+
+/// checks if compiled scripts of terrain in current directory are newer than
+/// terrain.toml, included files and parent terrains
+fn scripts(
+    home_dir: &Path,
+    current_dir: &Path,
+    executor: &Arc<Executor>,
+    fix: bool,
+) -> Option<Check> {
+    let (terrain_dir, toml_path) = get_terrain_dir(home_dir, current_dir)?;
+    let scripts_dir = get_central_dir_location(home_dir, &terrain_dir).join(SCRIPTS_DIR_NAME);
+
+    let check = match stale_scripts(&terrain_dir, &toml_path, &scripts_dir) {
+        Ok(stale) if stale.is_empty() => Check::pass(
+            SCRIPTS,
+            format!("scripts of terrain in {terrain_dir:?} are up to date"),
+        ),
+        Ok(stale) => Check::warn(
+            SCRIPTS,
+            format!(
+                "scripts are missing or older than terrain for: {}",
+                stale.join(", ")
+            ),
+            "run `terrain generate` or `terrain doctor --fix`",
+        ),
+        Err(err) => Check::fail(SCRIPTS, format!("{err:#}"), "run `terrain validate`"),
+    };
+
+    Some(check.fix_with(fix, || {
+        let context = Context::new(
+            &Verbs::Doctor { json: false, fix },
+            home_dir.to_path_buf(),
+            current_dir.to_path_buf(),
+            executor.clone(),
+        )?;
+        let (terrain, _) = Terrain::get_validated_and_fixed_terrain(&context)?;
+        generate::handle(context, terrain)
+    }))
+}
+
+/// names of biomes whose compiled scripts are missing or older than sources
+fn stale_scripts(terrain_dir: &Path, toml_path: &Path, scripts_dir: &Path) -> Result<Vec<String>> {
+    let toml = read_to_string(toml_path).context("failed to read terrain.toml")?;
+    let terrain = Terrain::from_toml(toml)
+        .and_then(|terrain| terrain.with_includes(toml_path, terrain_dir))
+        .and_then(|terrain| terrain.with_parent(terrain_dir))
+        .context("failed to parse terrain")?;
+    let sources = terrain.sources(toml_path);
+
+    Ok(terrain
+        .selectable_biomes()
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(NONE))
+        .filter(|biome| !is_up_to_date(&Zsh::compiled_script_path(scripts_dir, biome), &sources))
+        .map(str::to_string)
+        .collect())
+}
+
+/// `path` exists and is not older than any of the existing `sources`
+fn is_up_to_date(path: &Path, sources: &[PathBuf]) -> bool {
+    let modified = |path: &Path| -> Option<SystemTime> { path.metadata().ok()?.modified().ok() };
+    let Some(path_modified) = modified(path) else {
+        return false;
+    };
+    sources
+        .iter()
+        .filter_map(|source| modified(source))
+        .all(|source_modified| source_modified <= path_modified)
+}
+
+fn service(executor: Arc<Executor>) -> Check {
+    let status = ServiceProvider::get(executor).and_then(|service| service.status());
+    match status {
+        Ok(status) if status.starts_with("running") => {
+            Check::pass(SERVICE, format!("terrainiumd is {status}"))
+        }
+        Ok(status) => {
+            let hint = match status.as_str() {
+                "not installed" => "run `terrainiumd install`",
+                "not loaded" => "run `terrainiumd reload`",
+                _ => "run `terrainiumd start`",
+            };
+            Check::fail(SERVICE, format!("terrainiumd is {status}"), hint)
+        }
+        Err(err) => Check::fail(
+            SERVICE,
+            format!("failed to get status of terrainiumd: {err:#}"),
+            "run `terrainiumd status` to check the service",
+        ),
+    }
+}
+
+/// pings daemon on the socket, status of the daemon is returned if it responded
+async fn socket(socket: PathBuf) -> (Check, Option<DaemonStatus>) {
+    let hint = "run `terrainiumd start` or check logs of terrainiumd";
+    match tokio::time::timeout(SOCKET_TIMEOUT, daemon_status(None)).await {
+        Ok(Ok(status)) => (
+            Check::pass(
+                SOCKET,
+                format!("terrainiumd (pid {}) responded on {socket:?}", status.pid()),
+            ),
+            Some(status),
+        ),
+        Ok(Err(err)) => (Check::fail(SOCKET, format!("{err:#}"), hint), None),
+        Err(_) => (
+            Check::fail(
+                SOCKET,
+                format!("terrainiumd did not respond on {socket:?} in {SOCKET_TIMEOUT:?}"),
+                hint,
+            ),
+            None,
+        ),
+    }
+}
+
+/// compares version of running daemon, or of `terrainiumd` binary if daemon
+/// did not respond, with version of `terrain`
+fn version(executor: &Executor, daemon: Option<&DaemonStatus>) -> Check {
+    if let Some(daemon) = daemon {
+        return if daemon.version() == VERSION_INFO {
+            Check::pass(
+                VERSION,
+                format!("terrain and running terrainiumd are {VERSION_INFO}"),
+            )
+        } else {
+            Check::warn(
+                VERSION,
+                format!(
+                    "running terrainiumd {} does not match terrain {VERSION_INFO}",
+                    daemon.version()
+                ),
+                "restart terrainiumd with `terrainiumd stop && terrainiumd start`",
+            )
+        };
+    }
+
+    let command = Command::new(
+        TERRAINIUMD.to_string(),
+        vec!["--version".to_string()],
+        Some(std::env::temp_dir()),
+    );
+    let hint = "install terrain and terrainiumd from the same release and restart terrainiumd";
+
+    match executor.get_output(None, command) {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let daemon_version = stdout.trim().trim_start_matches(TERRAINIUMD).trim();
+            if daemon_version == VERSION_INFO {
+                Check::pass(
+                    VERSION,
+                    format!("terrain and terrainiumd are {VERSION_INFO}"),
+                )
+            } else {
+                Check::warn(
+                    VERSION,
+                    format!("terrainiumd {daemon_version} does not match terrain {VERSION_INFO}"),
+                    hint,
+                )
+            }
+        }
+        Ok(output) => Check::fail(
+            VERSION,
+            format!("`terrainiumd --version` exited with {}", output.status),
+            hint,
+        ),
+        Err(err) => Check::fail(
+            VERSION,
+            format!("failed to run `terrainiumd --version`: {err:#}"),
+            hint,
+        ),
+    }
+}
+
+/// checks if daemon can write session states to its directory
+fn state_dir(dir: &Path) -> Check {
+    if !exists(dir).unwrap_or_default() {
+        return Check::warn(
+            STATE_DIR,
+            format!("{dir:?} does not exist"),
+            "it is created when terrainiumd starts, run `terrainiumd start`",
+        );
+    }
+
+    let file = dir.join(".terrain-doctor");
+    match write(&file, "").and_then(|_| remove_file(&file)) {
+        Ok(()) => Check::pass(STATE_DIR, format!("{dir:?} is writable")),
+        Err(err) => Check::fail(
+            STATE_DIR,
+            format!("{dir:?} is not writable: {err}"),
+            "check permissions of the directory",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{File, create_dir_all, read_to_string, write};
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::shell::{Shell, Zsh};
+    use crate::client::test_utils::assertions::executor::{AssertExecutor, ExpectedCommand};
+    use crate::client::test_utils::assertions::zsh::ExpectZSH;
+    use crate::client::types::doctor::CheckStatus;
+    use crate::common::constants::{TERRAIN_TOML, TERRAINIUMD};
+    use crate::common::execute::MockExecutor;
+    use crate::common::types::command::Command;
+    use crate::common::types::daemon_status::DaemonStatus;
+    use crate::common::utils::VERSION_INFO;
+
+    fn set_modified(path: &Path, modified: SystemTime) -> Result<()> {
+        File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(modified)?;
+        Ok(())
+    }
+
+    #[test]
+    fn checks_and_fixes_shell_integration() -> Result<()> {
+        let home_dir = tempdir()?;
+        let rc_path = home_dir.path().join(".zshrc");
+        write(&rc_path, "export EDITOR=vim\n")?;
+
+        let integration_dir = home_dir.path().join(".config/terrainium/shell_integration");
+        let script = integration_dir.join("terrainium_init.zsh");
+        let compiled = integration_dir.join("terrainium_init.zwc");
+
+        let shell = Zsh::get(home_dir.path(), Arc::new(MockExecutor::new()));
+        let check = super::rc(&shell, home_dir.path(), false);
+        assert_eq!(check.status, CheckStatus::Fail);
+        let check = super::integration_script(&shell, home_dir.path(), false);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(check.message, format!("{script:?} is missing"));
+
+        // rc is fixed by setting up integration and appending to rc
+        let executor = ExpectZSH::with(MockExecutor::new(), home_dir.path())
+            .compile_script_successfully_for(&script, &compiled)
+            .successfully();
+        let shell = Zsh::get(home_dir.path(), Arc::new(executor));
+        let check = super::rc(&shell, home_dir.path(), true);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(check.fixed);
+        assert!(read_to_string(&rc_path)?.contains("terrainium_init.zsh"));
+
+        // compiled script is not created by mocked executor
+        let check = super::integration_script(&shell, home_dir.path(), false);
+        assert_eq!(check.message, format!("{script:?} is not compiled"));
+
+        write(&compiled, "")?;
+        set_modified(&script, SystemTime::now() - Duration::from_secs(60))?;
+        let check = super::integration_script(&shell, home_dir.path(), false);
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(!check.fixed);
+
+        write(&script, "outdated")?;
+        let check = super::integration_script(&shell, home_dir.path(), false);
+        assert_eq!(check.message, format!("{script:?} is outdated"));
+        Ok(())
+    }
+
+    #[test]
+    fn checks_stale_scripts() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let scripts_dir = tempdir()?;
+        let toml_path = terrain_dir.path().join(TERRAIN_TOML);
+        write(
+            &toml_path,
+            r#"
+name = "doctor"
+auto_apply = "off"
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.dev]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#,
+        )?;
+
+        let stale = super::stale_scripts(terrain_dir.path(), &toml_path, scripts_dir.path())?;
+        assert_eq!(stale, vec!["dev", "none"]);
+
+        create_dir_all(scripts_dir.path())?;
+        write(scripts_dir.path().join("terrain-dev.zwc"), "")?;
+        write(scripts_dir.path().join("terrain-none.zwc"), "")?;
+        set_modified(&toml_path, SystemTime::now() - Duration::from_secs(60))?;
+        let stale = super::stale_scripts(terrain_dir.path(), &toml_path, scripts_dir.path())?;
+        assert!(stale.is_empty());
+
+        set_modified(&toml_path, SystemTime::now() + Duration::from_secs(60))?;
+        let stale = super::stale_scripts(terrain_dir.path(), &toml_path, scripts_dir.path())?;
+        assert_eq!(stale, vec!["dev", "none"]);
+        Ok(())
+    }
+
+    #[test]
+    fn checks_daemon_version() {
+        let expect_version = |output: String| {
+            AssertExecutor::with(MockExecutor::new())
+                .get_output_for(
+                    None,
+                    ExpectedCommand {
+                        command: Command::new(
+                            TERRAINIUMD.to_string(),
+                            vec!["--version".to_string()],
+                            Some(std::env::temp_dir()),
+                        ),
+                        exit_code: 0,
+                        should_fail_to_execute: false,
+                        output,
+                    },
+                    1,
+                )
+                .successfully()
+        };
+
+        let executor = expect_version(format!("terrainiumd {VERSION_INFO}\n"));
+        assert_eq!(super::version(&executor, None).status, CheckStatus::Pass);
+
+        let executor = expect_version("terrainiumd v0.0.1-release+abc\n".to_string());
+        let check = super::version(&executor, None);
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(
+            check.message,
+            format!("terrainiumd v0.0.1-release+abc does not match terrain {VERSION_INFO}")
+        );
+
+        // version of running daemon is used without running the binary
+        let daemon = |version: &str| {
+            DaemonStatus::new(
+                version.to_string(),
+                Duration::from_secs(1),
+                1,
+                "/tmp/terrainiumd".to_string(),
+                0,
+                0,
+            )
+        };
+        let executor = MockExecutor::new();
+        let check = super::version(&executor, Some(&daemon(VERSION_INFO)));
+        assert_eq!(check.status, CheckStatus::Pass);
+        let check = super::version(&executor, Some(&daemon("v0.0.1-release+abc")));
+        assert_eq!(
+            check.message,
+            format!("running terrainiumd v0.0.1-release+abc does not match terrain {VERSION_INFO}")
+        );
+    }
+
+    #[test]
+    fn checks_state_dir() -> Result<()> {
+        let state_dir = tempdir()?;
+        assert_eq!(super::state_dir(state_dir.path()).status, CheckStatus::Pass);
+        assert_eq!(
+            super::state_dir(&state_dir.path().join("missing")).status,
+            CheckStatus::Warn
+        );
+        Ok(())
+    }
+}
@@ -1,19 +1,22 @@
 use std::sync::Arc;
 
 use anyhow::{Context as AnyhowContext, Result, bail};
+use tracing::{info, warn};
 use uuid::Uuid;
 
 use crate::client::args::BiomeArg;
-use crate::client::handlers::background::execute_request;
+use crate::client::handlers::background::{execute_request, send_or_record};
 use crate::client::shell::Shell;
 #[mockall_double::double]
 use crate::client::types::client::Client;
 use crate::client::types::context::Context;
 use crate::client::types::environment::Environment;
 use crate::client::types::proto::ProtoRequest;
+use crate::client::types::registry::Registration;
 use crate::client::types::terrain::Terrain;
-use crate::common::constants::{DEBUG_PATH, PATH, TERRAINIUM_DEV};
-use crate::common::types::paths::get_terrainiumd_paths;
+use crate::common::constants::{
+    DEBUG_PATH, PATH, TERRAIN_RELOAD_CONSTRUCTORS, TERRAINIUM_DEV, TRUE,
+};
 use crate::common::types::pb;
 use crate::common::utils::timestamp;
 
@@ -35,13 +38,26 @@ pub async fn handle(
         context.set_session_id(&Uuid::new_v4().to_string())
     };
 
+    if terrain.includes_changed(&context.scripts_dir())? {
+        info!("included files were modified, regenerating scripts");
+        context
+            .shell()
+            .generate_scripts(&context, terrain.clone())?;
+    }
+
     let mut environment = Environment::from(&terrain, biome, context.terrain_dir())
         .context("failed to generate environment")?;
 
+    // failing to register only affects `terrain list`
+    if let Err(err) = Registration::register(&context, &terrain, true) {
+        warn!("failed to register the terrain: {err:#}");
+    }
+    environment.resolve_secrets(context.executor(), context.terrain_dir())?;
+
     let zsh_envs = context
         .shell()
         .generate_envs(context.scripts_dir(), environment.selected_biome())?;
-    environment.append_envs(zsh_envs);
+    environment.append_envs(zsh_envs, "shell");
     environment.add_activation_envs(
         context.session_id().unwrap(),
         context.terrain_dir(),
@@ -52,6 +68,9 @@ pub async fn handle(
         || (context.config().auto_apply() && environment.auto_apply().is_background_enabled());
 
     let mut shell_envs = environment.envs();
+    if context.config().reload_constructors() {
+        shell_envs.insert(TERRAIN_RELOAD_CONSTRUCTORS.to_string(), TRUE.to_string());
+    }
     if cfg!(debug_assertions) && shell_envs.get(TERRAINIUM_DEV).is_some_and(|v| v == "true") {
         let path = std::env::var(PATH).context("expected environment variable PATH")?;
         let debug_path = context.terrain_dir().join(DEBUG_PATH);
@@ -85,21 +104,13 @@ async fn send_activate_request(
     environment: Environment,
     is_background: bool,
 ) -> Result<()> {
-    let mut client = if let Some(client) = client {
-        client
-    } else {
-        Client::new(get_terrainiumd_paths().socket()).await?
-    };
-
-    client
-        .request(ProtoRequest::Activate(activate_request(
-            context,
-            environment,
-            is_background,
-        )?))
-        .await?;
-
-    Ok(())
+    // shell is usable without daemon, only background commands and status are lost
+    send_or_record(
+        context,
+        ProtoRequest::Activate(activate_request(context, environment, is_background)?),
+        client,
+    )
+    .await
 }
 
 fn activate_request(
@@ -0,0 +1,107 @@
+use anyhow::{Context as AnyhowContext, Result};
+
+#[mockall_double::double]
+use crate::client::types::client::Client;
+use crate::common::types::event::Event;
+use crate::common::types::paths::get_terrainiumd_paths;
+use crate::common::types::pb;
+
+/// prints events streamed by daemon until daemon closes the stream
+pub async fn handle(
+    terrain_name: Option<String>,
+    session_id: Option<String>,
+    json: bool,
+    client: Option<Client>,
+) -> Result<()> {
+    let mut client = if let Some(client) = client {
+        client
+    } else {
+        Client::new(get_terrainiumd_paths().socket()).await?
+    };
+
+    client
+        .subscribe(pb::Subscribe {
+            terrain_name,
+            session_id,
+        })
+        .await
+        .context("failed to subscribe to daemon events")?;
+
+    while let Some(event) = client.next_event().await? {
+        println!("{}", format(&event, json)?);
+    }
+    Ok(())
+}
+
+/// one event per line, so json output can be consumed as json lines
+fn format(event: &Event, json: bool) -> Result<String> {
+    if json {
+        serde_json::to_string(event).context("failed to serialize event")
+    } else {
+        Ok(event.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mockall::Sequence;
+    use mockall::predicate::eq;
+    use pretty_assertions::assert_eq;
+
+    use crate::client::types::client::MockClient;
+    use crate::common::test_utils::{TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::event::{Event, EventKind};
+    use crate::common::types::pb;
+
+    fn session_ended() -> Event {
+        Event::new(
+            TEST_TERRAIN_NAME.to_string(),
+            TEST_SESSION_ID.to_string(),
+            EventKind::SessionEnded {
+                end_timestamp: "timestamp".to_string(),
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn prints_events_until_stream_is_closed() {
+        let mut sequence = Sequence::new();
+        let mut client = MockClient::default();
+        client
+            .expect_subscribe()
+            .with(eq(pb::Subscribe {
+                terrain_name: Some(TEST_TERRAIN_NAME.to_string()),
+                session_id: None,
+            }))
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|_| Ok(()));
+        client
+            .expect_next_event()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|| Ok(Some(session_ended())));
+        client
+            .expect_next_event()
+            .times(1)
+            .in_sequence(&mut sequence)
+            .return_once(|| Ok(None));
+
+        super::handle(
+            Some(TEST_TERRAIN_NAME.to_string()),
+            None,
+            true,
+            Some(client),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn formats_event_as_json_line() {
+        assert_eq!(
+            super::format(&session_ended(), true).unwrap(),
+            r#"{"terrain_name":"terrainium","session_id":"session_id","event":"session_ended","end_timestamp":"timestamp"}"#
+        );
+    }
+}
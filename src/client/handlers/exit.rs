@@ -5,7 +5,7 @@ use anyhow::{Context as AnyhowContext, Result, bail};
 use clap::ValueEnum;
 
 use crate::client::args::BiomeArg;
-use crate::client::handlers::background::execute_request;
+use crate::client::handlers::background::{execute_request, send_or_record};
 #[mockall_double::double]
 use crate::client::types::client::Client;
 use crate::client::types::context::Context;
@@ -13,7 +13,6 @@ use crate::client::types::environment::Environment;
 use crate::client::types::proto::ProtoRequest;
 use crate::client::types::terrain::{AutoApply, Terrain};
 use crate::common::constants::{TERRAIN_AUTO_APPLY, TERRAIN_SELECTED_BIOME};
-use crate::common::types::paths::get_terrainiumd_paths;
 use crate::common::types::pb;
 use crate::common::utils::timestamp;
 
@@ -25,29 +24,21 @@ pub async fn handle(context: Context, terrain: Terrain, client: Option<Client>)
         bail!("no active terrain found, use 'terrain enter' command to activate a terrain.");
     }
 
-    let mut client = if let Some(client) = client {
-        client
-    } else {
-        Client::new(get_terrainiumd_paths().socket()).await?
-    };
-
-    client
-        .request(ProtoRequest::Deactivate(deactivate(
-            terrain.name().to_string(),
-            session_id.expect("session id to be present").to_string(),
-            selected_biome.unwrap(),
-            terrain,
-            context,
-        )?))
-        .await?;
-    Ok(())
+    let request = ProtoRequest::Deactivate(deactivate(
+        terrain.name().to_string(),
+        session_id.expect("session id to be present").to_string(),
+        selected_biome.unwrap(),
+        terrain,
+        &context,
+    )?);
+    send_or_record(&context, request, client).await
 }
 
 /// 'terrain exit' should run background destructor commands only in following case:
 /// 1. Auto-apply is disabled i.e. TERRAIN_AUTO_APPLY env var is not set i.e.
 ///    user activated terrain manually
 /// 2. Auto-apply is enabled and background flag is also turned on
-fn should_run_destructor() -> bool {
+pub(crate) fn should_run_destructor() -> bool {
     let auto_apply = env::var(TERRAIN_AUTO_APPLY);
     match auto_apply {
         Ok(auto_apply) => {
@@ -68,7 +59,7 @@ fn deactivate(
     session_id: String,
     selected_biome: String,
     terrain: Terrain,
-    context: Context,
+    context: &Context,
 ) -> Result<pb::Deactivate> {
     let end_timestamp = timestamp();
     let destructors = if should_run_destructor() {
@@ -78,7 +69,7 @@ fn deactivate(
             context.terrain_dir(),
         )
         .context("failed to generate environment")?;
-        execute_request(&context, environment, false, end_timestamp.clone())?
+        execute_request(context, environment, false, end_timestamp.clone())?
     } else {
         None
     };
@@ -0,0 +1,195 @@
+use anyhow::{Context as AnyhowContext, Result};
+
+use crate::client::args::BiomeArg;
+use crate::client::types::context::Context;
+use crate::client::types::environment::Environment;
+use crate::client::types::provenance::{Origin, Provenance, explain_commands, explain_pairs};
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::TERRAIN_AUTO_APPLY;
+
+pub fn handle(
+    context: Context,
+    terrain: Terrain,
+    biome: BiomeArg,
+    env: Vec<String>,
+    alias: Vec<String>,
+) -> Result<()> {
+    let is_auto_apply = std::env::var(TERRAIN_AUTO_APPLY).is_ok();
+    let output = explain(context, terrain, biome, env, alias, is_auto_apply)?;
+    print!("{output}");
+    Ok(())
+}
+
+fn explain(
+    context: Context,
+    terrain: Terrain,
+    biome: BiomeArg,
+    env: Vec<String>,
+    alias: Vec<String>,
+    is_auto_apply: bool,
+) -> Result<String> {
+    let mut environment = Environment::from(&terrain, biome, context.terrain_dir())
+        .context("failed to generate environment")?;
+
+    // activation environment variables are only present in active terrain
+    if let Some(session_id) = context.session_id() {
+        environment.add_activation_envs(session_id, context.terrain_dir(), is_auto_apply);
+    }
+
+    let provenance = environment.merged().provenance();
+    let all = env.is_empty() && alias.is_empty();
+
+    let mut result = String::new();
+
+    if all || !env.is_empty() {
+        result += &explain_pairs(
+            "Environment Variables:",
+            environment.envs_ref(),
+            &env,
+            |key| explanation(&provenance, provenance.env(key), key),
+        );
+    }
+
+    if all || !alias.is_empty() {
+        result += &explain_pairs("Aliases:", environment.aliases_ref(), &alias, |key| {
+            explanation(&provenance, provenance.alias(key), key)
+        });
+    }
+
+    if all {
+        result += &explain_commands(
+            "Constructors:",
+            environment.constructors_ref(),
+            provenance.constructors(),
+        );
+        result += &explain_commands(
+            "Destructors:",
+            environment.destructors_ref(),
+            provenance.destructors(),
+        );
+    }
+
+    Ok(result)
+}
+
+/// value in each layer followed by the substituted environment variable references
+fn explanation(provenance: &Provenance, origins: &[Origin], key: &str) -> String {
+    origins
+        .iter()
+        .map(|origin| format!("\n{: <8}{origin}", ""))
+        .chain(
+            provenance
+                .substitutions(key)
+                .iter()
+                .map(|substitution| format!("\n{: <8}{substitution}", "")),
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{read_to_string, write};
+    use std::path::Path;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::args::BiomeArg;
+    use crate::client::types::context::Context;
+    use crate::client::types::terrain::Terrain;
+    use crate::common::constants::TERRAIN_TOML;
+    use crate::common::execute::MockExecutor;
+
+    #[test]
+    fn explains_layers_and_substitutions() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let output = super::explain(
+            context,
+            Terrain::example(),
+            BiomeArg::Default,
+            vec!["EDITOR".to_string(), "NESTED_POINTER".to_string()],
+            vec!["tenter".to_string(), "does_not_exist".to_string()],
+            false,
+        )?;
+
+        let expected = r#"Environment Variables:
+    EDITOR="nvim"
+        terrain: "vim"
+        biomes.example_biome: "nvim"
+    NESTED_POINTER="overridden_env_val-overridden_env_val-${NULL}"
+        terrain: "${POINTER_ENV_VAR}-${ENV_VAR}-${NULL_POINTER}"
+        ${NULL_POINTER} -> "${NULL}" from env NULL_POINTER
+        ${NULL} is not defined
+        ${ENV_VAR} -> "overridden_env_val" from env ENV_VAR
+        ${POINTER_ENV_VAR} -> "${ENV_VAR}" from env POINTER_ENV_VAR
+Aliases:
+    tenter="terrain enter --biome example_biome"
+        terrain: "terrain enter"
+        biomes.example_biome: "terrain enter --biome example_biome"
+    does_not_exist="!!!DOES_NOT_EXIST!!!"
+"#;
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn explains_included_and_activation_values() -> Result<()> {
+        let dir = tempdir()?;
+        write(
+            dir.path().join("base.toml"),
+            r#"
+[terrain.envs]
+LAYER = "base"
+"#,
+        )?;
+        let toml_path = dir.path().join(TERRAIN_TOML);
+        write(
+            &toml_path,
+            r#"
+name = "includes"
+auto_apply = "off"
+include = ["base.toml"]
+
+[terrain]
+envs = { LAYER = "local" }
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+"#,
+        )?;
+
+        let terrain = Terrain::from_toml(read_to_string(&toml_path)?)?
+            .with_includes(&toml_path, dir.path())?;
+        let context = Context::build(dir.path(), Path::new(""), false, MockExecutor::new())
+            .set_session_id("session_id");
+
+        let output = super::explain(
+            context,
+            terrain,
+            BiomeArg::None,
+            vec!["LAYER".to_string(), "TERRAIN_SESSION_ID".to_string()],
+            vec![],
+            false,
+        )?;
+
+        let base = dir.path().join("base.toml").canonicalize()?;
+        let expected = format!(
+            r#"Environment Variables:
+    LAYER="local"
+        {}:terrain: "base"
+        terrain: "local"
+    TERRAIN_SESSION_ID="session_id"
+        activation: "session_id"
+"#,
+            base.display()
+        );
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+use std::fs::write;
+use std::path::PathBuf;
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+use clap::ValueEnum;
+use tracing::warn;
+
+use crate::client::args::{BiomeArg, ExportFormat};
+use crate::client::types::context::Context;
+use crate::client::types::environment::Environment;
+use crate::client::types::export::Export;
+use crate::client::types::terrain::Terrain;
+
+pub fn handle(
+    context: Context,
+    terrain: Terrain,
+    format: ExportFormat,
+    biome: BiomeArg,
+    keep_references: bool,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let rendered = export(&context, &terrain, format, biome, keep_references)?;
+    match output {
+        Some(output) => {
+            write(&output, rendered).context(format!("failed to write export to {output:?}"))
+        }
+        None => {
+            print!("{rendered}");
+            Ok(())
+        }
+    }
+}
+
+fn export(
+    context: &Context,
+    terrain: &Terrain,
+    format: ExportFormat,
+    biome: BiomeArg,
+    keep_references: bool,
+) -> Result<String> {
+    if keep_references && !format.supports_references() {
+        bail!(
+            "'{}' format does not support environment variable references",
+            format
+                .to_possible_value()
+                .expect("export format to have value")
+                .get_name()
+        );
+    }
+
+    let environment = Environment::from(terrain, biome.clone(), context.terrain_dir())
+        .context("failed to generate environment")?;
+
+    // environment variables resolved by secret providers are never written to files
+    let exports = environment.exports();
+    environment
+        .envs_ref()
+        .keys()
+        .filter(|key| !exports.contains_key(*key))
+        .for_each(|key| warn!("environment variable '{key}' references secret, not exporting"));
+
+    let envs: BTreeMap<String, String> = if keep_references {
+        terrain
+            .merged(&biome)?
+            .envs()
+            .iter()
+            .filter(|(key, _)| exports.contains_key(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    } else {
+        exports
+    };
+
+    Ok(Export::new(&environment, envs, keep_references).render(format))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use crate::client::args::{BiomeArg, ExportFormat};
+    use crate::client::types::context::Context;
+    use crate::client::types::terrain::Terrain;
+    use crate::common::constants::EXAMPLE_BIOME;
+    use crate::common::execute::MockExecutor;
+
+    fn assert_export(format: ExportFormat, keep_references: bool, golden: &str) -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+        let output = super::export(
+            &context,
+            &Terrain::example(),
+            format,
+            BiomeArg::from_str(EXAMPLE_BIOME)?,
+            keep_references,
+        )?;
+
+        let expected = read_to_string(format!(
+            "./tests/data/terrain-example_biome.export.{golden}"
+        ))
+        .expect("test data to be read");
+        assert_eq!(output, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn exports_resolved_values() -> Result<()> {
+        assert_export(ExportFormat::Dotenv, false, "env")?;
+        assert_export(ExportFormat::Direnv, false, "envrc")?;
+        assert_export(ExportFormat::DockerEnvFile, false, "docker.env")?;
+        assert_export(ExportFormat::SystemdEnv, false, "systemd.env")?;
+        assert_export(ExportFormat::VscodeLaunch, false, "launch.json")?;
+        assert_export(ExportFormat::Justfile, false, "justfile")
+    }
+
+    #[test]
+    fn exports_references() -> Result<()> {
+        assert_export(ExportFormat::Dotenv, true, "references.env")?;
+        assert_export(ExportFormat::Direnv, true, "references.envrc")?;
+        assert_export(ExportFormat::Justfile, true, "references.justfile")
+    }
+
+    #[test]
+    fn fails_to_keep_references_for_unsupported_format() {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+        let err = super::export(
+            &context,
+            &Terrain::example(),
+            ExportFormat::SystemdEnv,
+            BiomeArg::Default,
+            true,
+        )
+        .expect_err("references to not be supported");
+
+        assert_eq!(
+            err.to_string(),
+            "'systemd-env' format does not support environment variable references"
+        );
+    }
+}
@@ -4,6 +4,7 @@ use anyhow::{Context as AnyhowContext, Result};
 
 use crate::client::shell::Shell;
 use crate::client::types::context::Context;
+use crate::client::types::registry::Registration;
 use crate::client::types::terrain::Terrain;
 
 pub fn handle(context: Context, terrain: Terrain) -> Result<()> {
@@ -11,20 +12,24 @@ pub fn handle(context: Context, terrain: Terrain) -> Result<()> {
         create_dir_all(context.scripts_dir()).context("failed to create scripts dir")?;
     }
 
+    Registration::register(&context, &terrain, false).context("failed to register the terrain")?;
     context.shell().generate_scripts(&context, terrain)?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs::read_to_string;
+
     use anyhow::Result;
+    use pretty_assertions::assert_eq;
     use tempfile::tempdir;
 
     use crate::client::test_utils::assertions::terrain::AssertTerrain;
     use crate::client::test_utils::assertions::zsh::ExpectZSH;
     use crate::client::types::context::Context;
     use crate::client::types::terrain::Terrain;
-    use crate::common::constants::{EXAMPLE_BIOME, NONE};
+    use crate::common::constants::{EXAMPLE_BIOME, NONE, TERRAIN_SOURCES};
     use crate::common::execute::MockExecutor;
 
     #[test]
@@ -39,6 +44,8 @@ mod tests {
 
         let context: Context =
             Context::build(current_dir.path(), central_dir.path(), false, executor);
+        let scripts_dir = context.scripts_dir();
+        let toml_path = context.toml_path().to_path_buf();
 
         super::handle(context, Terrain::example()).expect("no error to be thrown");
 
@@ -46,6 +53,12 @@ mod tests {
             .script_was_created_for(NONE)
             .script_was_created_for(EXAMPLE_BIOME);
 
+        // sources are stored so active shell can detect changes in terrain
+        assert_eq!(
+            read_to_string(scripts_dir.join(TERRAIN_SOURCES))?,
+            format!("{}\n", toml_path.display())
+        );
+
         Ok(())
     }
 
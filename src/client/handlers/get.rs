@@ -3,7 +3,9 @@ use anyhow::{Context as AnyhowContext, Result};
 use crate::client::args::GetArgs;
 use crate::client::types::context::Context;
 use crate::client::types::environment::Environment;
+use crate::client::types::provenance::{Origin, explain_commands, explain_pairs};
 use crate::client::types::terrain::{AutoApply, Terrain};
+use crate::common::constants::TERRAIN_DIR;
 
 pub fn handle(context: Context, terrain: Terrain, get_args: GetArgs) -> Result<()> {
     let output = get(context, terrain, get_args)?;
@@ -12,8 +14,18 @@ pub fn handle(context: Context, terrain: Terrain, get_args: GetArgs) -> Result<(
 }
 
 fn get(context: Context, terrain: Terrain, get_args: GetArgs) -> Result<String> {
-    let environment = Environment::from(&terrain, get_args.biome.clone(), context.terrain_dir())
-        .context("failed to generate environment")?;
+    let mut environment =
+        Environment::from(&terrain, get_args.biome.clone(), context.terrain_dir())
+            .context("failed to generate environment")?;
+
+    if get_args.reveal {
+        environment.resolve_secrets(context.executor(), context.terrain_dir())?;
+    }
+
+    // explain without any selector explains all values
+    if get_args.explain {
+        return Ok(explain(&environment, &get_args));
+    }
 
     if get_args.empty() {
         if get_args.json {
@@ -24,10 +36,8 @@ fn get(context: Context, terrain: Terrain, get_args: GetArgs) -> Result<String>
     }
 
     if get_args.auto_apply {
-        if context.config().auto_apply() {
-            return Ok(terrain.auto_apply().to_string());
-        }
-        return Ok(AutoApply::default().to_string());
+        let active_terrain_dir = std::env::var(TERRAIN_DIR).ok();
+        return Ok(auto_apply(&context, &terrain, active_terrain_dir).to_string());
     }
 
     let mut result = String::new();
@@ -55,21 +65,88 @@ fn get(context: Context, terrain: Terrain, get_args: GetArgs) -> Result<String>
     Ok(result)
 }
 
+/// auto apply for the terrain of current directory, `active_terrain_dir` is set
+/// if a terrain is already active (i.e. `TERRAIN_DIR` is set)
+fn auto_apply(
+    context: &Context,
+    terrain: &Terrain,
+    active_terrain_dir: Option<String>,
+) -> AutoApply {
+    if !context.config().auto_apply() {
+        return AutoApply::default();
+    }
+
+    terrain
+        .auto_apply()
+        .with_active_terrain(active_terrain_dir.as_deref(), &terrain.parent_dirs())
+}
+
+/// label of the layer that defines the value, as the last layer overrides the values
+/// from previous layers
+fn defined_in(origins: &[Origin]) -> String {
+    origins.last().map(Origin::label).unwrap_or_default()
+}
+
+fn explain(environment: &Environment, get_args: &GetArgs) -> String {
+    let provenance = environment.merged().provenance();
+    let all = get_args.empty();
+
+    let mut result = String::new();
+
+    if all || get_args.envs || !get_args.env.is_empty() {
+        result += &explain_pairs(
+            "Environment Variables:",
+            environment.envs_ref(),
+            &get_args.env,
+            |key| format!(" <- {}", defined_in(provenance.env(key))),
+        );
+    }
+
+    if all || get_args.aliases || !get_args.alias.is_empty() {
+        result += &explain_pairs(
+            "Aliases:",
+            environment.aliases_ref(),
+            &get_args.alias,
+            |key| format!(" <- {}", defined_in(provenance.alias(key))),
+        );
+    }
+
+    if all || get_args.constructors {
+        result += &explain_commands(
+            "Constructors:",
+            environment.constructors_ref(),
+            provenance.constructors(),
+        );
+    }
+
+    if all || get_args.destructors {
+        result += &explain_commands(
+            "Destructors:",
+            environment.destructors_ref(),
+            provenance.destructors(),
+        );
+    }
+
+    result
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs::read_to_string;
+    use std::fs::{Permissions, create_dir_all, read_to_string, set_permissions, write};
+    use std::os::unix::fs::PermissionsExt;
     use std::path::Path;
     use std::str::FromStr;
 
     use anyhow::Result;
     use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
 
     use crate::client::args::{BiomeArg, GetArgs};
     use crate::client::types::config::Config;
     use crate::client::types::context::Context;
-    use crate::client::types::terrain::Terrain;
     use crate::client::types::terrain::tests::set_auto_apply;
-    use crate::common::constants::EXAMPLE_BIOME;
+    use crate::client::types::terrain::{AutoApply, Terrain};
+    use crate::common::constants::{EXAMPLE_BIOME, TERRAIN_TOML};
     use crate::common::execute::MockExecutor;
 
     #[test]
@@ -86,6 +163,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -112,6 +191,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::default(), args).expect("to not throw an error");
@@ -138,6 +219,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -164,6 +247,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -190,6 +275,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -217,6 +304,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -244,6 +333,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::default(), args).expect("to not throw an error");
@@ -268,6 +359,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -299,6 +392,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -330,6 +425,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::default(), args).expect("to not throw an error");
@@ -354,6 +451,8 @@ mod tests {
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -388,6 +487,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -415,6 +516,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -442,6 +545,8 @@ Aliases:
             constructors: true,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -473,6 +578,8 @@ Aliases:
             constructors: false,
             destructors: true,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -504,6 +611,8 @@ Aliases:
             constructors: true,
             destructors: true,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -551,6 +660,8 @@ Destructors:
             constructors: true,
             destructors: true,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -594,6 +705,8 @@ Destructors:
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -624,6 +737,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: false,
+            reveal: false,
+            explain: false,
         };
 
         let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
@@ -658,6 +773,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: true,
+            reveal: false,
+            explain: false,
         };
 
         let mut terrain = Terrain::example();
@@ -671,6 +788,53 @@ Aliases:
         Ok(())
     }
 
+    #[test]
+    fn get_auto_apply_inside_active_terrain() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let parent_dir = tempdir()?;
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir)?;
+        write(
+            parent_dir.path().join(TERRAIN_TOML),
+            Terrain::example().to_toml(parent_dir.path())?,
+        )?;
+
+        let mut child = Terrain::example();
+        set_auto_apply(&mut child, "replace");
+        let child = child.to_toml(&child_dir)?.replace(
+            "auto_apply = \"replace\"",
+            "auto_apply = \"replace\"\ninherit_parent = true",
+        );
+        let child = Terrain::from_toml(child)?.with_parent(&child_dir)?;
+
+        let parent = parent_dir.path().display().to_string();
+        let unrelated = tempdir()?.path().display().to_string();
+
+        // not in a terrain shell
+        assert_eq!(
+            super::auto_apply(&context, &child, None),
+            AutoApply::Replace
+        );
+        // moving from parent to child, active shell should not be replaced
+        assert_eq!(
+            super::auto_apply(&context, &child, Some(parent.clone())),
+            AutoApply::Enabled
+        );
+        // child is already active
+        assert_eq!(
+            super::auto_apply(&context, &child, Some(child_dir.display().to_string())),
+            AutoApply::Off
+        );
+        // unrelated terrain is active
+        assert_eq!(
+            super::auto_apply(&context, &child, Some(unrelated)),
+            AutoApply::Off
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn get_auto_apply_replace() -> Result<()> {
         let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
@@ -685,6 +849,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: true,
+            reveal: false,
+            explain: false,
         };
 
         let mut terrain = Terrain::example();
@@ -712,6 +878,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: true,
+            reveal: false,
+            explain: false,
         };
 
         let mut terrain = Terrain::example();
@@ -739,6 +907,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: true,
+            reveal: false,
+            explain: false,
         };
 
         let mut terrain = Terrain::example();
@@ -766,6 +936,8 @@ Aliases:
             constructors: false,
             destructors: false,
             auto_apply: true,
+            reveal: false,
+            explain: false,
         };
 
         let mut terrain = Terrain::example();
@@ -778,4 +950,140 @@ Aliases:
 
         Ok(())
     }
+
+    #[test]
+    fn get_secret_env_with_and_without_reveal() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let secret_file = terrain_dir.path().join("token");
+        write(&secret_file, "secret-value\n")?;
+        set_permissions(&secret_file, Permissions::from_mode(0o600))?;
+
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("TOKEN", "file:token")]);
+
+        let args = |reveal: bool| GetArgs {
+            json: false,
+            biome: BiomeArg::Default,
+            aliases: false,
+            envs: false,
+            alias: vec![],
+            env: vec!["TOKEN".to_string()],
+            constructors: false,
+            destructors: false,
+            auto_apply: false,
+            reveal,
+            explain: false,
+        };
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let output = super::get(context, terrain.clone(), args(false))?;
+        assert_eq!(output, "Environment Variables:\n    TOKEN=\"file:token\"\n");
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let output = super::get(context, terrain, args(true))?;
+        assert_eq!(
+            output,
+            "Environment Variables:\n    TOKEN=\"secret-value\"\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_explain_for_default_biome() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let args = GetArgs {
+            json: false,
+            biome: BiomeArg::Default,
+            aliases: false,
+            envs: true,
+            alias: vec!["tenter".to_string(), "does_not_exist".to_string()],
+            env: vec![],
+            constructors: true,
+            destructors: false,
+            auto_apply: false,
+            reveal: false,
+            explain: true,
+        };
+
+        let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
+        let expected = r#"Environment Variables:
+    EDITOR="nvim" <- biomes.example_biome
+    ENV_VAR="overridden_env_val" <- biomes.example_biome
+    NESTED_POINTER="overridden_env_val-overridden_env_val-${NULL}" <- terrain
+    NULL_POINTER="${NULL}" <- terrain
+    PAGER="less" <- terrain
+    POINTER_ENV_VAR="overridden_env_val" <- terrain
+Aliases:
+    tenter="terrain enter --biome example_biome" <- biomes.example_biome
+    does_not_exist="!!!DOES_NOT_EXIST!!!"
+Constructors:
+    foreground:
+        `/bin/echo entering terrain` in terrain directory <- terrain
+        `/bin/echo entering biome example_biome` in terrain directory <- biomes.example_biome
+    background:
+        `/bin/bash -c ${PWD}/tests/scripts/print_num_for_10_sec` in terrain directory <- biomes.example_biome
+"#;
+
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_explain_without_selectors_explains_all() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+
+        let args = GetArgs {
+            json: false,
+            biome: BiomeArg::None,
+            aliases: false,
+            envs: false,
+            alias: vec![],
+            env: vec![],
+            constructors: false,
+            destructors: false,
+            auto_apply: false,
+            reveal: false,
+            explain: true,
+        };
+
+        let output = super::get(context, Terrain::example(), args).expect("to not throw an error");
+        let expected = r#"Environment Variables:
+    EDITOR="vim" <- terrain
+    ENV_VAR="env_val" <- terrain
+    NESTED_POINTER="env_val-env_val-${NULL}" <- terrain
+    NULL_POINTER="${NULL}" <- terrain
+    PAGER="less" <- terrain
+    POINTER_ENV_VAR="env_val" <- terrain
+Aliases:
+    tenter="terrain enter" <- terrain
+    texit="terrain exit" <- terrain
+Constructors:
+    foreground:
+        `/bin/echo entering terrain` in terrain directory <- terrain
+    background:
+Destructors:
+    foreground:
+        `/bin/echo exiting terrain` in terrain directory <- terrain
+    background:
+"#;
+
+        assert_eq!(output, expected);
+
+        Ok(())
+    }
 }
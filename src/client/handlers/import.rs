@@ -0,0 +1,167 @@
+use std::fs::write;
+use std::path::Path;
+
+use anyhow::{Context as AnyhowContext, Result};
+use toml_edit::{DocumentMut, value};
+
+use crate::client::args::ImportFormat;
+use crate::client::handlers::update::add_command;
+use crate::client::shell::Shell;
+use crate::client::types::biome::Biome;
+use crate::client::types::context::Context;
+use crate::client::types::import::Imported;
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::{ALIASES, BIOMES, CONSTRUCTORS, ENVS, FOREGROUND, TERRAIN};
+
+pub fn handle(
+    context: Context,
+    terrain_toml: DocumentMut,
+    format: ImportFormat,
+    path: &Path,
+    biome: Option<String>,
+) -> Result<()> {
+    let imported = Imported::from_file(&format, path)
+        .context(format!("failed to import {path:?} as {format:?}"))?;
+    import(&context, terrain_toml, &imported, biome)?;
+    print!("{imported}");
+    Ok(())
+}
+
+/// writes imported values to terrain.toml and validates the updated terrain
+fn import(
+    context: &Context,
+    mut terrain_toml: DocumentMut,
+    imported: &Imported,
+    biome_name: Option<String>,
+) -> Result<()> {
+    let biome = match biome_name {
+        Some(biome_name) => {
+            if terrain_toml[BIOMES].get(&biome_name).is_none() {
+                terrain_toml[BIOMES][&biome_name] = Biome::new_toml().into();
+            }
+            &mut terrain_toml[BIOMES][&biome_name]
+        }
+        None => &mut terrain_toml[TERRAIN],
+    };
+
+    imported.envs.iter().for_each(|(key, val)| {
+        biome[ENVS][key] = value(val);
+    });
+
+    imported.aliases.iter().for_each(|(key, val)| {
+        biome[ALIASES][key] = value(val);
+    });
+
+    imported.constructors.iter().for_each(|constructor| {
+        add_command(biome, CONSTRUCTORS, FOREGROUND, constructor.clone());
+    });
+
+    write(context.toml_path(), terrain_toml.to_string()).context("failed to write updated toml")?;
+    let (validated_and_fixed, _) = Terrain::get_validated_and_fixed_terrain(context)?;
+
+    context
+        .shell()
+        .generate_scripts(context, validated_and_fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, read_to_string, write};
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use toml_edit::DocumentMut;
+
+    use crate::client::args::ImportFormat;
+    use crate::client::test_utils::assertions::zsh::ExpectZSH;
+    use crate::client::types::context::Context;
+    use crate::client::types::import::Imported;
+    use crate::common::constants::{NONE, TERRAIN_TOML};
+    use crate::common::execute::MockExecutor;
+
+    #[test]
+    fn imports_into_new_biome() -> Result<()> {
+        let current_dir = tempdir()?;
+        let central_dir = tempdir()?;
+
+        let toml_path = current_dir.path().join(TERRAIN_TOML);
+        let toml = r#"name = "imported"
+auto_apply = "off"
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+"#;
+        write(&toml_path, toml)?;
+
+        let devcontainer = current_dir.path().join("devcontainer.json");
+        write(
+            &devcontainer,
+            r#"{
+    // comments are allowed in devcontainer.json
+    "name": "imported",
+    "image": "mcr.microsoft.com/devcontainers/rust:1",
+    "containerEnv": { "CARGO_HOME": "${localEnv:HOME}/.cargo" },
+    "postCreateCommand": ["cargo", "fetch"],
+}"#,
+        )?;
+        let imported = Imported::from_file(&ImportFormat::Devcontainer, &devcontainer)?;
+
+        let executor = ExpectZSH::with(MockExecutor::new(), current_dir.path())
+            .compile_terrain_script_for(NONE, central_dir.path())
+            .compile_terrain_script_for("devcontainer", central_dir.path())
+            .successfully();
+        let context = Context::build(current_dir.path(), central_dir.path(), false, executor);
+        create_dir_all(context.scripts_dir())?;
+
+        super::import(
+            &context,
+            toml.parse::<DocumentMut>()?,
+            &imported,
+            Some("devcontainer".to_string()),
+        )?;
+
+        let expected = r#"name = "imported"
+auto_apply = "off"
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+
+[biomes.devcontainer]
+
+[biomes.devcontainer.envs]
+CARGO_HOME = "${HOME}/.cargo"
+
+[biomes.devcontainer.aliases]
+
+[biomes.devcontainer.constructors]
+background = []
+
+[[biomes.devcontainer.constructors.foreground]]
+exe = "cargo"
+args = ["fetch"]
+
+[biomes.devcontainer.destructors]
+foreground = []
+background = []
+"#;
+        assert_eq!(read_to_string(&toml_path)?, expected);
+        assert_eq!(
+            imported.to_string(),
+            "imported 1 environment variable(s), 0 alias(es) and 1 constructor(s)\ncould not \
+             translate:\n    image - property has no equivalent in terrain\n"
+        );
+
+        Ok(())
+    }
+}
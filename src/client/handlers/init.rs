@@ -6,9 +6,16 @@ use anyhow::{Context as AnyhowContext, Result};
 use crate::client::handlers::edit;
 use crate::client::shell::Shell;
 use crate::client::types::context::Context;
+use crate::client::types::registry::Registration;
+use crate::client::types::template::Template;
 use crate::client::types::terrain::Terrain;
 
-pub fn handle(context: Context, example: bool, edit: bool) -> Result<()> {
+pub fn handle(
+    context: Context,
+    example: bool,
+    edit: bool,
+    template: Option<Template>,
+) -> Result<()> {
     if !exists(context.scripts_dir()).context("failed to check if scripts dir exists")? {
         create_dir_all(context.scripts_dir()).context("failed to create scripts dir")?;
     }
@@ -17,16 +24,23 @@ pub fn handle(context: Context, example: bool, edit: bool) -> Result<()> {
 
     let mut file = File::create_new(toml_path).context("error while creating new terrain.toml")?;
 
-    let mut terrain = if example {
-        Terrain::example()
+    let (mut terrain, toml_str) = if let Some(template) = template {
+        let toml_str = template
+            .to_toml(context.terrain_dir())
+            .context("failed to create terrain from template")?;
+        (template.terrain(), toml_str)
     } else {
-        Terrain::default()
+        let terrain = if example {
+            Terrain::example()
+        } else {
+            Terrain::default()
+        };
+        let toml_str = terrain
+            .to_toml(context.terrain_dir())
+            .expect("default or example terrain to be parsed to toml");
+        (terrain, toml_str)
     };
 
-    let toml_str = terrain
-        .to_toml(context.terrain_dir())
-        .expect("default or example terrain to be parsed to toml");
-
     file.write(toml_str.as_ref())
         .context("failed to write terrain in toml file")?;
 
@@ -36,6 +50,7 @@ pub fn handle(context: Context, example: bool, edit: bool) -> Result<()> {
         (terrain, _) = Terrain::get_validated_and_fixed_terrain(&context)?;
     }
 
+    Registration::register(&context, &terrain, false).context("failed to register the terrain")?;
     context.shell().generate_scripts(&context, terrain)?;
 
     Ok(())
@@ -58,6 +73,8 @@ pub mod tests {
         IN_CENTRAL_DIR, IN_CURRENT_DIR, WITH_EMPTY_TERRAIN_TOML, WITH_EXAMPLE_TERRAIN_TOML,
     };
     use crate::client::types::context::Context;
+    use crate::client::types::template::Template;
+    use crate::client::types::terrain::Terrain;
     use crate::common::constants::{EXAMPLE_BIOME, NONE, TERRAIN_TOML};
     use crate::common::execute::MockExecutor;
     use crate::common::types::command::Command;
@@ -76,7 +93,7 @@ pub mod tests {
             Context::build(current_dir.path(), central_dir.path(), false, executor);
 
         // execute
-        super::handle(context, false, false)?;
+        super::handle(context, false, false, None)?;
 
         // assertions
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
@@ -100,7 +117,7 @@ pub mod tests {
             Context::build(current_dir.path(), central_dir.path(), true, executor);
 
         // execute
-        super::handle(context, false, false)?;
+        super::handle(context, false, false, None)?;
 
         // assertions
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
@@ -123,7 +140,7 @@ pub mod tests {
         let context: Context =
             Context::build(current_dir.path(), central_dir.path(), false, executor);
 
-        super::handle(context, false, false)
+        super::handle(context, false, false, None)
             .expect("no error to be thrown when directory is not present");
 
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path()).scripts_dir_was_created();
@@ -146,7 +163,7 @@ pub mod tests {
 
         fs::remove_dir(&central_dir).expect("temp directory to be removed");
 
-        super::handle(context, false, false)
+        super::handle(context, false, false, None)
             .expect("no error to be thrown when directory is not present");
 
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
@@ -171,7 +188,8 @@ pub mod tests {
 
         fs::write(terrain_toml_path, "")?;
 
-        let err = super::handle(context, false, false).expect_err("expected error to be thrown");
+        let err =
+            super::handle(context, false, false, None).expect_err("expected error to be thrown");
 
         assert_eq!(err.to_string(), "error while creating new terrain.toml");
 
@@ -196,7 +214,7 @@ pub mod tests {
         let context: Context =
             Context::build(current_dir.path(), central_dir.path(), true, executor);
 
-        super::handle(context, true, false)?;
+        super::handle(context, true, false, None)?;
 
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
             .was_initialized(IN_CENTRAL_DIR, WITH_EXAMPLE_TERRAIN_TOML)
@@ -206,6 +224,34 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn init_creates_terrain_toml_from_template() -> Result<()> {
+        let current_dir = tempdir()?;
+        let central_dir = tempdir()?;
+
+        let executor = ExpectZSH::with(MockExecutor::new(), current_dir.path())
+            .compile_terrain_script_for(NONE, central_dir.path())
+            .successfully();
+
+        let context: Context =
+            Context::build(current_dir.path(), central_dir.path(), false, executor);
+
+        let template = Template::get(Path::new(""), "rust")?;
+        super::handle(context, false, false, Some(template.clone()))?;
+
+        let toml = fs::read_to_string(current_dir.path().join(TERRAIN_TOML))?;
+        assert!(toml.starts_with("# created by `terrain init` from template 'rust'\n"));
+        assert_eq!(
+            Terrain::from_toml(toml)?.terrain(),
+            template.terrain().terrain()
+        );
+
+        AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
+            .script_was_created_for(NONE);
+
+        Ok(())
+    }
+
     #[test]
     fn init_throws_error_if_terrain_toml_exists_in_central_dir() -> Result<()> {
         let current_dir = tempdir()?;
@@ -222,7 +268,8 @@ pub mod tests {
 
         fs::write(terrain_toml_path, "")?;
 
-        let err = super::handle(context, false, false).expect_err("expected error to be thrown");
+        let err =
+            super::handle(context, false, false, None).expect_err("expected error to be thrown");
 
         assert_eq!(err.to_string(), "error while creating new terrain.toml");
 
@@ -243,7 +290,7 @@ pub mod tests {
         let context: Context =
             Context::build(current_dir.path(), central_dir.path(), false, executor);
 
-        super::handle(context, true, false)?;
+        super::handle(context, true, false, None)?;
 
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
             .was_initialized(IN_CURRENT_DIR, WITH_EXAMPLE_TERRAIN_TOML)
@@ -287,7 +334,7 @@ pub mod tests {
             Context::build(current_dir.path(), central_dir.path(), false, executor);
 
         // execute
-        super::handle(context, false, true)?;
+        super::handle(context, false, true, None)?;
 
         // assertions
         AssertTerrain::with_dirs(current_dir.path(), central_dir.path())
@@ -331,7 +378,7 @@ pub mod tests {
             Context::build(terrain_dir.path(), central_dir.path(), true, executor);
 
         // execute
-        super::handle(context, false, true)?;
+        super::handle(context, false, true, None)?;
 
         // assertions
         AssertTerrain::with_dirs(terrain_dir.path(), central_dir.path())
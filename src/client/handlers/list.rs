@@ -0,0 +1,132 @@
+use std::path::Path;
+
+use anyhow::{Context as AnyhowContext, Result};
+
+use crate::client::types::context::Context;
+use crate::client::types::registry::Registration;
+
+pub fn handle(home_dir: &Path, json: bool, stale: bool, prune: bool) -> Result<()> {
+    let output = list(&Context::terrains_dir(home_dir), json, stale, prune)?;
+    print!("{output}");
+    Ok(())
+}
+
+fn list(terrains_dir: &Path, json: bool, stale: bool, prune: bool) -> Result<String> {
+    let registrations: Vec<Registration> = Registration::all(terrains_dir)?
+        .into_iter()
+        .filter(|registration| !(stale || prune) || registration.is_stale())
+        .collect();
+
+    if prune {
+        return registrations
+            .iter()
+            .map(|registration| {
+                registration.prune()?;
+                Ok(format!(
+                    "removed terrain '{}' of {:?}\n",
+                    registration.name(),
+                    registration.terrain_dir()
+                ))
+            })
+            .collect::<Result<String>>();
+    }
+
+    if json {
+        return serde_json::to_string_pretty(&registrations)
+            .context("failed to convert terrains to json");
+    }
+
+    if registrations.is_empty() {
+        return Ok(if stale {
+            "no stale terrains found\n".to_string()
+        } else {
+            "no terrains found\n".to_string()
+        });
+    }
+
+    Ok(registrations
+        .iter()
+        .map(|registration| registration.to_string())
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use serde_json::Value;
+    use tempfile::tempdir;
+
+    use crate::client::types::context::Context;
+    use crate::client::types::registry::{REGISTRY_ENTRY, Registration};
+    use crate::client::types::terrain::Terrain;
+    use crate::common::constants::{TERRAIN_TOML, TEST_TIMESTAMP};
+    use crate::common::execute::MockExecutor;
+
+    #[test]
+    fn lists_prunes_stale_terrains() -> Result<()> {
+        let terrains_dir = tempdir()?;
+        let project_dir = tempdir()?;
+
+        let local_dir = project_dir.path().join("local");
+        let local_central_dir = terrains_dir.path().join("local");
+        create_dir_all(&local_dir)?;
+        write(local_dir.join(TERRAIN_TOML), "")?;
+        let context = Context::build(&local_dir, &local_central_dir, false, MockExecutor::new());
+        Registration::register(&context, &Terrain::example(), true)?;
+
+        let removed_dir = project_dir.path().join("removed");
+        let removed_central_dir = terrains_dir.path().join("removed");
+        create_dir_all(&removed_central_dir)?;
+        write(removed_central_dir.join(TERRAIN_TOML), "")?;
+        let context = Context::build(
+            &removed_dir,
+            &removed_central_dir,
+            true,
+            MockExecutor::new(),
+        );
+        Registration::register(&context, &Terrain::default(), false)?;
+
+        // central directory without registry entry is not listed
+        create_dir_all(terrains_dir.path().join("unregistered"))?;
+
+        let listed: Value =
+            serde_json::from_str(&super::list(terrains_dir.path(), true, false, false)?)?;
+        let listed = listed.as_array().expect("terrains to be listed");
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0]["terrain_dir"], local_dir.display().to_string());
+        assert_eq!(listed[0]["central"], false);
+        assert_eq!(listed[0]["default_biome"], "example_biome");
+        assert_eq!(listed[0]["last_entered"], TEST_TIMESTAMP);
+        assert_eq!(listed[1]["terrain_dir"], removed_dir.display().to_string());
+        assert_eq!(listed[1]["central"], true);
+        assert_eq!(listed[1]["last_entered"], Value::Null);
+
+        let stale: Value =
+            serde_json::from_str(&super::list(terrains_dir.path(), true, true, false)?)?;
+        let stale = stale.as_array().expect("stale terrains to be listed");
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0]["terrain_dir"], removed_dir.display().to_string());
+
+        let pruned = super::list(terrains_dir.path(), false, false, true)?;
+        assert_eq!(
+            pruned,
+            format!(
+                "removed terrain '{}' of {removed_dir:?}\n",
+                Terrain::default().name()
+            )
+        );
+        assert!(!removed_central_dir.exists());
+        assert!(local_central_dir.join(REGISTRY_ENTRY).exists());
+
+        assert_eq!(
+            super::list(terrains_dir.path(), false, true, false)?,
+            "no stale terrains found\n"
+        );
+
+        Ok(())
+    }
+}
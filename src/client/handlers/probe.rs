@@ -0,0 +1,271 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::client::types::config::Config;
+use crate::client::types::context::get_terrain_dir;
+use crate::client::types::probe::Probe;
+use crate::client::types::terrain::AutoApply;
+
+pub fn handle(
+    home_dir: &Path,
+    current_dir: &Path,
+    active_terrain_dir: Option<String>,
+    default_biome: bool,
+) -> Result<()> {
+    let config = Config::from_file().unwrap_or_default();
+    let output = probe(
+        home_dir,
+        current_dir,
+        &config,
+        active_terrain_dir,
+        default_biome,
+    )?;
+    print!("{output}");
+    Ok(())
+}
+
+/// same as `get --auto-apply` but reads cached values of terrain.toml
+/// instead of parsing and validating whole terrain
+fn probe(
+    home_dir: &Path,
+    current_dir: &Path,
+    config: &Config,
+    active_terrain_dir: Option<String>,
+    default_biome: bool,
+) -> Result<String> {
+    let Some((terrain_dir, toml_path)) = get_terrain_dir(home_dir, current_dir) else {
+        if default_biome {
+            return Ok(String::new());
+        }
+        return Ok(AutoApply::Off.to_string());
+    };
+
+    let probe =
+        Probe::get(home_dir, &terrain_dir, &toml_path).context("failed to probe the terrain")?;
+
+    if default_biome {
+        return Ok(probe.default_biome().cloned().unwrap_or_default());
+    }
+
+    if !config.auto_apply() {
+        return Ok(AutoApply::default().to_string());
+    }
+
+    let active_terrain_dir = active_terrain_dir.as_deref();
+    // parent terrains are only probed when they are required
+    let parent_dirs = if active_terrain_dir.is_some_and(|active| !active.is_empty()) {
+        probe.parent_dirs(home_dir, &terrain_dir)?
+    } else {
+        vec![]
+    };
+
+    Ok(probe
+        .auto_apply()
+        .with_active_terrain(active_terrain_dir, &parent_dirs)
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, read_to_string, write};
+    use std::path::Path;
+    use std::time::Instant;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use super::probe;
+    use crate::client::types::config::Config;
+    use crate::client::types::context::get_central_dir_location;
+    use crate::client::types::probe::{PROBE_CACHE, Probe};
+    use crate::client::types::terrain::Terrain;
+    use crate::common::constants::TERRAIN_TOML;
+
+    fn write_terrain(dir: &Path, auto_apply: &str, inherit_parent: bool) -> Result<()> {
+        write(
+            dir.join(TERRAIN_TOML),
+            format!(
+                "auto_apply = \"{auto_apply}\"\ninherit_parent = {inherit_parent}\n\
+                 default_biome = \"example_biome\"\n\n[terrain]\n\n\
+                 [biomes.example_biome]\n"
+            ),
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn probe_caches_auto_apply() -> Result<()> {
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        write_terrain(terrain_dir.path(), "enabled", false)?;
+
+        let central_dir = get_central_dir_location(home_dir.path(), terrain_dir.path());
+        create_dir_all(&central_dir)?;
+
+        let config = Config::default();
+        let auto_apply = probe(home_dir.path(), terrain_dir.path(), &config, None, false)?;
+        assert_eq!(auto_apply, "enabled");
+
+        let cache_path = central_dir.join(PROBE_CACHE);
+        assert!(read_to_string(&cache_path)?.contains("\"auto_apply\":\"enabled\""));
+
+        let default_biome = probe(home_dir.path(), terrain_dir.path(), &config, None, true)?;
+        assert_eq!(default_biome, "example_biome");
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_does_not_create_central_dir() -> Result<()> {
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        write_terrain(terrain_dir.path(), "enabled", false)?;
+
+        let auto_apply = probe(
+            home_dir.path(),
+            terrain_dir.path(),
+            &Config::default(),
+            None,
+            false,
+        )?;
+        assert_eq!(auto_apply, "enabled");
+        assert!(!get_central_dir_location(home_dir.path(), terrain_dir.path()).exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_invalidates_cache_when_terrain_is_modified() -> Result<()> {
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        write_terrain(terrain_dir.path(), "enabled", false)?;
+
+        let config = Config::default();
+        assert_eq!(
+            probe(home_dir.path(), terrain_dir.path(), &config, None, false)?,
+            "enabled"
+        );
+
+        write_terrain(terrain_dir.path(), "all", false)?;
+        assert_eq!(
+            probe(home_dir.path(), terrain_dir.path(), &config, None, false)?,
+            "all"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_returns_off_when_auto_apply_is_disabled_in_config() -> Result<()> {
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        write_terrain(terrain_dir.path(), "all", false)?;
+
+        let auto_apply = probe(
+            home_dir.path(),
+            terrain_dir.path(),
+            &Config::auto_apply_off(),
+            None,
+            false,
+        )?;
+        assert_eq!(auto_apply, "off");
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_nested_terrain_when_parent_is_active() -> Result<()> {
+        let home_dir = tempdir()?;
+        let parent_dir = tempdir()?;
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir)?;
+        write_terrain(parent_dir.path(), "all", false)?;
+        write_terrain(&child_dir, "all", true)?;
+
+        let config = Config::default();
+        let active = Some(parent_dir.path().to_string_lossy().to_string());
+
+        // child inheriting active terrain is applied without replacing the shell
+        assert_eq!(
+            probe(home_dir.path(), &child_dir, &config, active.clone(), false)?,
+            "background"
+        );
+
+        // moving back to active terrain does not apply anything
+        assert_eq!(
+            probe(home_dir.path(), parent_dir.path(), &config, active, false)?,
+            "off"
+        );
+
+        // child does not inherit active terrain
+        write_terrain(&child_dir, "all", false)?;
+        assert_eq!(
+            probe(
+                home_dir.path(),
+                &child_dir,
+                &config,
+                Some(parent_dir.path().to_string_lossy().to_string()),
+                false
+            )?,
+            "off"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_without_terrain() -> Result<()> {
+        let home_dir = tempdir()?;
+        let current_dir = tempdir()?;
+
+        let config = Config::default();
+        assert_eq!(
+            probe(home_dir.path(), current_dir.path(), &config, None, false)?,
+            "off"
+        );
+        assert_eq!(
+            probe(home_dir.path(), current_dir.path(), &config, None, true)?,
+            ""
+        );
+
+        Ok(())
+    }
+
+    /// compares full parse and validation done by `get --auto-apply`
+    /// against cached probe, run with `cargo test -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn benchmark_probe_against_validation() -> Result<()> {
+        const ITERATIONS: u32 = 1000;
+
+        let home_dir = tempdir()?;
+        let terrain_dir = tempdir()?;
+        std::fs::copy(
+            "./tests/data/terrain.example.auto_apply.enabled.toml",
+            terrain_dir.path().join(TERRAIN_TOML),
+        )?;
+        let toml_path = terrain_dir.path().join(TERRAIN_TOML);
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            let terrain = Terrain::from_toml(read_to_string(&toml_path)?)?;
+            let _ = terrain.validate(terrain_dir.path());
+        }
+        let validation = start.elapsed();
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            Probe::get(home_dir.path(), terrain_dir.path(), &toml_path)?;
+        }
+        let cached = start.elapsed();
+
+        println!(
+            "{ITERATIONS} iterations: validation {validation:?}, probe {cached:?} ({:.1}x faster)",
+            validation.as_secs_f64() / cached.as_secs_f64()
+        );
+        assert!(cached < validation);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+use std::time::Duration;
+
+use anyhow::{Context as AnyhowContext, Result};
+use serde::Serialize;
+
+use crate::client::handlers::status::terrain_state;
+#[mockall_double::double]
+use crate::client::types::client::Client;
+use crate::common::constants::{TERRAIN_NAME, TERRAIN_SELECTED_BIOME, TERRAIN_SESSION_ID};
+
+/// segments of the prompt for active terrain
+#[derive(Debug, PartialEq, Serialize)]
+struct Prompt {
+    name: String,
+    biome: String,
+    session_id: String,
+    /// background constructors that are starting or running, not set if
+    /// daemon was not queried or did not respond in time
+    running: Option<usize>,
+    /// background constructors that have failed, not set if daemon was not
+    /// queried or did not respond in time
+    failed: Option<usize>,
+}
+
+pub async fn handle(
+    format: String,
+    json: bool,
+    no_daemon: bool,
+    timeout: u64,
+    client: Option<Client>,
+) -> Result<()> {
+    // prints nothing when terrain is not active so prompt is left unchanged
+    let Some(mut prompt) = Prompt::from_env() else {
+        return Ok(());
+    };
+
+    if !no_daemon {
+        prompt
+            .with_health(Duration::from_millis(timeout), client)
+            .await;
+    }
+
+    let output = if json {
+        serde_json::to_string(&prompt).context("failed to serialize prompt")?
+    } else {
+        prompt.render(&format)
+    };
+    println!("{output}");
+
+    Ok(())
+}
+
+impl Prompt {
+    /// reads the `TERRAIN_*` variables set in active session
+    fn from_env() -> Option<Self> {
+        let session_id = std::env::var(TERRAIN_SESSION_ID)
+            .ok()
+            .filter(|session_id| !session_id.is_empty())?;
+        Some(Self::new(
+            std::env::var(TERRAIN_NAME).unwrap_or_default(),
+            std::env::var(TERRAIN_SELECTED_BIOME).unwrap_or_default(),
+            session_id,
+        ))
+    }
+
+    fn new(name: String, biome: String, session_id: String) -> Self {
+        Self {
+            name,
+            biome,
+            session_id,
+            running: None,
+            failed: None,
+        }
+    }
+
+    /// queries daemon for status of background constructors, errors and slow
+    /// responses are ignored so prompt is never blocked longer than `timeout`
+    async fn with_health(&mut self, timeout: Duration, client: Option<Client>) {
+        let state = tokio::time::timeout(
+            timeout,
+            terrain_state(
+                self.name.clone(),
+                Some(self.session_id.clone()),
+                None,
+                client,
+            ),
+        )
+        .await;
+
+        if let Ok(Ok(state)) = state {
+            let (running, failed) = state.constructors_health();
+            self.running = Some(running);
+            self.failed = Some(failed);
+        }
+    }
+
+    /// `●N` for running and `✗N` for failed constructors, segments with zero
+    /// commands or unknown health are omitted
+    fn health(&self) -> String {
+        let mut health = String::new();
+        if let Some(running) = self.running.filter(|running| *running > 0) {
+            health += &format!(" ●{running}");
+        }
+        if let Some(failed) = self.failed.filter(|failed| *failed > 0) {
+            health += &format!(" ✗{failed}");
+        }
+        health
+    }
+
+    fn render(&self, format: &str) -> String {
+        let count = |count: Option<usize>| count.map(|c| c.to_string()).unwrap_or_default();
+        format
+            .replace("{name}", &self.name)
+            .replace("{biome}", &self.biome)
+            .replace("{session_id}", &self.session_id)
+            .replace("{running}", &count(self.running))
+            .replace("{failed}", &count(self.failed))
+            .replace("{health}", &self.health())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::Prompt;
+    use crate::client::test_utils::assertions::client::ExpectClient;
+    use crate::client::types::client::MockClient;
+    use crate::client::types::proto::{ProtoRequest, ProtoResponse};
+    use crate::client::types::terrain::AutoApply;
+    use crate::common::constants::EXAMPLE_BIOME;
+    use crate::common::test_utils;
+    use crate::common::test_utils::{RequestFor, TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::terrain_state::test_utils::{
+        terrain_state_after_activate, terrain_state_after_construct_failed,
+    };
+
+    const DEFAULT_FORMAT: &str = "{name}({biome}){health}";
+
+    fn active_prompt() -> Prompt {
+        Prompt::new(
+            TEST_TERRAIN_NAME.to_string(),
+            EXAMPLE_BIOME.to_string(),
+            TEST_SESSION_ID.to_string(),
+        )
+    }
+
+    fn expect_status(response: ProtoResponse) -> MockClient {
+        ExpectClient::send(ProtoRequest::Status(test_utils::expected_status_request(
+            RequestFor::SessionId(TEST_SESSION_ID.to_string()),
+            "",
+        )))
+        .with_expected_response(response)
+        .successfully()
+    }
+
+    #[test]
+    fn renders_format() {
+        let mut prompt = active_prompt();
+        assert_eq!(prompt.render(DEFAULT_FORMAT), "terrainium(example_biome)");
+
+        prompt.running = Some(2);
+        prompt.failed = Some(1);
+        assert_eq!(
+            prompt.render(DEFAULT_FORMAT),
+            "terrainium(example_biome) ●2 ✗1"
+        );
+        assert_eq!(
+            prompt.render("[{name}:{biome}] {running}/{failed} {session_id}"),
+            "[terrainium:example_biome] 2/1 session_id"
+        );
+
+        prompt.running = Some(0);
+        assert_eq!(
+            prompt.render(DEFAULT_FORMAT),
+            "terrainium(example_biome) ✗1"
+        );
+    }
+
+    #[tokio::test]
+    async fn adds_health_of_background_constructors() {
+        let state =
+            terrain_state_after_activate(TEST_SESSION_ID.to_string(), false, &AutoApply::default());
+        let client = expect_status(ProtoResponse::Status(Box::new(state.into())));
+
+        let mut prompt = active_prompt();
+        prompt
+            .with_health(Duration::from_secs(1), Some(client))
+            .await;
+        assert_eq!(prompt.running, Some(1));
+        assert_eq!(prompt.failed, Some(0));
+        assert_eq!(
+            prompt.render(DEFAULT_FORMAT),
+            "terrainium(example_biome) ●1"
+        );
+
+        let state = terrain_state_after_construct_failed(
+            TEST_SESSION_ID.to_string(),
+            false,
+            &AutoApply::default(),
+        );
+        let client = expect_status(ProtoResponse::Status(Box::new(state.into())));
+
+        let mut prompt = active_prompt();
+        prompt
+            .with_health(Duration::from_secs(1), Some(client))
+            .await;
+        assert_eq!(
+            prompt.render(DEFAULT_FORMAT),
+            "terrainium(example_biome) ✗1"
+        );
+    }
+
+    #[tokio::test]
+    async fn ignores_daemon_errors() {
+        let client = ExpectClient::send(ProtoRequest::Status(test_utils::expected_status_request(
+            RequestFor::SessionId(TEST_SESSION_ID.to_string()),
+            "",
+        )))
+        .with_returning_error("daemon is not running");
+
+        let mut prompt = active_prompt();
+        prompt
+            .with_health(Duration::from_secs(1), Some(client))
+            .await;
+        assert_eq!(prompt.running, None);
+        assert_eq!(prompt.failed, None);
+        assert_eq!(
+            serde_json::to_string(&prompt).unwrap(),
+            r#"{"name":"terrainium","biome":"example_biome","session_id":"session_id","running":null,"failed":null}"#
+        );
+    }
+}
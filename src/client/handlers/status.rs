@@ -4,6 +4,7 @@ use anyhow::{Context as AnyhowContext, Result, bail};
 use crate::client::types::client::Client;
 use crate::client::types::proto::{ProtoRequest, ProtoResponse};
 use crate::common::constants::TERRAIN_SESSION_ID;
+use crate::common::types::daemon_status::DaemonStatus;
 use crate::common::types::paths::get_terrainiumd_paths;
 use crate::common::types::pb;
 use crate::common::types::terrain_state::TerrainState;
@@ -15,6 +16,24 @@ pub async fn handle(
     recent: Option<u32>,
     client: Option<Client>,
 ) -> Result<()> {
+    let status = terrain_state(terrain_name, session_id, recent, client).await?;
+    let status = if json {
+        serde_json::to_string_pretty(&status).context("failed to serialize status")?
+    } else {
+        format!("{status}")
+    };
+    println!("{status}");
+
+    Ok(())
+}
+
+/// fetches the state of the terrain session from daemon
+pub(crate) async fn terrain_state(
+    terrain_name: String,
+    session_id: Option<String>,
+    recent: Option<u32>,
+    client: Option<Client>,
+) -> Result<TerrainState> {
     let mut client = if let Some(client) = client {
         client
     } else {
@@ -30,18 +49,27 @@ pub async fn handle(
         .await?;
 
     if let ProtoResponse::Status(status) = response {
-        let status: TerrainState = status.try_into().context("failed to convert status")?;
-        let status = if json {
-            serde_json::to_string_pretty(&status).context("failed to serialize status")?
-        } else {
-            format!("{status}")
-        };
-        println!("{status}");
+        status.try_into().context("failed to convert status")
     } else {
         bail!("invalid status response from daemon");
     }
+}
 
-    Ok(())
+/// fetches version, uptime and number of tracked sessions and commands from daemon
+pub async fn daemon_status(client: Option<Client>) -> Result<DaemonStatus> {
+    let mut client = if let Some(client) = client {
+        client
+    } else {
+        Client::new(get_terrainiumd_paths().socket()).await?
+    };
+
+    let response = client.request(ProtoRequest::Ping(pb::Ping {})).await?;
+
+    if let ProtoResponse::Pong(pong) = response {
+        Ok(pong.into())
+    } else {
+        bail!("invalid ping response from daemon");
+    }
 }
 
 fn status(
@@ -88,6 +116,7 @@ mod tests {
     use crate::common::test_utils::{
         RequestFor, TEST_SESSION_ID, TEST_TERRAIN_NAME, expected_env_vars_example_biome,
     };
+    use crate::common::types::daemon_status::DaemonStatus;
     use crate::common::types::pb;
 
     fn expected_status_response(
@@ -107,6 +136,7 @@ mod tests {
             envs: expected_env_vars_example_biome(),
             constructors: Default::default(),
             destructors: Default::default(),
+            biome_switches: vec![],
         }
     }
 
@@ -279,4 +309,30 @@ mod tests {
 
         assert_eq!(error, "invalid status response from daemon");
     }
+
+    #[tokio::test]
+    async fn returns_daemon_status() {
+        let pong = pb::Pong {
+            version: "v0.1.0-release+hash".to_string(),
+            uptime_seconds: 65,
+            pid: 42,
+            state_dir: "/tmp/terrainiumd".to_string(),
+            sessions: 2,
+            running_commands: 1,
+        };
+        let client = ExpectClient::send(ProtoRequest::Ping(pb::Ping {}))
+            .with_expected_response(ProtoResponse::Pong(pong.clone()))
+            .successfully();
+
+        let status = super::daemon_status(Some(client)).await.unwrap();
+        assert_eq!(status, DaemonStatus::from(pong));
+        assert_eq!(status.version(), "v0.1.0-release+hash");
+        assert_eq!(status.pid(), 42);
+
+        let client = ExpectClient::send(ProtoRequest::Ping(pb::Ping {}))
+            .with_expected_response(ProtoResponse::Success)
+            .successfully();
+        let error = super::daemon_status(Some(client)).await.unwrap_err();
+        assert_eq!(error.to_string(), "invalid ping response from daemon");
+    }
 }
@@ -0,0 +1,387 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::str::FromStr;
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+
+use crate::client::args::BiomeArg;
+use crate::client::handlers::background::{execute_request, send_or_record};
+use crate::client::handlers::exit::should_run_destructor;
+#[mockall_double::double]
+use crate::client::types::client::Client;
+use crate::client::types::context::Context;
+use crate::client::types::environment::Environment;
+use crate::client::types::export::shell_quote;
+use crate::client::types::proto::ProtoRequest;
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::{TERRAIN_AUTO_APPLY, TERRAIN_SELECTED_BIOME, TERRAIN_SWITCH};
+use crate::common::types::pb;
+use crate::common::utils::timestamp;
+
+/// informs daemon about the biome switch and runs background commands of both biomes
+///
+/// foreground commands, environment variables and aliases are handled by the shell
+/// integration after this command succeeds
+pub async fn handle(
+    context: Context,
+    terrain: Terrain,
+    biome: BiomeArg,
+    client: Option<Client>,
+) -> Result<()> {
+    let session_id = context.session_id();
+    let selected_biome = env::var(TERRAIN_SELECTED_BIOME).ok();
+
+    let (Some(session_id), Some(selected_biome)) = (session_id, selected_biome) else {
+        bail!("no active terrain found, use 'terrain enter' command to activate a terrain.");
+    };
+
+    let switch_file = context
+        .scripts_dir()
+        .join(format!("{TERRAIN_SWITCH}.{session_id}"));
+    let (request, secrets) = switch(&context, &terrain, session_id, selected_biome, biome)?;
+    let script = switch_script(&request.to_biome, &secrets);
+
+    send_or_record(&context, ProtoRequest::Switch(request), client).await?;
+
+    // file contains secrets so it is only readable by the user
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(switch_file)
+        .and_then(|mut file| file.write_all(script.as_bytes()))
+        .context("failed to store the biome to switch")
+}
+
+/// script sourced by shell integration to switch the biome
+///
+/// shell integration loads the resolved biome, as biome argument can be an alias,
+/// and exports the secrets as they are not part of the generated scripts
+fn switch_script(to_biome: &str, secrets: &BTreeMap<String, String>) -> String {
+    let secrets = secrets
+        .iter()
+        .map(|(k, v)| format!("    {} {}\n", shell_quote(k), shell_quote(v)))
+        .collect::<String>();
+    format!(
+        "__terrainium_switch_biome={}\n__terrainium_switch_secrets=(\n{secrets})\n",
+        shell_quote(to_biome)
+    )
+}
+
+fn switch(
+    context: &Context,
+    terrain: &Terrain,
+    session_id: String,
+    selected_biome: String,
+    biome: BiomeArg,
+) -> Result<(pb::Switch, BTreeMap<String, String>)> {
+    let from = Environment::from(
+        terrain,
+        BiomeArg::from_str(&selected_biome)?,
+        context.terrain_dir(),
+    )
+    .context("failed to generate environment for current biome")?;
+    let mut to = Environment::from(terrain, biome, context.terrain_dir())
+        .context("failed to generate environment for biome to switch")?;
+
+    if from.selected_biome() == to.selected_biome() {
+        bail!("biome '{}' is already active", to.selected_biome());
+    }
+
+    to.resolve_secrets(context.executor(), context.terrain_dir())?;
+    let secrets = to.resolved_secrets().clone();
+
+    // recorded by daemon so session state matches the switched biome
+    let is_auto_apply = env::var(TERRAIN_AUTO_APPLY).is_ok_and(|a| !a.is_empty());
+    let mut envs = to.exports();
+    envs.extend(to.activation_env_vars(session_id.clone(), context.terrain_dir(), is_auto_apply));
+
+    let timestamp = timestamp();
+    let from_biome = from.selected_biome().to_string();
+    let to_biome = to.selected_biome().to_string();
+
+    // background commands are run by the same rules as `terrain exit`
+    let (destructors, constructors) = if should_run_destructor() {
+        (
+            execute_request(context, from, false, timestamp.clone())?,
+            execute_request(context, to, true, timestamp.clone())?,
+        )
+    } else {
+        (None, None)
+    };
+
+    let request = pb::Switch {
+        session_id,
+        terrain_name: terrain.name().to_string(),
+        from_biome,
+        to_biome,
+        timestamp,
+        destructors,
+        constructors,
+        envs,
+    };
+    Ok((request, secrets))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env::VarError;
+    use std::fs::{Permissions, create_dir_all, metadata, read_to_string, set_permissions, write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::str::FromStr;
+
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+    use tempfile::tempdir;
+
+    use crate::client::args::BiomeArg;
+    use crate::client::test_utils::assertions::client::ExpectClient;
+    use crate::client::test_utils::{restore_env_var, set_env_var};
+    use crate::client::types::client::MockClient;
+    use crate::client::types::context::Context;
+    use crate::client::types::proto::{ProtoRequest, ProtoResponse};
+    use crate::client::types::terrain::{AutoApply, Terrain};
+    use crate::common::constants::{
+        EXAMPLE_BIOME, NONE, TERRAIN_SELECTED_BIOME, TERRAIN_SWITCH, TEST_TIMESTAMP,
+    };
+    use crate::common::execute::MockExecutor;
+    use crate::common::test_utils::{
+        TEST_SESSION_ID, TEST_TERRAIN_DIR, TEST_TERRAIN_NAME, expected_activation_env_vars,
+        expected_env_vars_none, expected_execute_request_example_biome,
+    };
+    use crate::common::types::pb;
+
+    #[tokio::test]
+    async fn should_throw_an_error_if_terrain_session_id_not_set() {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::default());
+
+        let actual_error = super::handle(
+            context,
+            Terrain::example(),
+            BiomeArg::from_str(NONE).unwrap(),
+            None,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+
+        assert_eq!(
+            actual_error,
+            "no active terrain found, use 'terrain enter' command to activate a terrain."
+        );
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn should_throw_an_error_if_biome_is_already_active() {
+        let selected_biome: std::result::Result<String, VarError>;
+        unsafe {
+            selected_biome = set_env_var(TERRAIN_SELECTED_BIOME, Some(EXAMPLE_BIOME));
+        }
+
+        let context = Context::build(
+            Path::new(TEST_TERRAIN_DIR),
+            Path::new(""),
+            false,
+            MockExecutor::default(),
+        )
+        .set_session_id(TEST_SESSION_ID);
+
+        let actual_error = super::handle(
+            context,
+            Terrain::example(),
+            BiomeArg::from_str(EXAMPLE_BIOME).unwrap(),
+            None,
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+
+        assert_eq!(actual_error, "biome 'example_biome' is already active");
+
+        unsafe {
+            restore_env_var(TERRAIN_SELECTED_BIOME, selected_biome);
+        }
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn send_request_to_switch_from_example_biome_to_none() {
+        let selected_biome: std::result::Result<String, VarError>;
+        unsafe {
+            selected_biome = set_env_var(TERRAIN_SELECTED_BIOME, Some(EXAMPLE_BIOME));
+        }
+
+        let mut envs = expected_env_vars_none();
+        envs.append(&mut expected_activation_env_vars(
+            NONE,
+            false,
+            &AutoApply::Off,
+            TEST_TERRAIN_DIR,
+        ));
+        let client = ExpectClient::send(ProtoRequest::Switch(pb::Switch {
+            session_id: TEST_SESSION_ID.to_string(),
+            terrain_name: TEST_TERRAIN_NAME.to_string(),
+            from_biome: EXAMPLE_BIOME.to_string(),
+            to_biome: NONE.to_string(),
+            timestamp: TEST_TIMESTAMP.to_string(),
+            destructors: Some(expected_execute_request_example_biome(
+                Some(TEST_SESSION_ID.to_string()),
+                false,
+            )),
+            constructors: None,
+            envs,
+        }))
+        .successfully();
+
+        let central_dir = tempdir().unwrap();
+        let context = Context::build(
+            Path::new(TEST_TERRAIN_DIR),
+            central_dir.path(),
+            false,
+            MockExecutor::default(),
+        )
+        .set_session_id(TEST_SESSION_ID);
+        create_dir_all(context.scripts_dir()).unwrap();
+
+        super::handle(
+            context,
+            Terrain::example(),
+            BiomeArg::from_str(NONE).unwrap(),
+            Some(client),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            read_switch_file(central_dir.path()),
+            "__terrainium_switch_biome=none\n__terrainium_switch_secrets=(\n)\n"
+        );
+
+        unsafe {
+            restore_env_var(TERRAIN_SELECTED_BIOME, selected_biome);
+        }
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn stores_resolved_biome_for_default_alias() {
+        let selected_biome: std::result::Result<String, VarError>;
+        unsafe {
+            selected_biome = set_env_var(TERRAIN_SELECTED_BIOME, Some(NONE));
+        }
+
+        let mut client = MockClient::default();
+        client
+            .expect_request()
+            .withf(|request| {
+                matches!(request, ProtoRequest::Switch(switch) if switch.to_biome == EXAMPLE_BIOME)
+            })
+            .return_once(|_| Ok(ProtoResponse::Success));
+
+        let central_dir = tempdir().unwrap();
+        let context = Context::build(
+            Path::new(TEST_TERRAIN_DIR),
+            central_dir.path(),
+            false,
+            MockExecutor::default(),
+        )
+        .set_session_id(TEST_SESSION_ID);
+        create_dir_all(context.scripts_dir()).unwrap();
+
+        super::handle(
+            context,
+            Terrain::example(),
+            BiomeArg::from_str("__default__").unwrap(),
+            Some(client),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            read_switch_file(central_dir.path()),
+            "__terrainium_switch_biome=example_biome\n__terrainium_switch_secrets=(\n)\n"
+        );
+
+        unsafe {
+            restore_env_var(TERRAIN_SELECTED_BIOME, selected_biome);
+        }
+    }
+
+    #[serial]
+    #[tokio::test]
+    async fn passes_secrets_of_switched_biome_to_shell() {
+        let selected_biome: std::result::Result<String, VarError>;
+        unsafe {
+            selected_biome = set_env_var(TERRAIN_SELECTED_BIOME, Some(EXAMPLE_BIOME));
+        }
+
+        let terrain_dir = tempdir().unwrap();
+        let secret_file = terrain_dir.path().join("token");
+        write(&secret_file, "it's secret\n").unwrap();
+        set_permissions(&secret_file, Permissions::from_mode(0o600)).unwrap();
+
+        let mut terrain = Terrain::example();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("TOKEN", "file:token")]);
+
+        let mut client = MockClient::default();
+        client
+            .expect_request()
+            .withf(|request| {
+                matches!(request, ProtoRequest::Switch(switch) if switch.to_biome == NONE)
+            })
+            .return_once(|_| Ok(ProtoResponse::Success));
+
+        let central_dir = tempdir().unwrap();
+        let context = Context::build(
+            terrain_dir.path(),
+            central_dir.path(),
+            false,
+            MockExecutor::default(),
+        )
+        .set_session_id(TEST_SESSION_ID);
+        create_dir_all(context.scripts_dir()).unwrap();
+
+        super::handle(
+            context,
+            terrain,
+            BiomeArg::from_str(NONE).unwrap(),
+            Some(client),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            read_switch_file(central_dir.path()),
+            "__terrainium_switch_biome=none\n__terrainium_switch_secrets=(\n    TOKEN 'it'\\''s secret'\n)\n"
+        );
+        let switch_file = central_dir
+            .path()
+            .join("scripts")
+            .join(format!("{TERRAIN_SWITCH}.{TEST_SESSION_ID}"));
+        assert_eq!(
+            metadata(switch_file).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        unsafe {
+            restore_env_var(TERRAIN_SELECTED_BIOME, selected_biome);
+        }
+    }
+
+    fn read_switch_file(central_dir: &Path) -> String {
+        read_to_string(
+            central_dir
+                .join("scripts")
+                .join(format!("{TERRAIN_SWITCH}.{TEST_SESSION_ID}")),
+        )
+        .unwrap()
+    }
+}
@@ -1,14 +1,18 @@
 use std::fs::{copy, write};
 
 use anyhow::{Context as AnyhowContext, Result, bail};
-use toml_edit::{DocumentMut, value};
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Table, Value, value};
 
 use crate::client::args::UpdateArgs;
 use crate::client::shell::Shell;
 use crate::client::types::biome::Biome;
 use crate::client::types::context::Context;
 use crate::client::types::terrain::Terrain;
-use crate::common::constants::{ALIASES, AUTO_APPLY, BIOMES, DEFAULT_BIOME, ENVS, NONE, TERRAIN};
+use crate::common::constants::{
+    ALIASES, AUTO_APPLY, BACKGROUND, BIOMES, CONSTRUCTORS, DEFAULT_BIOME, DESTRUCTORS, ENVS,
+    EXTENDS, FOREGROUND, NONE, TERRAIN,
+};
+use crate::common::types::command::Command;
 
 pub fn handle(
     context: Context,
@@ -28,9 +32,12 @@ pub fn handle(
             );
         }
         terrain_toml[DEFAULT_BIOME] = value(new_default);
+    } else if let Some(biome_name) = update_args.delete_biome {
+        delete_biome(&terrain, &mut terrain_toml, &biome_name)?;
+    } else if let Some((old_name, new_name)) = update_args.rename_biome {
+        rename_biome(&terrain, &mut terrain_toml, &old_name, &new_name)?;
     } else {
-        let biome_name = if update_args.new.is_some() {
-            let new_biome = update_args.new.expect("new biome to be some");
+        let biome_name = if let Some(new_biome) = update_args.new {
             terrain_toml[BIOMES][&new_biome] = Biome::new_toml().into();
             new_biome
         } else {
@@ -40,6 +47,10 @@ pub fn handle(
         let biome = if biome_name == NONE {
             &mut terrain_toml[TERRAIN]
         } else {
+            // biome might only be defined in included files
+            if terrain_toml[BIOMES].get(&biome_name).is_none() {
+                terrain_toml[BIOMES][&biome_name] = Biome::new_toml().into();
+            }
             &mut terrain_toml[BIOMES][&biome_name]
         };
 
@@ -50,6 +61,44 @@ pub fn handle(
         update_args.alias.into_iter().for_each(|alias| {
             biome[ALIASES][alias.key] = value(alias.value);
         });
+
+        for env in update_args.unset_env {
+            if remove_key(biome, ENVS, &env).is_none() {
+                bail!("environment variable '{env}' is not defined in biome '{biome_name}'");
+            }
+        }
+
+        for alias in update_args.unset_alias {
+            if remove_key(biome, ALIASES, &alias).is_none() {
+                bail!("alias '{alias}' is not defined in biome '{biome_name}'");
+            }
+        }
+
+        let commands_type = if update_args.background {
+            BACKGROUND
+        } else {
+            FOREGROUND
+        };
+
+        if let Some(constructor) = update_args.add_constructor {
+            add_command(biome, CONSTRUCTORS, commands_type, constructor);
+        }
+
+        if let Some(destructor) = update_args.add_destructor {
+            add_command(biome, DESTRUCTORS, commands_type, destructor);
+        }
+
+        if let Some(index) = update_args.remove_constructor {
+            remove_command(biome, CONSTRUCTORS, commands_type, index).context(format!(
+                "failed to remove constructor from biome '{biome_name}'"
+            ))?;
+        }
+
+        if let Some(index) = update_args.remove_destructor {
+            remove_command(biome, DESTRUCTORS, commands_type, index).context(format!(
+                "failed to remove destructor from biome '{biome_name}'"
+            ))?;
+        }
     }
 
     if update_args.backup {
@@ -69,6 +118,175 @@ pub fn handle(
     Ok(())
 }
 
+fn remove_key(biome: &mut Item, table: &str, key: &str) -> Option<Item> {
+    biome
+        .get_mut(table)
+        .and_then(|table| table.as_table_like_mut())
+        .and_then(|table| table.remove(key))
+}
+
+fn biomes_extending<'a>(terrain: &'a Terrain, biome_name: &str) -> Vec<&'a str> {
+    terrain
+        .biomes()
+        .iter()
+        .filter(|(_, biome)| biome.extends().contains(&biome_name))
+        .map(|(name, _)| name.as_str())
+        .collect()
+}
+
+fn delete_biome(terrain: &Terrain, terrain_toml: &mut DocumentMut, biome_name: &str) -> Result<()> {
+    let extending = biomes_extending(terrain, biome_name);
+    if !extending.is_empty() {
+        bail!(
+            "cannot delete biome '{biome_name}', it is extended by biome(s): '{}'",
+            extending.join("', '")
+        );
+    }
+
+    let removed = terrain_toml[BIOMES]
+        .as_table_like_mut()
+        .and_then(|biomes| biomes.remove(biome_name));
+    if removed.is_none() {
+        bail!("cannot delete biome '{biome_name}', biome is not defined in terrain.toml");
+    }
+
+    if let Some(biomes) = terrain_toml[BIOMES].as_table_mut()
+        && biomes.is_empty()
+    {
+        // `biomes` is required, empty implicit table is not written to file
+        biomes.set_implicit(false);
+    }
+
+    if terrain_toml.get(DEFAULT_BIOME).and_then(|d| d.as_str()) == Some(biome_name) {
+        terrain_toml.remove(DEFAULT_BIOME);
+    }
+    Ok(())
+}
+
+fn rename_biome(
+    terrain: &Terrain,
+    terrain_toml: &mut DocumentMut,
+    old_name: &str,
+    new_name: &str,
+) -> Result<()> {
+    if new_name == NONE || terrain.biomes().contains_key(new_name) {
+        bail!(
+            "cannot rename biome '{old_name}' to '{new_name}', biome '{new_name}' already exists"
+        );
+    }
+
+    // tables keep their position, so biome is written at the same place in the file
+    let renamed = terrain_toml[BIOMES]
+        .as_table_like_mut()
+        .and_then(|biomes| biomes.remove(old_name));
+    let Some(biome) = renamed else {
+        bail!("cannot rename biome '{old_name}', biome is not defined in terrain.toml");
+    };
+    terrain_toml[BIOMES][new_name] = biome;
+
+    if terrain_toml.get(DEFAULT_BIOME).and_then(|d| d.as_str()) == Some(old_name) {
+        terrain_toml[DEFAULT_BIOME] = value(new_name);
+    }
+
+    for extending in biomes_extending(terrain, old_name) {
+        let Some(extends) = terrain_toml[BIOMES]
+            .get_mut(extending)
+            .and_then(|biome| biome.get_mut(EXTENDS))
+        else {
+            // extends is specified in included file
+            continue;
+        };
+
+        if extends.as_str() == Some(old_name) {
+            *extends = value(new_name);
+        } else if let Some(extends) = extends.as_array_mut() {
+            extends
+                .iter_mut()
+                .filter(|biome| biome.as_str() == Some(old_name))
+                .for_each(|biome| *biome = new_name.into());
+        }
+    }
+    Ok(())
+}
+
+fn command_toml(command: Command) -> Table {
+    let mut table = Table::new();
+    table.insert("exe", value(command.exe()));
+    table.insert("args", value(Array::from_iter(command.args())));
+    if let Some(cwd) = command.cwd() {
+        table.insert("cwd", value(cwd.to_string_lossy().to_string()));
+    }
+    table
+}
+
+pub(crate) fn add_command(
+    biome: &mut Item,
+    operation: &str,
+    commands_type: &str,
+    command: Command,
+) {
+    let commands = &mut biome[operation][commands_type];
+    let command = command_toml(command);
+
+    match commands {
+        Item::ArrayOfTables(commands) => commands.push(command),
+        Item::Value(Value::Array(commands)) if !commands.is_empty() => {
+            commands.push(command.into_inline_table());
+        }
+        _ => {
+            // missing or empty inline array is replaced by array of tables
+            let mut array = ArrayOfTables::new();
+            array.push(command);
+            *commands = Item::ArrayOfTables(array);
+        }
+    }
+}
+
+fn remove_command(
+    biome: &mut Item,
+    operation: &str,
+    commands_type: &str,
+    index: usize,
+) -> Result<()> {
+    let Some(operation) = biome
+        .get_mut(operation)
+        .and_then(|operation| operation.as_table_like_mut())
+    else {
+        bail!("there are no {commands_type} commands defined");
+    };
+    let Some(commands) = operation.get_mut(commands_type) else {
+        bail!("there are no {commands_type} commands defined");
+    };
+
+    let len = match commands {
+        Item::ArrayOfTables(commands) => commands.len(),
+        Item::Value(Value::Array(commands)) => commands.len(),
+        _ => 0,
+    };
+    if index >= len {
+        bail!(
+            "there is no {commands_type} command at index {index}, there are {len} commands \
+             defined"
+        );
+    }
+
+    match commands {
+        Item::ArrayOfTables(array) => {
+            array.remove(index);
+            if array.is_empty() {
+                // empty array of tables is not written to file, so it is replaced by `[]`
+                *commands = value(Array::new());
+                operation.fmt();
+            }
+        }
+        Item::Value(Value::Array(array)) => {
+            array.remove(index);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::{copy, create_dir_all, read_to_string};
@@ -92,6 +310,7 @@ mod tests {
     use crate::client::types::terrain::{AutoApply, Terrain};
     use crate::common::constants::{EXAMPLE_BIOME, NONE, TERRAIN_TOML};
     use crate::common::execute::MockExecutor;
+    use crate::common::types::command::Command;
 
     #[test]
     fn set_default_biome() {
@@ -128,6 +347,15 @@ mod tests {
                 biome: BiomeArg::Default,
                 alias: vec![],
                 env: vec![],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: false,
                 auto_apply: None,
@@ -177,6 +405,15 @@ mod tests {
                 biome: BiomeArg::Default,
                 alias: vec![],
                 env: vec![],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: false,
                 auto_apply: None,
@@ -251,6 +488,15 @@ mod tests {
                         value: "VALUE".to_string(),
                     },
                 ],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: Some("example_biome2".to_string()),
                 backup: false,
                 auto_apply: None,
@@ -308,6 +554,15 @@ mod tests {
                     key: "EDITOR".to_string(),
                     value: "nano".to_string(),
                 }],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: false,
                 auto_apply: None,
@@ -364,6 +619,15 @@ mod tests {
                     key: "EDITOR".to_string(),
                     value: "nano".to_string(),
                 }],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: false,
                 auto_apply: None,
@@ -417,6 +681,15 @@ mod tests {
                     key: "EDITOR".to_string(),
                     value: "nano".to_string(),
                 }],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: false,
                 auto_apply: None,
@@ -474,6 +747,15 @@ mod tests {
                     key: "EDITOR".to_string(),
                     value: "nano".to_string(),
                 }],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: true,
                 auto_apply: None,
@@ -525,6 +807,15 @@ mod tests {
                 biome: BiomeArg::Default,
                 alias: vec![],
                 env: vec![],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: true,
                 auto_apply: Some(AutoApply::Enabled),
@@ -579,6 +870,15 @@ mod tests {
                 biome: BiomeArg::Default,
                 alias: vec![],
                 env: vec![],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 new: None,
                 backup: true,
                 auto_apply: Some(AutoApply::default()),
@@ -596,4 +896,188 @@ mod tests {
         .script_was_created_for(NONE)
         .script_was_created_for(EXAMPLE_BIOME);
     }
+
+    fn update_args() -> UpdateArgs {
+        UpdateArgs {
+            set_default: None,
+            biome: BiomeArg::Default,
+            alias: vec![],
+            env: vec![],
+            unset_env: vec![],
+            unset_alias: vec![],
+            delete_biome: None,
+            rename_biome: None,
+            add_constructor: None,
+            add_destructor: None,
+            remove_constructor: None,
+            remove_destructor: None,
+            background: false,
+            new: None,
+            backup: false,
+            auto_apply: None,
+        }
+    }
+
+    fn update_example_terrain(update_args: UpdateArgs, biomes: &[&str]) -> anyhow::Result<String> {
+        let current_dir = tempdir().expect("tempdir to be created");
+        let central_dir = tempdir().expect("tempdir to be created");
+
+        let terrain_toml: PathBuf = current_dir.path().join(TERRAIN_TOML);
+        copy(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS, &terrain_toml)
+            .expect("test terrain to be copied to test dir");
+
+        let toml = read_to_string(&terrain_toml)
+            .unwrap()
+            .parse::<DocumentMut>()
+            .unwrap();
+
+        let executor = biomes
+            .iter()
+            .fold(
+                ExpectZSH::with(MockExecutor::new(), current_dir.path()),
+                |executor, biome| executor.compile_terrain_script_for(biome, central_dir.path()),
+            )
+            .successfully();
+
+        let context = Context::build(current_dir.path(), central_dir.path(), false, executor);
+        create_dir_all(context.scripts_dir()).expect("test scripts dir to be created");
+
+        super::handle(context, Terrain::example(), toml, update_args)?;
+
+        Ok(read_to_string(&terrain_toml).expect("terrain to be read"))
+    }
+
+    #[test]
+    fn unset_env_alias_and_update_commands() {
+        let actual = update_example_terrain(
+            UpdateArgs {
+                unset_env: vec!["EDITOR".to_string()],
+                unset_alias: vec!["tenter".to_string()],
+                add_constructor: Some(Command::new(
+                    "/bin/echo".to_string(),
+                    vec!["-n".to_string(), "added".to_string()],
+                    Some(PathBuf::from("/tmp")),
+                )),
+                remove_destructor: Some(0),
+                ..update_args()
+            },
+            &[EXAMPLE_BIOME, NONE],
+        )
+        .expect("no error to be thrown");
+
+        let expected = read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS)
+            .unwrap()
+            .replace(
+                "[biomes.example_biome.envs]\nEDITOR = \"nvim\"\n",
+                "[biomes.example_biome.envs]\n",
+            )
+            .replace(
+                "[biomes.example_biome.aliases]\ntenter = \"terrain enter --biome example_biome\"\n",
+                "[biomes.example_biome.aliases]\n",
+            )
+            .replace(
+                "args = [\"entering biome example_biome\"]\n",
+                "args = [\"entering biome example_biome\"]\n\n[[biomes.example_biome.constructors.foreground]]\nexe = \"/bin/echo\"\nargs = [\"-n\", \"added\"]\ncwd = \"/tmp\"\n",
+            )
+            .replace(
+                "[[biomes.example_biome.destructors.foreground]]\nexe = \"/bin/echo\"\nargs = [\"exiting biome example_biome\"]\n",
+                "[biomes.example_biome.destructors]\nforeground = []\n",
+            );
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn unset_env_that_does_not_exist() {
+        let err = update_example_terrain(
+            UpdateArgs {
+                unset_env: vec!["DOES_NOT_EXIST".to_string()],
+                ..update_args()
+            },
+            &[],
+        )
+        .expect_err("error to be thrown")
+        .to_string();
+
+        assert_eq!(
+            err,
+            "environment variable 'DOES_NOT_EXIST' is not defined in biome 'example_biome'"
+        );
+    }
+
+    #[test]
+    fn remove_command_that_does_not_exist() {
+        let err = update_example_terrain(
+            UpdateArgs {
+                remove_constructor: Some(1),
+                background: true,
+                ..update_args()
+            },
+            &[],
+        )
+        .expect_err("error to be thrown");
+
+        assert_eq!(
+            format!("{err:#}"),
+            "failed to remove constructor from biome 'example_biome': there is no background \
+             command at index 1, there are 1 commands defined"
+        );
+    }
+
+    #[test]
+    fn delete_biome() {
+        let actual = update_example_terrain(
+            UpdateArgs {
+                delete_biome: Some(EXAMPLE_BIOME.to_string()),
+                ..update_args()
+            },
+            &[NONE],
+        )
+        .expect("no error to be thrown");
+
+        let expected = read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS).unwrap();
+        let expected = expected[..expected.find("\n[biomes.example_biome.envs]").unwrap()]
+            .replace("default_biome = \"example_biome\"\n", "")
+            + "\n[biomes]\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn rename_biome() {
+        let actual = update_example_terrain(
+            UpdateArgs {
+                rename_biome: Some((EXAMPLE_BIOME.to_string(), "renamed".to_string())),
+                ..update_args()
+            },
+            &["renamed", NONE],
+        )
+        .expect("no error to be thrown");
+
+        let expected = read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS)
+            .unwrap()
+            .replace(
+                "default_biome = \"example_biome\"",
+                "default_biome = \"renamed\"",
+            )
+            .replace("[biomes.example_biome.", "[biomes.renamed.")
+            .replace("[[biomes.example_biome.", "[[biomes.renamed.");
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn rename_biome_to_existing_biome() {
+        let err = update_example_terrain(
+            UpdateArgs {
+                rename_biome: Some((EXAMPLE_BIOME.to_string(), NONE.to_string())),
+                ..update_args()
+            },
+            &[],
+        )
+        .expect_err("error to be thrown")
+        .to_string();
+
+        assert_eq!(
+            err,
+            "cannot rename biome 'example_biome' to 'none', biome 'none' already exists"
+        );
+    }
 }
@@ -0,0 +1,486 @@
+use std::fs::{read_to_string, write};
+
+use anyhow::{Context as AnyhowContext, Result, bail};
+use serde_json::{Value, json};
+use toml_edit::ImDocument;
+
+use crate::client::args::{BiomeArg, ValidationFormat};
+use crate::client::types::context::Context;
+use crate::client::types::environment::Environment;
+use crate::client::types::terrain::Terrain;
+use crate::client::validation::{ReportedResult, ValidationMessageLevel, ValidationResult};
+use crate::common::utils::unified_diff;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+pub fn handle(context: Context, terrain: Terrain, fail_on: ValidationMessageLevel) -> Result<()> {
+    if fail_on != ValidationMessageLevel::Error {
+        // results of terrain are printed while reading it, results of environments and
+        // secret providers are printed while they are computed
+        return fail_if_any(&validation_results(&context)?, &fail_on);
+    }
+
+    biomes(&terrain).try_for_each(|biome| {
+        // create environments to run environment validations inside `Environment::from`
+        // and resolve secrets to surface secret provider errors
+        Environment::from(&terrain, biome, context.terrain_dir())?
+            .resolve_secrets(context.executor(), context.terrain_dir())
+    })
+}
+
+/// prints validation results in specified format
+pub fn report(
+    context: Context,
+    format: ValidationFormat,
+    fail_on: ValidationMessageLevel,
+) -> Result<()> {
+    let results = validation_results(&context)?;
+    let output = match format {
+        ValidationFormat::Json => serde_json::to_string_pretty(&results),
+        ValidationFormat::Sarif => serde_json::to_string_pretty(&sarif(&results)),
+    }
+    .context("failed to convert validation results to json")?;
+
+    println!("{output}");
+    fail_if_any(&results, &fail_on)
+}
+
+/// all biomes that can be selected, including biomes defined only in parent terrains
+fn biomes(terrain: &Terrain) -> impl Iterator<Item = BiomeArg> {
+    std::iter::once(BiomeArg::None)
+        .chain(terrain.selectable_biomes().into_iter().map(BiomeArg::Some))
+}
+
+fn fail_if_any(results: &[ReportedResult], fail_on: &ValidationMessageLevel) -> Result<()> {
+    // levels are ordered from most to least severe
+    let failed = results.iter().filter(|r| r.level <= *fail_on).count();
+    if failed > 0 {
+        bail!("terrain has {failed} validation result(s) with level '{fail_on}' or above");
+    }
+    Ok(())
+}
+
+/// validation results of terrain, its parent terrains, environments of all biomes and
+/// secret providers
+///
+/// results of environments and secret providers are printed, logs are disabled for
+/// formatted output.
+fn validation_results(context: &Context) -> Result<Vec<ReportedResult>> {
+    let toml_str = read_to_string(context.toml_path()).context("failed to read terrain.toml")?;
+    let toml = ImDocument::parse(toml_str.as_str()).context("failed to parse terrain toml")?;
+    let report = |result: &ValidationResult| result.report(context.toml_path(), &toml);
+
+    let terrain = match Terrain::from_toml(toml_str.clone())?
+        .with_includes(context.toml_path(), context.terrain_dir())
+    {
+        Ok(terrain) => terrain.with_validation_config(context.config().validation()),
+        Err(err) => return Ok(vec![report(&ValidationResult::invalid_include(&err))]),
+    };
+
+    let terrain_results = terrain.configured(context.terrain_dir());
+    let mut results: Vec<ReportedResult> =
+        terrain_results.results_ref().iter().map(report).collect();
+    results.extend(terrain.parent_reports(context.terrain_dir())?);
+
+    // environments cannot be created for terrain with errors
+    if results
+        .iter()
+        .all(|r| r.level != ValidationMessageLevel::Error)
+    {
+        let terrain = terrain.with_parent(context.terrain_dir())?;
+        for biome in biomes(&terrain) {
+            let mut environment = Environment::unvalidated(&terrain, biome, context.terrain_dir())?;
+            let environment_results =
+                terrain.apply_validation_config(match environment.validate() {
+                    Ok(results) => results,
+                    Err(err) => err.results,
+                });
+            environment_results.print_validation_message();
+            results.extend(environment_results.results_ref().iter().map(report));
+
+            let secret_results = terrain.apply_validation_config(
+                environment.try_resolve_secrets(context.executor(), context.terrain_dir()),
+            );
+            secret_results.print_validation_message();
+            results.extend(secret_results.results_ref().iter().map(report));
+        }
+    }
+
+    results.sort_by(|a, b| {
+        (&a.level, &a.file, &a.location, &a.biome, &a.message).cmp(&(
+            &b.level,
+            &b.file,
+            &b.location,
+            &b.biome,
+            &b.message,
+        ))
+    });
+    Ok(results)
+}
+
+fn sarif(results: &[ReportedResult]) -> Value {
+    let results: Vec<Value> = results
+        .iter()
+        .map(|result| {
+            let level = match result.level {
+                ValidationMessageLevel::Error => "error",
+                ValidationMessageLevel::Warn => "warning",
+                ValidationMessageLevel::Info | ValidationMessageLevel::Debug => "note",
+            };
+            let mut physical_location = json!({
+                "artifactLocation": { "uri": result.file.display().to_string() }
+            });
+            if let Some(location) = &result.location {
+                physical_location["region"] = json!({
+                    "startLine": location.line,
+                    "startColumn": location.column,
+                });
+            }
+            json!({
+                "ruleId": result.rule.id(),
+                "level": level,
+                "message": { "text": result.message },
+                "locations": [{ "physicalLocation": physical_location }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// writes fixes for the fixable values to terrain.toml, only prints the diff
+/// of fixes if `dry_run` is set
+pub fn fix(context: Context, dry_run: bool) -> Result<()> {
+    let output = fix_terrain(&context, dry_run)?;
+    print!("{output}");
+    Ok(())
+}
+
+fn fix_terrain(context: &Context, dry_run: bool) -> Result<String> {
+    let (current, fixed) = Terrain::get_fixed_toml(context)?;
+
+    let Some(fixed) = fixed else {
+        return Ok("terrain.toml does not have any values to fix\n".to_string());
+    };
+
+    let toml_path = context.toml_path().display().to_string();
+    let diff = unified_diff(&toml_path, &toml_path, &current, &fixed);
+
+    if !dry_run {
+        write(context.toml_path(), fixed).context("failed to write fixed terrain.toml")?;
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{copy, create_dir_all, read_to_string, write};
+    use std::os::unix::process::ExitStatusExt;
+    use std::path::Path;
+    use std::process::{ExitStatus, Output};
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::types::context::Context;
+    use crate::client::types::terrain::Terrain;
+    use crate::client::validation::{Location, ReportedResult, Rule, ValidationMessageLevel};
+    use crate::common::constants::TERRAIN_TOML;
+    use crate::common::execute::MockExecutor;
+
+    const TERRAIN_WITH_SPACES: &str = "./tests/data/terrain.example.comments.spaces.toml";
+    const FIXED_TERRAIN: &str = "./tests/data/terrain.example.comments.toml";
+
+    #[test]
+    fn validates_all_biomes() -> Result<()> {
+        let context = Context::build(Path::new(""), Path::new(""), false, MockExecutor::new());
+        super::handle(context, Terrain::example(), ValidationMessageLevel::Error)
+    }
+
+    #[test]
+    fn throws_error_for_secret_provider_errors() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("TOKEN", "env-file:.env")]);
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let err = super::handle(context, terrain, ValidationMessageLevel::Error)
+            .expect_err("expected error to be thrown");
+
+        assert_eq!(err.to_string(), "failed to resolve secrets for environment");
+        Ok(())
+    }
+
+    #[test]
+    fn fix_dry_run_prints_diff_without_updating_terrain() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let toml_path = terrain_dir.path().join(TERRAIN_TOML);
+        copy(TERRAIN_WITH_SPACES, &toml_path)?;
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let diff = super::fix_terrain(&context, true)?;
+
+        let path = toml_path.display();
+        assert!(diff.starts_with(&format!("--- {path}\n+++ {path}\n@@ ")));
+        assert!(diff.contains("\n-\" PAGER\" = \"less\"\n+PAGER = \"less\"\n"));
+        assert_eq!(
+            read_to_string(TERRAIN_WITH_SPACES)?,
+            read_to_string(&toml_path)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn fix_updates_terrain() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let toml_path = terrain_dir.path().join(TERRAIN_TOML);
+        copy(TERRAIN_WITH_SPACES, &toml_path)?;
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        assert!(!super::fix_terrain(&context, false)?.is_empty());
+        assert_eq!(read_to_string(FIXED_TERRAIN)?, read_to_string(&toml_path)?);
+
+        // nothing to fix after fixing
+        assert_eq!(
+            super::fix_terrain(&context, false)?,
+            "terrain.toml does not have any values to fix\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_validation_results_with_location() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let toml_path = terrain_dir.path().join(TERRAIN_TOML);
+        copy(TERRAIN_WITH_SPACES, &toml_path)?;
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let results = super::validation_results(&context)?;
+
+        assert_eq!(results.len(), 10);
+        assert!(
+            results
+                .iter()
+                .all(|result| result.level == ValidationMessageLevel::Warn
+                    && result.file == toml_path)
+        );
+        assert_eq!(
+            results[2],
+            ReportedResult {
+                level: ValidationMessageLevel::Warn,
+                rule: Rule::UntrimmedIdentifier,
+                message: "trimming spaces from identifier: ' PAGER'".to_string(),
+                file: toml_path.clone(),
+                biome: "none".to_string(),
+                target: "env".to_string(),
+                key: Some(" PAGER".to_string()),
+                fix: Some("trim".to_string()),
+                location: Some(Location {
+                    line: 12,
+                    column: 1
+                }),
+            }
+        );
+
+        // commands are located at their array of tables
+        let command = results
+            .iter()
+            .find(|result| result.target == "constructor:background")
+            .expect("background constructor result to be present");
+        assert_eq!(command.biome, "example_biome");
+        assert_eq!(
+            command.location,
+            Some(Location {
+                line: 43,
+                column: 37
+            })
+        );
+
+        super::fail_if_any(&results, &ValidationMessageLevel::Error)?;
+        let err = super::fail_if_any(&results, &ValidationMessageLevel::Warn)
+            .expect_err("expected warnings to fail validation");
+        assert_eq!(
+            err.to_string(),
+            "terrain has 10 validation result(s) with level 'warn' or above"
+        );
+
+        let sarif = super::sarif(&results);
+        assert_eq!(sarif["version"], "2.1.0");
+        let sarif_result = &sarif["runs"][0]["results"][2];
+        assert_eq!(sarif_result["ruleId"], "untrimmed-identifier");
+        assert_eq!(sarif_result["level"], "warning");
+        assert_eq!(
+            sarif_result["locations"][0]["physicalLocation"]["region"]["startLine"],
+            12
+        );
+
+        Ok(())
+    }
+
+    const EMPTY_COMMANDS: &str = "aliases = {}\n\
+         constructors = { foreground = [], background = [] }\n\
+         destructors = { foreground = [], background = [] }\n\n[biomes]\n";
+
+    #[test]
+    fn reports_include_errors_as_results() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        write(
+            terrain_dir.path().join(TERRAIN_TOML),
+            format!(
+                "name = \"included\"\nauto_apply = \"off\"\ninclude = [\"missing.toml\"]\n\n\
+                 [terrain]\nenvs = {{}}\n{EMPTY_COMMANDS}"
+            ),
+        )?;
+
+        let context = Context::build(
+            terrain_dir.path(),
+            Path::new(""),
+            false,
+            MockExecutor::new(),
+        );
+        let results = super::validation_results(&context)?;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].level, ValidationMessageLevel::Error);
+        assert_eq!(results[0].rule, Rule::InvalidInclude);
+        assert_eq!(results[0].location, Some(Location { line: 3, column: 1 }));
+        Ok(())
+    }
+
+    #[test]
+    fn reports_parent_terrain_and_secret_provider_results() -> Result<()> {
+        let parent_dir = tempdir()?;
+        let parent_toml = parent_dir.path().join(TERRAIN_TOML);
+        write(
+            &parent_toml,
+            "name = \"parent\"\nauto_apply = \"off\"\n\n[terrain]\naliases = {}\n\
+             constructors = { foreground = [], background = [] }\n\
+             destructors = { foreground = [], background = [] }\n\n\
+             [terrain.envs]\n\" SPACED\" = \"x\"\n\n[biomes]\n",
+        )?;
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir)?;
+        write(
+            child_dir.join(TERRAIN_TOML),
+            format!(
+                "name = \"child\"\nauto_apply = \"off\"\ninherit_parent = true\n\n\
+                 [terrain]\nenvs = {{ TOKEN = \"env-file:.env\" }}\n{EMPTY_COMMANDS}"
+            ),
+        )?;
+
+        let context = Context::build(&child_dir, Path::new(""), false, MockExecutor::new());
+        let results = super::validation_results(&context)?;
+
+        let parent = results
+            .iter()
+            .find(|result| result.rule == Rule::UntrimmedIdentifier)
+            .expect("parent terrain result to be present");
+        assert_eq!(parent.file, parent_toml);
+        assert!(parent.location.is_some());
+
+        let secret = results
+            .iter()
+            .find(|result| result.rule == Rule::SecretProvider)
+            .expect("secret provider result to be present");
+        assert_eq!(secret.level, ValidationMessageLevel::Error);
+        Ok(())
+    }
+
+    #[test]
+    fn validates_biomes_defined_only_in_parent_terrain() -> Result<()> {
+        let parent_dir = tempdir()?;
+        write(
+            parent_dir.path().join(TERRAIN_TOML),
+            format!(
+                "name = \"parent\"\nauto_apply = \"off\"\n\n[terrain]\nenvs = {{}}\n\
+                 aliases = {{}}\nconstructors = {{ foreground = [], background = [] }}\n\
+                 destructors = {{ foreground = [], background = [] }}\n\n[biomes.dev]\n\
+                 envs = {{ TOKEN = \"env-file:.env\" }}\n{EMPTY_COMMANDS}"
+            ),
+        )?;
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir)?;
+        write(
+            child_dir.join(TERRAIN_TOML),
+            format!(
+                "name = \"child\"\nauto_apply = \"off\"\ninherit_parent = true\n\n\
+                 [terrain]\nenvs = {{}}\n{EMPTY_COMMANDS}"
+            ),
+        )?;
+
+        let context = Context::build(&child_dir, Path::new(""), false, MockExecutor::new());
+        let results = super::validation_results(&context)?;
+
+        let secret = results
+            .iter()
+            .find(|result| result.rule == Rule::SecretProvider)
+            .expect("secret provider result of parent biome to be present");
+        assert_eq!(secret.level, ValidationMessageLevel::Error);
+        assert_eq!(secret.biome, "dev");
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_secrets_once_when_failing_on_warnings() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let toml = format!(
+            "name = \"secrets\"\nauto_apply = \"off\"\n\n[terrain]\n\
+             envs = {{ TOKEN = \"cmd:pass show token\" }}\n{EMPTY_COMMANDS}"
+        );
+        write(terrain_dir.path().join(TERRAIN_TOML), &toml)?;
+
+        let mut executor = MockExecutor::new();
+        executor.expect_get_output().times(1).returning(|_, _| {
+            Ok(Output {
+                status: ExitStatus::from_raw(0),
+                stdout: b"token".to_vec(),
+                stderr: vec![],
+            })
+        });
+        let context = Context::build(terrain_dir.path(), Path::new(""), false, executor);
+
+        super::handle(
+            context,
+            Terrain::from_toml(toml)?,
+            ValidationMessageLevel::Warn,
+        )
+    }
+}
@@ -25,8 +25,24 @@ pub fn init_logging(args: &ClientArgs) -> WorkerGuard {
             .with_filter(level_filter),
     );
 
-    if !matches!(args.command, Some(Verbs::Get { debug: false, .. })) {
-        // do not print any logs for get command as output will be used by scripts
+    if !matches!(
+        args.command,
+        Some(Verbs::Get { debug: false, .. })
+            | Some(Verbs::Probe { .. })
+            | Some(Verbs::Prompt { .. })
+            | Some(Verbs::Diff { json: true, .. })
+            | Some(Verbs::List { json: true, .. })
+            | Some(Verbs::Doctor { json: true, .. })
+            | Some(Verbs::Events { json: true, .. })
+            | Some(Verbs::Export { output: None, .. })
+            | Some(Verbs::Validate {
+                format: Some(_),
+                ..
+            })
+    ) {
+        // do not print any logs for get, probe, prompt, json diff, json list, json doctor,
+        // json events, export to stdout and formatted validate commands as output will be
+        // used by scripts
         tracing::subscriber::set_global_default(subscriber)
             .expect("unable to set global subscriber");
     }
@@ -31,6 +31,8 @@ const MAIN_TEMPLATE: &str = include_str!("../../../templates/zsh_final_script.hb
 
 #[derive(Serialize)]
 struct ScriptData {
+    exports: BTreeMap<String, String>,
+    secrets: Vec<String>,
     environment: Environment,
     typeset: Vec<&'static str>,
 }
@@ -75,8 +77,8 @@ fn get_debug_command_check() -> &'static str {
     if cfg!(debug_assertions) {
         r#"
     elif [ "${command[1]} ${command[2]}" = "cargo run" ] && [ "$TERRAINIUM_DEV" = "true" ]; then
-        typeset +x  __terrainium_is_terrain="true"
-        typeset +x  __terrainium_verb="${command[4]}""#
+        typeset +x __terrainium_is_terrain="true"
+        typeset +x __terrainium_verb="${command[4]}""#
     } else {
         ""
     }
@@ -108,7 +110,8 @@ source "$HOME/.config/terrainium/shell_integration/{ZSH_INIT_SCRIPT_NAME}"
             r#"#!/usr/bin/env zsh
 
 function __terrainium_auto_apply() {{
-    auto_apply="$(terrain get --auto-apply 2> /dev/null)"
+    # active terrain is passed so child terrains can be applied on top of it
+    auto_apply="$(TERRAIN_DIR="$TERRAIN_DIR" terrain probe 2> /dev/null)"
     if [ $? != 0 ]; then
         auto_apply="{}"
     fi
@@ -166,7 +169,12 @@ if [ -n "$TERRAIN_SESSION_ID" ]; then
     builtin unfunction -- "${{terrain_init}}"
     __terrainium_enter
     __terrainium_unexport_envs
+    # used to load scripts of other biomes on `terrain switch` and to reload terrain
+    typeset +x __terrainium_scripts_dir="${{TERRAIN_INIT_SCRIPT:h}}"
+    zmodload -F zsh/stat b:zstat
+    __terrainium_store_script_state
 {}
+    chpwd_functions=(__terrainium_chpwd_functions $chpwd_functions)
 else
     preexec_functions=(__terrainium_fpath_preexec_function $preexec_functions)
     precmd_functions=(__terrainium_fpath_precmd_function $precmd_functions)
@@ -256,8 +264,8 @@ fi
         let scripts_dir = context.scripts_dir();
 
         let result: Result<Vec<_>> = terrain
-            .biomes()
-            .keys()
+            .selectable_biomes()
+            .iter()
             .map(|biome_name| -> Result<()> {
                 self.create_and_compile(
                     &terrain,
@@ -279,6 +287,8 @@ fi
         )
         .context("failed to generate scripts for 'none'".to_string())?;
 
+        terrain.store_includes_state(&scripts_dir)?;
+        terrain.store_sources(context.toml_path(), &scripts_dir)?;
         Ok(())
     }
 
@@ -425,7 +435,7 @@ impl Zsh {
         Ok(())
     }
 
-    fn compiled_script_path(scripts_dir: &Path, biome_name: &str) -> PathBuf {
+    pub(crate) fn compiled_script_path(scripts_dir: &Path, biome_name: &str) -> PathBuf {
         scripts_dir.join(format!("terrain-{biome_name}.zwc"))
     }
 
@@ -453,6 +463,8 @@ impl Zsh {
             "zsh".to_string(),
             Zsh::templates(),
             ScriptData {
+                exports: environment.exports(),
+                secrets: environment.secret_names(),
                 environment,
                 typeset: re_un_exports(),
             },
@@ -536,6 +548,31 @@ mod tests {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn reload_keeps_secrets_in_shell() {
+        let script_dir = tempdir().unwrap();
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("EDITOR", "nano"), ("TOKEN", "file:token")]);
+
+        let script_path = script_dir.path().join("terrain-none.zsh");
+
+        Zsh::get(&PathBuf::new(), Arc::new(MockExecutor::new()))
+            .create_script(
+                &terrain,
+                NONE.to_string(),
+                script_path.as_path(),
+                script_dir.path(),
+            )
+            .expect("creating script failed");
+
+        let actual = fs::read_to_string(script_path).unwrap();
+        assert!(actual.contains("function __terrainium_unset_envs() {\n    unset EDITOR\n}\n"));
+        assert!(actual.contains("function __terrainium_unset_secrets() {\n    unset TOKEN\n}\n"));
+        assert!(!actual.contains("export TOKEN"));
+    }
+
     #[should_panic(
         expected = "expected to generate environment from terrain for biome \"invalid_biome_name\""
     )]
@@ -3,6 +3,16 @@ pub mod client;
 pub mod commands;
 pub mod config;
 pub mod context;
+pub mod doctor;
 pub mod environment;
+pub mod export;
+pub mod fragment;
+pub mod import;
+pub mod offline;
+pub mod probe;
 pub mod proto;
+pub mod provenance;
+pub mod registry;
+pub mod secret;
+pub mod template;
 pub mod terrain;
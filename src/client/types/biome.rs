@@ -9,9 +9,14 @@ use serde::{Deserialize, Serialize};
 use toml_edit::{Array, Item, Table, value};
 
 use crate::client::types::commands::Commands;
-use crate::client::validation::{IdentifierType, ValidationResults, validate_identifiers};
+use crate::client::types::provenance::{Provenance, Resolved, Substitution};
+use crate::client::validation::{
+    IdentifierType, ValidationResults, validate_alias_shadowing, validate_critical_envs,
+    validate_identifiers,
+};
 use crate::common::constants::{
-    ALIASES, BACKGROUND, CONSTRUCTORS, DESTRUCTORS, DOES_NOT_EXIST, ENVS, FOREGROUND,
+    ALIASES, BACKGROUND, BIOMES, CONSTRUCTORS, DESTRUCTORS, DOES_NOT_EXIST, ENVS, FOREGROUND, NONE,
+    TERRAIN,
 };
 use crate::common::types::command::{Command, OperationType};
 
@@ -53,15 +58,50 @@ fn get_filtered<'a>(
     result
 }
 
+/// biome(s) to inherit envs, aliases and commands from
+#[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Extends {
+    pub(crate) fn biomes(&self) -> Vec<&str> {
+        match self {
+            Extends::One(biome) => vec![biome.as_str()],
+            Extends::Many(biomes) => biomes.iter().map(|b| b.as_str()).collect(),
+        }
+    }
+}
+
 #[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
-#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct Biome {
     #[serde(skip)]
     name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    extends: Option<Extends>,
     envs: BTreeMap<String, String>,
     aliases: BTreeMap<String, String>,
     constructors: Commands,
     destructors: Commands,
+    #[serde(skip)]
+    provenance: Provenance,
+}
+
+/// provenance only describes where the values come from, biomes with same values
+/// are equal irrespective of where they were defined
+impl PartialEq for Biome {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.extends == other.extends
+            && self.envs == other.envs
+            && self.aliases == other.aliases
+            && self.constructors == other.constructors
+            && self.destructors == other.destructors
+    }
 }
 
 impl Biome {
@@ -74,10 +114,12 @@ impl Biome {
     ) -> Self {
         Biome {
             name,
+            extends: None,
             envs,
             aliases,
             constructors,
             destructors,
+            provenance: Provenance::default(),
         }
     }
 
@@ -100,6 +142,53 @@ impl Biome {
         self.name.clone()
     }
 
+    /// name of the layer in terrain.toml i.e. `terrain` or `biomes.<biome_name>`
+    pub(crate) fn layer_name(&self) -> String {
+        if self.name == NONE || self.name.is_empty() {
+            TERRAIN.to_string()
+        } else {
+            format!("{BIOMES}.{}", self.name)
+        }
+    }
+
+    /// starts tracking the provenance of values, `file` is set for biomes that are
+    /// not defined in terrain.toml of current terrain
+    pub(crate) fn tracked(mut self, file: Option<&Path>) -> Self {
+        self.provenance = Provenance::new(
+            file,
+            &self.layer_name(),
+            &self.envs,
+            &self.aliases,
+            &self.constructors,
+            &self.destructors,
+        );
+        self
+    }
+
+    /// provenance of the values, biomes that are not tracked yet are treated as
+    /// defined in terrain.toml of current terrain
+    pub(crate) fn provenance(&self) -> Provenance {
+        if self.provenance.is_tracked() {
+            self.provenance.clone()
+        } else {
+            self.clone().tracked(None).provenance
+        }
+    }
+
+    /// see [Biome::provenance]
+    pub(crate) fn qualified(mut self, file: &Path) -> Self {
+        self.provenance = self.provenance().qualified(file);
+        self
+    }
+
+    /// names of the biomes this biome extends in the order they are specified
+    pub(crate) fn extends(&self) -> Vec<&str> {
+        self.extends
+            .as_ref()
+            .map(|extends| extends.biomes())
+            .unwrap_or_default()
+    }
+
     pub(crate) fn aliases(&self) -> &BTreeMap<String, String> {
         &self.aliases
     }
@@ -242,18 +331,46 @@ impl Biome {
         Some((idx, command))
     }
 
-    pub(crate) fn append_envs(&mut self, envs: BTreeMap<String, String>) {
+    /// appends environment variables that are not defined in terrain, `layer` is the
+    /// source of the variables e.g. `activation`
+    pub(crate) fn append_envs(&mut self, envs: BTreeMap<String, String>, layer: &str) {
+        let mut provenance = self.provenance();
+        provenance.append_envs(&envs, layer);
+        self.provenance = provenance;
         self.envs.extend(envs);
     }
 
     pub(crate) fn merge(&self, another: &Biome) -> Biome {
-        Biome::new(
+        let mut merged = Biome::new(
             another.name(),
             self.merge_envs(another),
             self.merge_aliases(another),
             self.merge_constructors(another),
             self.merge_destructors(another),
-        )
+        );
+        merged.provenance = self.provenance().merge(&another.provenance());
+        merged
+    }
+
+    /// merges biome with the same biome from included file, values in `self` take precedence
+    pub(crate) fn merge_included(&self, included: &Biome) -> Biome {
+        let mut merged = included.merge(self);
+        merged.extends = self.extends.clone().or(included.extends.clone());
+        merged
+    }
+
+    /// merges biome with the biome from parent terrain, values in `self` take precedence
+    ///
+    /// constructors of parent are run before and destructors of parent are run after
+    /// the ones in `self`
+    pub(crate) fn merge_parent(&self, parent: &Biome) -> Biome {
+        let mut merged = parent.merge(self);
+        merged.destructors = self.merge_destructors(parent);
+        merged
+            .provenance
+            .set_destructors(&self.provenance(), &parent.provenance());
+        merged.extends = self.extends.clone();
+        merged
     }
 
     pub(crate) fn merge_destructors(&self, another: &Biome) -> Commands {
@@ -305,6 +422,17 @@ impl Biome {
         envs: &BTreeMap<String, String>,
         result_string: String,
         envs_to_substitute: Vec<String>,
+    ) -> String {
+        Self::tracked_substitute_envs(envs, result_string, envs_to_substitute, &mut vec![])
+    }
+
+    /// same as [Biome::recursive_substitute_envs] but also records where each
+    /// reference was resolved from in `substitutions`
+    fn tracked_substitute_envs(
+        envs: &BTreeMap<String, String>,
+        result_string: String,
+        envs_to_substitute: Vec<String>,
+        substitutions: &mut Vec<Substitution>,
     ) -> String {
         let mut envs_to_substitute = envs_to_substitute;
         let mut result_string = result_string;
@@ -313,13 +441,17 @@ impl Biome {
         if !envs_to_substitute.is_empty() {
             let env = envs_to_substitute.pop().unwrap();
 
-            if envs.contains_key(&env) || std::env::var(&env).is_ok() {
-                let env_val = if let Some(env_val) = envs.get(&env) {
-                    env_val.to_string()
-                } else {
-                    std::env::var(&env).unwrap()
-                };
+            let resolved = match (envs.get(&env), std::env::var(&env)) {
+                (Some(env_val), _) => Resolved::Env(env_val.to_string()),
+                (None, Ok(env_val)) => Resolved::System(env_val),
+                (None, Err(_)) => Resolved::Undefined,
+            };
+            let substitution = Substitution::new(env.clone(), resolved.clone());
+            if !substitutions.contains(&substitution) {
+                substitutions.push(substitution);
+            }
 
+            if let Resolved::Env(env_val) | Resolved::System(env_val) = resolved {
                 // if value present in terrain envs or system envs replace the value
                 let value_to_replace = format!("${{{env}}}");
                 result_string = result_string.replace(&value_to_replace, &env_val);
@@ -328,29 +460,37 @@ impl Biome {
                 let new_env_to_substitute = Self::get_envs_to_substitute(&env_val);
                 envs_to_substitute.extend(new_env_to_substitute);
             }
-            return Self::recursive_substitute_envs(envs, result_string, envs_to_substitute);
+            return Self::tracked_substitute_envs(
+                envs,
+                result_string,
+                envs_to_substitute,
+                substitutions,
+            );
         }
         result_string
     }
 
     pub(crate) fn substitute_envs(&mut self) {
+        let mut provenance = self.provenance();
         let biome_envs = self.envs();
         let substituted_envs: Vec<(String, String)> = biome_envs
             .iter()
             .map(|(key, value)| {
                 let envs_to_substitute = Self::get_envs_to_substitute(value);
-                (
-                    key.clone(),
-                    Self::recursive_substitute_envs(
-                        biome_envs,
-                        value.to_owned(),
-                        envs_to_substitute,
-                    ),
-                )
+                let mut substitutions = vec![];
+                let substituted = Self::tracked_substitute_envs(
+                    biome_envs,
+                    value.to_owned(),
+                    envs_to_substitute,
+                    &mut substitutions,
+                );
+                provenance.set_substitutions(key, substitutions);
+                (key.clone(), substituted)
             })
             .collect();
 
         self.set_envs(BTreeMap::from_iter(substituted_envs));
+        self.provenance = provenance;
     }
 
     pub(crate) fn substitute_cwd(&mut self, terrain_dir: &Path) -> Result<()> {
@@ -376,11 +516,15 @@ impl Biome {
     }
 
     fn validate_envs<'a>(&'a self, biome_name: &'a str) -> ValidationResults<'a> {
-        validate_identifiers(IdentifierType::Env, &self.envs, biome_name)
+        let mut result = validate_identifiers(IdentifierType::Env, &self.envs, biome_name);
+        result.append(validate_critical_envs(&self.envs, biome_name));
+        result
     }
 
     fn validate_aliases<'a>(&'a self, biome_name: &'a str) -> ValidationResults<'a> {
-        validate_identifiers(IdentifierType::Alias, &self.aliases, biome_name)
+        let mut result = validate_identifiers(IdentifierType::Alias, &self.aliases, biome_name);
+        result.append(validate_alias_shadowing(&self.aliases, biome_name));
+        result
     }
 
     fn validate_constructors<'a>(
@@ -404,6 +548,7 @@ impl Biome {
     pub(crate) fn replace_env_key(&mut self, key: &str, fixed: &str) {
         let value = self.envs.remove(key).unwrap();
         self.envs.insert(fixed.to_string(), value);
+        self.provenance.rename_env(key, fixed);
     }
 
     pub(crate) fn replace_env_key_toml(biome_toml: &mut Item, key: &str, fixed: &str) {
@@ -413,6 +558,7 @@ impl Biome {
     pub(crate) fn replace_alias_key(&mut self, key: &str, fixed: &str) {
         let value = self.aliases.remove(key).unwrap();
         self.aliases.insert(fixed.to_string(), value);
+        self.provenance.rename_alias(key, fixed);
     }
 
     pub(crate) fn replace_alias_key_toml(biome_toml: &mut Item, key: &str, fixed: &str) {
@@ -531,4 +677,8 @@ impl Biome {
     pub(crate) fn set_aliases(&mut self, aliases: BTreeMap<String, String>) {
         self.aliases = aliases;
     }
+
+    pub(crate) fn set_extends(&mut self, extends: Option<Extends>) {
+        self.extends = extends;
+    }
 }
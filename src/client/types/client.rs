@@ -7,10 +7,12 @@ use prost_types::Any;
 use tokio::net::UnixStream;
 
 use crate::client::types::proto::{ProtoRequest, ProtoResponse};
+use crate::common::types::event::Event;
 use crate::common::types::pb;
 use crate::common::types::pb::response::Payload;
 use crate::common::types::socket::{
-    Socket, socket_is_ready, socket_read, socket_stop_write, socket_write_and_stop,
+    Socket, socket_is_ready, socket_read, socket_read_frame, socket_stop_write,
+    socket_write_and_stop,
 };
 
 #[derive(Debug)]
@@ -58,13 +60,7 @@ impl Client {
     }
 
     pub async fn request(&mut self, payload: ProtoRequest) -> Result<ProtoResponse> {
-        let request: Any = match &payload {
-            ProtoRequest::Activate(activate) => Any::from_msg(activate),
-            ProtoRequest::Deactivate(deactivate) => Any::from_msg(deactivate),
-            ProtoRequest::Execute(commands) => Any::from_msg(commands),
-            ProtoRequest::Status(status) => Any::from_msg(status),
-        }
-        .context(format!("failed to convert request {payload:?} to any"))?;
+        let request: Any = payload.to_any()?;
 
         self.write_and_stop(request).await?;
 
@@ -78,15 +74,41 @@ impl Client {
                 Payload::Error(err) => {
                     bail!("error response from daemon: {err}");
                 }
-                Payload::Body(body) => match body.message {
-                    None => Ok(ProtoResponse::Success),
-                    Some(status) => Ok(ProtoResponse::Status(Box::new(status))),
+                Payload::Body(body) => match (body.message, body.pong) {
+                    (Some(status), _) => Ok(ProtoResponse::Status(Box::new(status))),
+                    (None, Some(pong)) => Ok(ProtoResponse::Pong(pong)),
+                    (None, None) => Ok(ProtoResponse::Success),
                 },
             }
         } else {
             bail!("no response payload for request {:?}", payload);
         }
     }
+
+    /// sends `Subscribe` request, events streamed by daemon are read with `next_event`
+    pub async fn subscribe(&mut self, subscribe: pb::Subscribe) -> Result<()> {
+        let request = Any::from_msg(&subscribe)
+            .context(format!("failed to convert request {subscribe:?} to any"))?;
+        self.write_and_stop(request).await
+    }
+
+    /// next event from the subscription, `None` when daemon has closed the stream
+    pub async fn next_event(&mut self) -> Result<Option<Event>> {
+        let Some(response) = socket_read_frame(self).await? else {
+            return Ok(None);
+        };
+        let response: pb::Response = response
+            .to_msg()
+            .context("failed to parse event response")?;
+
+        match response.payload {
+            Some(Payload::Error(err)) => bail!("error response from daemon: {err}"),
+            Some(Payload::Body(pb::Body {
+                event: Some(event), ..
+            })) => Ok(Some(event.try_into().context("failed to convert event")?)),
+            _ => bail!("invalid event response from daemon"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +117,8 @@ mock! {
     pub Client {
         pub async fn new(path: PathBuf) -> Result<Self>;
         pub async fn request(&mut self, payload: ProtoRequest) -> Result<ProtoResponse>;
+        pub async fn subscribe(&mut self, subscribe: pb::Subscribe) -> Result<()>;
+        pub async fn next_event(&mut self) -> Result<Option<Event>>;
     }
 
     impl Socket for Client {
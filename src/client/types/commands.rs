@@ -6,7 +6,7 @@ use anyhow::{Context, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::client::validation::ValidationResults;
+use crate::client::validation::{ValidationResults, validate_duplicate_commands};
 use crate::common::types::command::{Command, CommandsType, OperationType};
 use crate::common::types::pb;
 
@@ -88,6 +88,19 @@ impl Commands {
             ))
         });
 
+        result.append(validate_duplicate_commands(
+            &self.foreground,
+            biome_name,
+            operation_type,
+            &CommandsType::Foreground,
+        ));
+        result.append(validate_duplicate_commands(
+            &self.background,
+            biome_name,
+            operation_type,
+            &CommandsType::Background,
+        ));
+
         result
     }
 }
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{read_to_string, write};
 use std::path::PathBuf;
 
@@ -8,6 +9,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
+use crate::client::validation::{Rule, Severity};
 use crate::common::constants::{CONFIG_LOCATION, TERRAINIUM_CONF};
 
 #[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
@@ -17,6 +19,18 @@ pub struct Config {
     schema: String,
 
     auto_apply: bool,
+
+    /// re-run constructors of active terrain if they were changed when terrain is reloaded
+    #[serde(default)]
+    reload_constructors: bool,
+
+    /// start terrainiumd through its service when it is not reachable
+    #[serde(default)]
+    auto_start_daemon: bool,
+
+    /// severity of validation rules for all terrains, terrain.toml can override it
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    validation: BTreeMap<Rule, Severity>,
 }
 
 pub fn schema_url() -> String {
@@ -34,6 +48,9 @@ impl Default for Config {
         Self {
             schema: schema_url(),
             auto_apply: true,
+            reload_constructors: false,
+            auto_start_daemon: false,
+            validation: BTreeMap::new(),
         }
     }
 }
@@ -58,6 +75,18 @@ impl Config {
         self.auto_apply
     }
 
+    pub(crate) fn reload_constructors(&self) -> bool {
+        self.reload_constructors
+    }
+
+    pub(crate) fn auto_start_daemon(&self) -> bool {
+        self.auto_start_daemon
+    }
+
+    pub(crate) fn validation(&self) -> &BTreeMap<Rule, Severity> {
+        &self.validation
+    }
+
     pub fn create_file() -> Result<()> {
         let path = get_config_path().context("failed to get config path")?;
         if path.exists() {
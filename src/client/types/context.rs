@@ -6,6 +6,7 @@ use anyhow::{Context as AnyhowContext, Result, bail};
 use crate::client::args::Verbs;
 use crate::client::shell::{Shell, Zsh, get_shell};
 use crate::client::types::config::Config;
+use crate::client::types::template::TEMPLATES_DIR_NAME;
 use crate::common::constants::{
     CONFIG_LOCATION, SHELL_INTEGRATION_SCRIPTS_DIR, TERRAIN_DIR, TERRAIN_SESSION_ID, TERRAIN_TOML,
 };
@@ -24,7 +25,7 @@ pub struct Context {
 }
 
 const TERRAINS_DIR_NAME: &str = "terrains";
-const SCRIPTS_DIR_NAME: &str = "scripts";
+pub(crate) const SCRIPTS_DIR_NAME: &str = "scripts";
 
 fn is_terrain_present(home_dir: &Path, cwd: &Path) -> Option<PathBuf> {
     let local_toml = cwd.join(TERRAIN_TOML);
@@ -39,7 +40,7 @@ fn is_terrain_present(home_dir: &Path, cwd: &Path) -> Option<PathBuf> {
     None
 }
 
-fn get_central_dir_location(home_dir: &Path, terrain_dir: &Path) -> PathBuf {
+pub(crate) fn get_central_dir_location(home_dir: &Path, terrain_dir: &Path) -> PathBuf {
     let terrain_dir_name = Path::canonicalize(terrain_dir)
         .expect("expected current directory to be valid")
         .to_string_lossy()
@@ -52,7 +53,7 @@ fn get_central_dir_location(home_dir: &Path, terrain_dir: &Path) -> PathBuf {
         .join(terrain_dir_name)
 }
 
-fn get_terrain_dir(home_dir: &Path, cwd: &Path) -> Option<(PathBuf, PathBuf)> {
+pub(crate) fn get_terrain_dir(home_dir: &Path, cwd: &Path) -> Option<(PathBuf, PathBuf)> {
     if let Some(toml_path) = is_terrain_present(home_dir, cwd) {
         return Some((cwd.to_path_buf(), toml_path));
     } else if cwd.parent().is_some() && cwd.parent().unwrap().exists() {
@@ -77,14 +78,18 @@ impl Context {
             | Verbs::Update { active: true, .. }
             | Verbs::Generate { active: true, .. }
             | Verbs::Get { active: true, .. }
+            | Verbs::Explain { active: true, .. }
+            | Verbs::Export { active: true, .. }
             | Verbs::Validate { active: true, .. }
             | Verbs::Construct { .. }
             | Verbs::Destruct { .. }
             | Verbs::Exit
+            | Verbs::Switch { .. }
+            | Verbs::Diff { shell: true, .. }
             | Verbs::Status { .. } => {
                 // for edit, update, generate, get if active flag is passed
                 // use TERRAIN_DIR to create context
-                // for exit, construct, destruct only run if terrain is active
+                // for exit, switch, construct, destruct only run if terrain is active
                 let terrain_dir = std::env::var(TERRAIN_DIR);
                 let session_id = std::env::var(TERRAIN_SESSION_ID);
 
@@ -206,6 +211,16 @@ impl Context {
         home_dir.join(CONFIG_LOCATION)
     }
 
+    /// directory that contains central directories of all the terrains
+    pub fn terrains_dir(home_dir: &Path) -> PathBuf {
+        Self::config_dir(home_dir).join(TERRAINS_DIR_NAME)
+    }
+
+    /// directory that contains user templates for `terrain init`
+    pub fn templates_dir(home_dir: &Path) -> PathBuf {
+        Self::config_dir(home_dir).join(TEMPLATES_DIR_NAME)
+    }
+
     pub fn toml_path(&self) -> &Path {
         &self.toml_path
     }
@@ -309,6 +324,7 @@ pub(crate) mod tests {
     use crate::client::test_utils::assertions::zsh::ExpectZSH;
     use crate::client::test_utils::{restore_env_var, set_env_var};
     use crate::client::types::terrain::Terrain;
+    use crate::client::validation::ValidationMessageLevel;
     use crate::common::constants::{TERRAIN_DIR, TERRAIN_SESSION_ID, TERRAIN_TOML};
     use crate::common::execute::MockExecutor;
     use crate::common::test_utils::TEST_SESSION_ID;
@@ -698,7 +714,7 @@ pub(crate) mod tests {
             .compile_script_successfully_for_times(
                 &shell_integration_dir.join("terrainium_init.zsh"),
                 &shell_integration_dir.join("terrainium_init.zwc"),
-                16,
+                17,
             )
             .successfully();
 
@@ -756,6 +772,8 @@ pub(crate) mod tests {
                     central: false,
                     example: true,
                     edit: false,
+                    template: None,
+                    detect: false,
                 },
                 expected: &init_dir_ctx,
             },
@@ -764,6 +782,8 @@ pub(crate) mod tests {
                     central: true,
                     example: true,
                     edit: false,
+                    template: None,
+                    detect: false,
                 },
                 expected: &central_dir_ctx,
             },
@@ -775,11 +795,23 @@ pub(crate) mod tests {
                 expected: &current_dir_ctx,
             },
             TestVerbContext {
-                verb: Verbs::Validate { active: true },
+                verb: Verbs::Validate {
+                    active: true,
+                    fix: false,
+                    dry_run: false,
+                    format: None,
+                    fail_on: ValidationMessageLevel::Error,
+                },
                 expected: &terrain_dir_ctx,
             },
             TestVerbContext {
-                verb: Verbs::Validate { active: false },
+                verb: Verbs::Validate {
+                    active: false,
+                    fix: false,
+                    dry_run: false,
+                    format: None,
+                    fail_on: ValidationMessageLevel::Error,
+                },
                 expected: &current_dir_ctx,
             },
             TestVerbContext {
@@ -803,6 +835,8 @@ pub(crate) mod tests {
                     constructors: false,
                     destructors: false,
                     auto_apply: false,
+                    reveal: false,
+                    explain: false,
                 },
                 expected: &current_dir_ctx,
             },
@@ -814,6 +848,16 @@ pub(crate) mod tests {
                     new: None,
                     alias: vec![],
                     env: vec![],
+                    unset_env: vec![],
+                    unset_alias: vec![],
+                    delete_biome: None,
+                    rename_biome: None,
+                    add_constructor: None,
+                    add_destructor: None,
+                    cwd: None,
+                    remove_constructor: None,
+                    remove_destructor: None,
+                    background: false,
                     auto_apply: None,
                     backup: false,
                 },
@@ -840,6 +884,8 @@ pub(crate) mod tests {
                     constructors: false,
                     destructors: false,
                     auto_apply: false,
+                    reveal: false,
+                    explain: false,
                 },
                 expected: &terrain_dir_ctx,
             },
@@ -851,6 +897,16 @@ pub(crate) mod tests {
                     new: None,
                     alias: vec![],
                     env: vec![],
+                    unset_env: vec![],
+                    unset_alias: vec![],
+                    delete_biome: None,
+                    rename_biome: None,
+                    add_constructor: None,
+                    add_destructor: None,
+                    cwd: None,
+                    remove_constructor: None,
+                    remove_destructor: None,
+                    background: false,
                     auto_apply: None,
                     backup: false,
                 },
@@ -872,6 +928,12 @@ pub(crate) mod tests {
                 verb: Verbs::Exit,
                 expected: &terrain_dir_ctx,
             },
+            TestVerbContext {
+                verb: Verbs::Switch {
+                    biome: BiomeArg::None,
+                },
+                expected: &terrain_dir_ctx,
+            },
         ];
 
         for data in verbs {
@@ -945,6 +1007,8 @@ pub(crate) mod tests {
                 constructors: false,
                 destructors: false,
                 auto_apply: false,
+                reveal: false,
+                explain: false,
             },
             Verbs::Update {
                 active: true,
@@ -953,6 +1017,16 @@ pub(crate) mod tests {
                 new: None,
                 alias: vec![],
                 env: vec![],
+                unset_env: vec![],
+                unset_alias: vec![],
+                delete_biome: None,
+                rename_biome: None,
+                add_constructor: None,
+                add_destructor: None,
+                cwd: None,
+                remove_constructor: None,
+                remove_destructor: None,
+                background: false,
                 auto_apply: None,
                 backup: false,
             },
@@ -963,6 +1037,9 @@ pub(crate) mod tests {
                 biome: BiomeArg::None,
             },
             Verbs::Exit,
+            Verbs::Switch {
+                biome: BiomeArg::None,
+            },
         ];
 
         let executor = Arc::new(MockExecutor::new());
@@ -0,0 +1,95 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::common::types::styles::{error, sub_value, success, value, warning};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// result of a single check run by `terrain doctor`
+#[derive(Debug, PartialEq, Serialize)]
+pub(crate) struct Check {
+    pub(crate) name: &'static str,
+    pub(crate) status: CheckStatus,
+    pub(crate) message: String,
+    /// how the problem can be fixed by user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hint: Option<String>,
+    /// set if problem was fixed by `--fix`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) fixed: bool,
+}
+
+impl Check {
+    pub(crate) fn pass(name: &'static str, message: String) -> Self {
+        Self::new(name, CheckStatus::Pass, message, None)
+    }
+
+    pub(crate) fn warn(name: &'static str, message: String, hint: &str) -> Self {
+        Self::new(name, CheckStatus::Warn, message, Some(hint.to_string()))
+    }
+
+    pub(crate) fn fail(name: &'static str, message: String, hint: &str) -> Self {
+        Self::new(name, CheckStatus::Fail, message, Some(hint.to_string()))
+    }
+
+    fn new(name: &'static str, status: CheckStatus, message: String, hint: Option<String>) -> Self {
+        Self {
+            name,
+            status,
+            message,
+            hint,
+            fixed: false,
+        }
+    }
+
+    /// runs `apply` to fix the problem if `fix` is set and check did not pass
+    pub(crate) fn fix_with(self, fix: bool, apply: impl FnOnce() -> Result<()>) -> Self {
+        if !fix || self.status == CheckStatus::Pass {
+            return self;
+        }
+
+        match apply() {
+            Ok(()) => Self {
+                status: CheckStatus::Pass,
+                hint: None,
+                fixed: true,
+                ..self
+            },
+            Err(err) => Self {
+                status: CheckStatus::Fail,
+                message: format!("{}, failed to fix: {err:#}", self.message),
+                ..self
+            },
+        }
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let status = match self.status {
+            CheckStatus::Pass => success("✓"),
+            CheckStatus::Warn => warning("!"),
+            CheckStatus::Fail => error("✗"),
+        };
+        let fixed = if self.fixed { " (fixed)" } else { "" };
+        writeln!(
+            f,
+            "{status} {}{} {}{fixed}",
+            value(self.name),
+            " ".repeat(20usize.saturating_sub(self.name.len())),
+            self.message
+        )?;
+        if let Some(hint) = &self.hint {
+            writeln!(f, "  {}{}", " ".repeat(21), sub_value(hint))?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::path::Path;
+use std::sync::Arc;
 
 use anyhow::{Context, Result, bail};
 use serde::Serialize;
@@ -8,14 +9,17 @@ use serde::Serialize;
 use crate::client::args::BiomeArg;
 use crate::client::types::biome::Biome;
 use crate::client::types::commands::Commands;
+use crate::client::types::secret::{Secret, unescaped};
 use crate::client::types::terrain::{AutoApply, Terrain};
 use crate::client::validation::{
-    ValidationError, ValidationFixAction, ValidationMessageLevel, ValidationResult,
+    Rule, ValidationError, ValidationFixAction, ValidationMessageLevel, ValidationResult,
     ValidationResults,
 };
 use crate::common::constants::{
     TERRAIN_AUTO_APPLY, TERRAIN_DIR, TERRAIN_NAME, TERRAIN_SELECTED_BIOME, TERRAIN_SESSION_ID,
 };
+#[mockall_double::double]
+use crate::common::execute::Executor;
 
 #[derive(Serialize, Debug, PartialEq)]
 pub struct Environment {
@@ -24,10 +28,38 @@ pub struct Environment {
     selected_biome: String,
     auto_apply: AutoApply,
     merged: Biome,
+    #[serde(skip)]
+    secrets: BTreeMap<String, Secret>,
+    #[serde(skip)]
+    resolved: BTreeMap<String, String>,
 }
 
 impl Environment {
     pub fn from(terrain: &Terrain, selected_biome: BiomeArg, terrain_dir: &Path) -> Result<Self> {
+        let environment = Self::unvalidated(terrain, selected_biome, terrain_dir)?;
+        let results = terrain.apply_validation_config(match environment.validate() {
+            Ok(results) => results,
+            Err(err) => err.results,
+        });
+        results.print_validation_message();
+        if results
+            .results_ref()
+            .iter()
+            .any(|r| r.level == ValidationMessageLevel::Error)
+        {
+            bail!("failed to validate environment");
+        }
+
+        Ok(environment)
+    }
+
+    /// environment without printing and failing on validation results,
+    /// [Environment::validate] has to be called separately
+    pub(crate) fn unvalidated(
+        terrain: &Terrain,
+        selected_biome: BiomeArg,
+        terrain_dir: &Path,
+    ) -> Result<Self> {
         let mut merged: Biome = terrain.merged(&selected_biome)?;
 
         merged.substitute_envs();
@@ -35,21 +67,111 @@ impl Environment {
             .substitute_cwd(terrain_dir)
             .context("failed to substitute cwd for environment")?;
 
-        let environment = Environment {
+        let secrets = Self::secrets_of(&merged);
+        // escaped values are not secrets, so prefix is removed only after finding secrets
+        merged.set_envs(
+            merged
+                .envs()
+                .iter()
+                .map(|(k, v)| (k.to_string(), unescaped(v).to_string()))
+                .collect(),
+        );
+
+        Ok(Environment {
             name: terrain.name().clone(),
             default_biome: terrain.default_biome().clone(),
             selected_biome: merged.name(),
             auto_apply: terrain.auto_apply().clone(),
+            secrets,
+            resolved: BTreeMap::new(),
             merged,
-        };
-        let result = environment.validate();
-        if let Err(e) = &result {
-            e.results.print_validation_message();
-            bail!("failed to validate environment");
+        })
+    }
+
+    /// resolves environment variables that reference secret providers
+    ///
+    /// provider failures are reported as validation errors
+    pub fn resolve_secrets(&mut self, executor: &Arc<Executor>, terrain_dir: &Path) -> Result<()> {
+        let results = self.try_resolve_secrets(executor, terrain_dir);
+        results.print_validation_message();
+        if results
+            .results_ref()
+            .iter()
+            .any(|val| val.level == ValidationMessageLevel::Error)
+        {
+            bail!("failed to resolve secrets for environment");
         }
-        result.unwrap().print_validation_message();
+        Ok(())
+    }
 
-        Ok(environment)
+    /// resolves secrets that can be resolved, without printing and failing on
+    /// validation results of secret providers
+    ///
+    /// secrets that are already resolved are not resolved again.
+    pub(crate) fn try_resolve_secrets<'a>(
+        &mut self,
+        executor: &Arc<Executor>,
+        terrain_dir: &Path,
+    ) -> ValidationResults<'a> {
+        if self
+            .secrets
+            .keys()
+            .all(|env| self.resolved.contains_key(env))
+        {
+            return ValidationResults::default();
+        }
+
+        let envs = Some(Arc::new(self.exports()));
+        let r#for = format!("{}(env)", self.selected_biome);
+        let mut results = HashSet::new();
+        let mut resolved = BTreeMap::new();
+
+        self.secrets
+            .iter()
+            .filter(|(env, _)| !self.resolved.contains_key(*env))
+            .for_each(
+                |(env, secret)| match secret.resolve(executor, envs.clone(), terrain_dir) {
+                    Ok(secret) => {
+                        secret.warnings.into_iter().for_each(|message| {
+                            results.insert(ValidationResult {
+                                level: ValidationMessageLevel::Warn,
+                                message,
+                                r#for: r#for.clone(),
+                                rule: Rule::SecretProvider,
+                                fix_action: ValidationFixAction::None,
+                            });
+                        });
+                        resolved.insert(env.to_string(), secret.value);
+                    }
+                    Err(err) => {
+                        results.insert(ValidationResult {
+                            level: ValidationMessageLevel::Error,
+                            message: format!(
+                                "failed to resolve environment variable '{env}' using secret \
+                             provider '{}': {err:#}",
+                                secret.provider()
+                            ),
+                            r#for: r#for.clone(),
+                            rule: Rule::SecretProvider,
+                            fix_action: ValidationFixAction::None,
+                        });
+                    }
+                },
+            );
+
+        self.resolved.extend(resolved.clone());
+        self.append_envs(resolved, "secret provider");
+        ValidationResults::new(false, results)
+    }
+
+    /// names of the environment variables resolved by secret providers
+    pub(crate) fn secret_names(&self) -> Vec<String> {
+        self.secrets.keys().cloned().collect()
+    }
+
+    /// values of the environment variables resolved by secret providers
+    pub(crate) fn resolved_secrets(&self) -> &BTreeMap<String, String> {
+        &self.resolved
     }
 
     pub fn add_activation_envs(
@@ -58,7 +180,10 @@ impl Environment {
         terrain_dir: &Path,
         is_auto_apply: bool,
     ) {
-        self.append_envs(self.activation_env_vars(session_id, terrain_dir, is_auto_apply));
+        self.append_envs(
+            self.activation_env_vars(session_id, terrain_dir, is_auto_apply),
+            "activation",
+        );
     }
 
     pub fn activation_env_vars(
@@ -109,6 +234,17 @@ impl Environment {
         self.merged.envs()
     }
 
+    /// environment variables that can be exported by shell scripts
+    /// i.e. all environment variables except ones resolved by secret providers
+    pub fn exports(&self) -> BTreeMap<String, String> {
+        self.merged
+            .envs()
+            .iter()
+            .filter(|(k, _)| !self.secrets.contains_key(*k))
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
     pub fn aliases(&self) -> BTreeMap<String, String> {
         self.merged.aliases().clone()
     }
@@ -133,11 +269,20 @@ impl Environment {
         self.merged.destructors()
     }
 
-    pub(crate) fn append_envs(&mut self, envs: BTreeMap<String, String>) {
-        self.merged.append_envs(envs);
+    /// see [Biome::append_envs]
+    pub(crate) fn append_envs(&mut self, envs: BTreeMap<String, String>, layer: &str) {
+        self.merged.append_envs(envs, layer);
     }
 
-    fn validate_envs(&self) -> ValidationResults {
+    fn secrets_of(biome: &Biome) -> BTreeMap<String, Secret> {
+        biome
+            .envs()
+            .iter()
+            .filter_map(|(k, v)| Secret::parse(k, v).map(|secret| (k.to_string(), secret)))
+            .collect()
+    }
+
+    fn validate_envs(&self) -> ValidationResults<'_> {
         let mut result = HashSet::new();
         self.merged.envs().iter().for_each(|(k, v)| {
             // validate if all env references are resolved
@@ -153,6 +298,7 @@ impl Environment {
                          variable."
                     ),
                     r#for: self.selected_biome().clone(),
+                    rule: Rule::UndefinedVariable,
                     fix_action: ValidationFixAction::None,
                 });
             }
@@ -160,7 +306,9 @@ impl Environment {
         ValidationResults::new(false, result)
     }
 
-    pub(crate) fn validate(&self) -> std::result::Result<ValidationResults, ValidationError> {
+    pub(crate) fn validate(
+        &self,
+    ) -> std::result::Result<ValidationResults<'_>, ValidationError<'_>> {
         let results = self.validate_envs();
         if results
             .results_ref()
@@ -181,6 +329,8 @@ impl Environment {
             selected_biome,
             auto_apply: AutoApply::default(),
             merged: merged.clone(),
+            secrets: Self::secrets_of(merged),
+            resolved: BTreeMap::new(),
         }
     }
 
@@ -214,7 +364,9 @@ mod tests {
     use std::env::VarError;
     use std::fs;
     use std::fs::create_dir_all;
+    use std::os::unix::fs::PermissionsExt;
     use std::path::PathBuf;
+    use std::sync::Arc;
 
     use anyhow::Result;
     use pretty_assertions::assert_eq;
@@ -235,9 +387,10 @@ mod tests {
         add_biome, force_set_invalid_default_biome, get_test_biome,
     };
     use crate::client::validation::{
-        ValidationFixAction, ValidationMessageLevel, ValidationResult,
+        Rule, ValidationFixAction, ValidationMessageLevel, ValidationResult,
     };
     use crate::common::constants::{EXAMPLE_BIOME, NONE};
+    use crate::common::execute::MockExecutor;
     use crate::common::test_utils::expected_env_vars_example_biome;
     use crate::common::types::command::Command;
 
@@ -573,8 +726,80 @@ mod tests {
                           set before using 'NESTED_POINTER' environment variable."
                     .to_string(),
                 r#for: "none".to_string(),
+                rule: Rule::UndefinedVariable,
                 fix_action: ValidationFixAction::None,
             })
         );
     }
+
+    #[test]
+    fn resolves_secrets() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let secret_file = terrain_dir.path().join("token");
+        fs::write(&secret_file, "secret-value\n")?;
+        fs::set_permissions(&secret_file, fs::Permissions::from_mode(0o600))?;
+
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("EDITOR", "nano"), ("TOKEN", "file:token")]);
+
+        let mut environment =
+            Environment::from(&terrain, BiomeArg::None, terrain_dir.path()).expect("not to fail");
+
+        let mut exports = BTreeMap::new();
+        exports.insert("EDITOR".to_string(), "nano".to_string());
+        assert_eq!(environment.exports(), exports);
+        assert_eq!(environment.envs().get("TOKEN").unwrap(), "file:token");
+
+        environment.resolve_secrets(&Arc::new(MockExecutor::new()), terrain_dir.path())?;
+
+        assert_eq!(environment.envs().get("TOKEN").unwrap(), "secret-value");
+        assert_eq!(environment.exports(), exports);
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_secrets_fails_for_provider_error() -> Result<()> {
+        let terrain_dir = tempdir()?;
+
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("TOKEN", "file:does_not_exist")]);
+
+        let mut environment =
+            Environment::from(&terrain, BiomeArg::None, terrain_dir.path()).expect("not to fail");
+
+        let err = environment
+            .resolve_secrets(&Arc::new(MockExecutor::new()), terrain_dir.path())
+            .expect_err("expected error to be thrown");
+
+        assert_eq!(err.to_string(), "failed to resolve secrets for environment");
+        assert_eq!(
+            environment.envs().get("TOKEN").unwrap(),
+            "file:does_not_exist"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_escaped_values_that_look_like_secrets() -> Result<()> {
+        let terrain_dir = tempdir()?;
+
+        let mut terrain = Terrain::default();
+        terrain
+            .terrain_mut()
+            .add_envs(vec![("DATABASE_URL", "literal:file:./dev.db")]);
+
+        let mut environment =
+            Environment::from(&terrain, BiomeArg::None, terrain_dir.path()).expect("not to fail");
+        environment.resolve_secrets(&Arc::new(MockExecutor::new()), terrain_dir.path())?;
+
+        let mut exports = BTreeMap::new();
+        exports.insert("DATABASE_URL".to_string(), "file:./dev.db".to_string());
+        assert_eq!(environment.exports(), exports);
+        assert_eq!(environment.envs(), exports);
+        Ok(())
+    }
 }
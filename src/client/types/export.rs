@@ -0,0 +1,346 @@
+use std::collections::{BTreeMap, HashSet};
+
+use regex::Regex;
+use serde_json::{Value, json};
+use tracing::warn;
+
+use crate::client::args::ExportFormat;
+use crate::client::types::biome::Biome;
+use crate::client::types::environment::Environment;
+use crate::common::types::command::Command;
+
+/// environment rendered in format of another tool by `terrain export`
+pub(crate) struct Export<'a> {
+    environment: &'a Environment,
+    /// environment variables to export, either resolved or with references kept
+    envs: BTreeMap<String, String>,
+    keep_references: bool,
+}
+
+impl<'a> Export<'a> {
+    pub(crate) fn new(
+        environment: &'a Environment,
+        envs: BTreeMap<String, String>,
+        keep_references: bool,
+    ) -> Self {
+        Export {
+            environment,
+            envs,
+            keep_references,
+        }
+    }
+
+    pub(crate) fn render(&self, format: ExportFormat) -> String {
+        match format {
+            ExportFormat::Dotenv => self.dotenv(),
+            ExportFormat::Direnv => self.direnv(),
+            ExportFormat::DockerEnvFile => self.docker_env_file(),
+            ExportFormat::SystemdEnv => self.systemd_env(),
+            ExportFormat::VscodeLaunch => self.vscode_launch(),
+            ExportFormat::Justfile => self.justfile(),
+        }
+    }
+
+    fn header(&self, comment: &str) -> String {
+        format!(
+            "{comment} generated by `terrain export` from terrain '{}' biome '{}'\n",
+            self.environment.name(),
+            self.environment.selected_biome()
+        )
+    }
+
+    fn dotenv(&self) -> String {
+        let mut rendered = self.header("#");
+        ordered(&self.envs).into_iter().for_each(|(key, value)| {
+            rendered += &format!("{key}={}\n", double_quoted(value, self.keep_references));
+        });
+        rendered
+    }
+
+    fn direnv(&self) -> String {
+        let mut rendered = self.header("#");
+        ordered(&self.envs).into_iter().for_each(|(key, value)| {
+            rendered += &format!(
+                "export {key}={}\n",
+                double_quoted(value, self.keep_references)
+            );
+        });
+        rendered
+    }
+
+    /// values are used as is by docker, so values with new lines cannot be exported
+    fn docker_env_file(&self) -> String {
+        let mut rendered = self.header("#");
+        self.single_line_envs().for_each(|(key, value)| {
+            rendered += &format!("{key}={value}\n");
+        });
+        rendered
+    }
+
+    fn systemd_env(&self) -> String {
+        let mut rendered = self.header("#");
+        self.single_line_envs().for_each(|(key, value)| {
+            rendered += &format!(
+                "{key}=\"{}\"\n",
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            );
+        });
+        rendered
+    }
+
+    /// `node-terminal` launch configurations that open terminal with environment
+    /// of the terrain, and run aliases and constructors
+    fn vscode_launch(&self) -> String {
+        let configuration = |name: String, command: Option<String>, cwd: Option<String>| {
+            let mut configuration = json!({
+                "name": name,
+                "type": "node-terminal",
+                "request": "launch",
+                "env": self.envs,
+            });
+            if let Some(command) = command {
+                configuration["command"] = Value::String(command);
+            }
+            if let Some(cwd) = cwd {
+                configuration["cwd"] = Value::String(cwd);
+            }
+            configuration
+        };
+
+        let mut configurations = vec![configuration(
+            format!(
+                "{}: {}",
+                self.environment.name(),
+                self.environment.selected_biome()
+            ),
+            None,
+            None,
+        )];
+        self.environment
+            .aliases_ref()
+            .iter()
+            .for_each(|(name, value)| {
+                configurations.push(configuration(
+                    format!("alias: {name}"),
+                    Some(value.clone()),
+                    None,
+                ));
+            });
+        let constructors = self.environment.constructors_ref();
+        constructors
+            .foreground()
+            .iter()
+            .chain(constructors.background())
+            .for_each(|command| {
+                configurations.push(configuration(
+                    format!("constructor: {}", shell_command(command)),
+                    Some(shell_command(command)),
+                    cwd(command),
+                ));
+            });
+
+        let launch = json!({
+            "version": "0.2.0",
+            "configurations": configurations,
+        });
+        format!(
+            "{}{}\n",
+            self.header("//"),
+            serde_json::to_string_pretty(&launch).expect("launch configuration to be serialized")
+        )
+    }
+
+    /// environment variables are exported by just, aliases are recipes that
+    /// pass through the arguments and constructors and destructors are recipes
+    /// that run the commands in order
+    fn justfile(&self) -> String {
+        let mut rendered = self.header("#");
+        rendered += "\n";
+
+        self.envs.iter().for_each(|(key, value)| {
+            let value = if self.keep_references {
+                self.just_expression(value)
+            } else {
+                just_string(value)
+            };
+            rendered += &format!("export {key} := {value}\n");
+        });
+
+        self.environment
+            .aliases_ref()
+            .iter()
+            .for_each(|(name, value)| {
+                rendered += &format!(
+                    "\n# alias `{name}`\n{name} *args:\n    {} {{{{args}}}}\n",
+                    just_recipe_line(value)
+                );
+            });
+
+        let recipes = [
+            (
+                "construct",
+                "foreground constructors",
+                self.environment.constructors_ref().foreground(),
+            ),
+            (
+                "construct-background",
+                "background constructors",
+                self.environment.constructors_ref().background(),
+            ),
+            (
+                "destruct",
+                "foreground destructors",
+                self.environment.destructors_ref().foreground(),
+            ),
+            (
+                "destruct-background",
+                "background destructors",
+                self.environment.destructors_ref().background(),
+            ),
+        ];
+        recipes
+            .into_iter()
+            .filter(|(_, _, commands)| !commands.is_empty())
+            .for_each(|(recipe, description, commands)| {
+                rendered += &format!("\n# runs {description}\n{recipe}:\n");
+                commands.iter().for_each(|command| {
+                    let line = match cwd(command) {
+                        Some(cwd) => {
+                            format!("cd {} && {}", shell_quote(&cwd), shell_command(command))
+                        }
+                        None => shell_command(command),
+                    };
+                    rendered += &format!("    {}\n", just_recipe_line(&line));
+                });
+            });
+
+        rendered
+    }
+
+    /// just expression that concatenates literal parts of the value with the
+    /// referenced variables, references not defined by terrain are read from
+    /// the environment and kept as is if not set, same as terrain
+    fn just_expression(&self, value: &str) -> String {
+        let reference = Regex::new(r"\$\{(.*?)}").expect("reference regex to be parsed");
+        let mut parts = vec![];
+        let mut last = 0;
+        for captures in reference.captures_iter(value) {
+            let matched = captures.get(0).expect("match to be present");
+            if matched.start() > last {
+                parts.push(just_string(&value[last..matched.start()]));
+            }
+            let name = &captures[1];
+            if self.envs.contains_key(name) {
+                parts.push(name.to_string());
+            } else {
+                parts.push(format!(
+                    "env_var_or_default({}, {})",
+                    just_string(name),
+                    just_string(matched.as_str())
+                ));
+            }
+            last = matched.end();
+        }
+        if last < value.len() || parts.is_empty() {
+            parts.push(just_string(&value[last..]));
+        }
+        parts.join(" + ")
+    }
+
+    fn single_line_envs(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.envs.iter().filter(|(key, value)| {
+            let single_line = !value.contains('\n');
+            if !single_line {
+                warn!("environment variable '{key}' is not exported as value has new lines");
+            }
+            single_line
+        })
+    }
+}
+
+/// environment variables ordered so that referenced variables are defined before
+/// the values referencing them, as shell evaluates the values in order
+fn ordered(envs: &BTreeMap<String, String>) -> Vec<(&String, &String)> {
+    fn visit<'a>(
+        key: &str,
+        envs: &'a BTreeMap<String, String>,
+        visited: &mut HashSet<&'a str>,
+        ordered: &mut Vec<(&'a String, &'a String)>,
+    ) {
+        let Some((key, value)) = envs.get_key_value(key) else {
+            return;
+        };
+        if !visited.insert(key) {
+            return;
+        }
+        Biome::get_envs_to_substitute(value)
+            .iter()
+            .for_each(|reference| visit(reference, envs, visited, ordered));
+        ordered.push((key, value));
+    }
+
+    let mut visited = HashSet::new();
+    let mut ordered = vec![];
+    envs.keys()
+        .for_each(|key| visit(key, envs, &mut visited, &mut ordered));
+    ordered
+}
+
+/// double quoted shell value, `${VAR}` references are only expanded if they are kept
+fn double_quoted(value: &str, keep_references: bool) -> String {
+    let mut quoted = String::from('"');
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '"' | '`' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            '$' if !(keep_references && chars.peek() == Some(&'{')) => quoted.push_str("\\$"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub(crate) fn shell_quote(value: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c);
+    if !value.is_empty() && value.chars().all(safe) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+fn shell_command(command: &Command) -> String {
+    std::iter::once(command.exe())
+        .chain(command.args().iter().map(String::as_str))
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// cwd of the command if it is set to a directory
+fn cwd(command: &Command) -> Option<String> {
+    command
+        .cwd()
+        .as_ref()
+        .map(|cwd| cwd.display().to_string())
+        .filter(|cwd| !cwd.is_empty())
+}
+
+fn just_string(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+/// `{{` starts interpolation in just recipes
+fn just_recipe_line(line: &str) -> String {
+    line.replace("{{", "{{{{")
+}
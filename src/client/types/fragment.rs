@@ -0,0 +1,376 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{canonicalize, read_to_string};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use toml_edit::DocumentMut;
+
+use crate::client::types::biome::Biome;
+use crate::client::types::terrain::{AutoApply, Terrain};
+use crate::client::validation::{
+    ValidationFixAction, ValidationMessageLevel, ValidationResult, ValidationResults,
+};
+use crate::common::constants::{
+    ALIASES, BACKGROUND, BIOMES, CONSTRUCTORS, DESTRUCTORS, ENVS, FOREGROUND, NONE, TERRAIN,
+};
+use crate::common::utils::expand_tilde;
+
+/// name of the file in scripts directory that stores modification times of included files
+/// at the time scripts were generated
+pub(crate) const INCLUDES_STATE: &str = "terrain.includes";
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FragmentToml {
+    include: Vec<String>,
+    terrain: Biome,
+    biomes: BTreeMap<String, Biome>,
+}
+
+/// terrain fragment loaded from file specified in `include`
+#[derive(Debug, Clone)]
+pub(crate) struct Fragment {
+    pub(crate) path: PathBuf,
+    pub(crate) terrain: Biome,
+    pub(crate) biomes: BTreeMap<String, Biome>,
+}
+
+/// fragments do not have to specify all the fields of biome
+fn fill_biome_defaults(biome: &mut toml::Value) {
+    if let Some(biome) = biome.as_table_mut() {
+        for key in [ENVS, ALIASES] {
+            biome
+                .entry(key)
+                .or_insert_with(|| toml::Value::Table(Default::default()));
+        }
+        for key in [CONSTRUCTORS, DESTRUCTORS] {
+            if let Some(commands) = biome
+                .entry(key)
+                .or_insert_with(|| toml::Value::Table(Default::default()))
+                .as_table_mut()
+            {
+                for commands_type in [FOREGROUND, BACKGROUND] {
+                    commands
+                        .entry(commands_type)
+                        .or_insert_with(|| toml::Value::Array(vec![]));
+                }
+            }
+        }
+    }
+}
+
+fn parse(contents: &str) -> Result<FragmentToml> {
+    let mut fragment: toml::Table = contents.parse().context("failed to parse toml")?;
+
+    if let Some(terrain) = fragment.get_mut(TERRAIN) {
+        fill_biome_defaults(terrain);
+    }
+    if let Some(biomes) = fragment.get_mut(BIOMES).and_then(|b| b.as_table_mut()) {
+        biomes
+            .iter_mut()
+            .for_each(|(_, biome)| fill_biome_defaults(biome));
+    }
+
+    toml::Value::Table(fragment)
+        .try_into()
+        .context("failed to parse terrain values")
+}
+
+/// main terrain and biomes of fragment with their names set
+fn named(fragment: FragmentToml) -> (Biome, BTreeMap<String, Biome>) {
+    let mut terrain = fragment.terrain;
+    terrain.set_name(NONE.to_string());
+    let mut biomes = fragment.biomes;
+    biomes
+        .iter_mut()
+        .for_each(|(name, biome)| biome.set_name(name.to_string()));
+    (terrain, biomes)
+}
+
+/// parses `terrain` and `biomes` in the same format as included files i.e. all the
+/// fields of biome are optional, `include` is ignored
+pub(crate) fn parse_biomes(contents: &str) -> Result<(Biome, BTreeMap<String, Biome>)> {
+    parse(contents).map(named)
+}
+
+/// resolves included path relative to the file that includes it
+fn resolve(including: &Path, include: &str) -> Result<PathBuf> {
+    let path = expand_tilde(include);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        including
+            .parent()
+            .map(|parent| parent.join(&path))
+            .unwrap_or(path)
+    };
+    canonicalize(&path).context(format!(
+        "failed to find file {path:?} included in {including:?}"
+    ))
+}
+
+/// validates fragment and fixes fixable values in memory,
+/// included files are shared so they are never written
+fn validated(
+    path: PathBuf,
+    fragment: FragmentToml,
+    doc: DocumentMut,
+    terrain_dir: &Path,
+) -> Result<Fragment> {
+    let (terrain, biomes) = named(fragment);
+    let unvalidated = Terrain::new(terrain, biomes, None, AutoApply::default());
+
+    let mut results = unvalidated.terrain().validate(NONE, terrain_dir);
+    unvalidated.biomes().iter().for_each(|(name, biome)| {
+        results.append(biome.validate(name, terrain_dir));
+    });
+
+    // warnings that are not fixable will be reported again when validating
+    // the merged terrain so those are skipped
+    let reported: HashSet<ValidationResult> = results
+        .results_ref()
+        .iter()
+        .filter(|result| {
+            result.level == ValidationMessageLevel::Error
+                || !matches!(result.fix_action, ValidationFixAction::None)
+        })
+        .cloned()
+        .collect();
+    let qualified = ValidationResults::new(results.is_fixable(), reported).qualified(&path);
+    qualified.print_validation_message();
+
+    if qualified
+        .results_ref()
+        .iter()
+        .any(|r| r.level == ValidationMessageLevel::Error)
+    {
+        bail!("included terrain {path:?} had validation errors");
+    }
+
+    let fixed = if results.is_fixable() {
+        Terrain::fix_invalid_values(&unvalidated, doc, results).0
+    } else {
+        unvalidated.clone()
+    };
+
+    Ok(Fragment {
+        terrain: fixed.terrain().clone().tracked(Some(&path)),
+        biomes: fixed
+            .biomes()
+            .iter()
+            .map(|(name, biome)| (name.clone(), biome.clone().tracked(Some(&path))))
+            .collect(),
+        path,
+    })
+}
+
+fn load(
+    including: &Path,
+    includes: &[String],
+    terrain_dir: &Path,
+    stack: &mut Vec<PathBuf>,
+    loaded: &mut Vec<Fragment>,
+) -> Result<()> {
+    for include in includes {
+        let path = resolve(including, include)?;
+
+        if stack.contains(&path) {
+            stack.push(path);
+            let cycle: Vec<String> = stack.iter().map(|p| p.display().to_string()).collect();
+            bail!("terrains have cyclic `include`: {}", cycle.join(" -> "));
+        }
+
+        if loaded.iter().any(|fragment| fragment.path == path) {
+            // already included, first occurrence is used
+            continue;
+        }
+
+        let contents =
+            read_to_string(&path).context(format!("failed to read included terrain {path:?}"))?;
+        let fragment =
+            parse(&contents).context(format!("failed to parse included terrain {path:?}"))?;
+        let doc = contents
+            .parse::<DocumentMut>()
+            .context(format!("failed to parse included terrain {path:?}"))?;
+
+        stack.push(path.clone());
+        load(&path, &fragment.include, terrain_dir, stack, loaded)?;
+        stack.pop();
+
+        loaded.push(validated(path, fragment, doc, terrain_dir)?);
+    }
+    Ok(())
+}
+
+/// loads all the fragments included by terrain.toml, including nested includes
+///
+/// fragments are returned in the order of precedence, lowest first. i.e. files included by
+/// a fragment come before the fragment itself, and later entries in `include`
+/// come after earlier ones.
+pub(crate) fn load_includes(
+    toml_path: &Path,
+    includes: &[String],
+    terrain_dir: &Path,
+) -> Result<Vec<Fragment>> {
+    let mut stack = vec![canonicalize(toml_path).unwrap_or(toml_path.to_path_buf())];
+    let mut loaded = vec![];
+    load(toml_path, includes, terrain_dir, &mut stack, &mut loaded)?;
+    Ok(loaded)
+}
+
+fn modified(path: &Path) -> Result<u128> {
+    Ok(path
+        .metadata()
+        .context(format!("failed to read metadata of {path:?}"))?
+        .modified()
+        .context(format!("failed to get modification time of {path:?}"))?
+        .duration_since(UNIX_EPOCH)
+        .context("modification time is before unix epoch")?
+        .as_nanos())
+}
+
+/// current state of included files, i.e. `<modified time> <path>` per line
+pub(crate) fn includes_state(included: &[PathBuf]) -> Result<String> {
+    included
+        .iter()
+        .map(|path| Ok(format!("{} {}\n", modified(path)?, path.display())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{create_dir_all, write};
+    use std::path::Path;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::types::fragment::load_includes;
+    use crate::common::constants::TERRAIN_TOML;
+
+    #[test]
+    fn loads_nested_includes_in_precedence_order() -> Result<()> {
+        let root = tempdir()?;
+        let shared = root.path().join("shared");
+        let service = root.path().join("service");
+        create_dir_all(&shared)?;
+        create_dir_all(&service)?;
+
+        write(
+            shared.join("base.toml"),
+            r#"
+include = ["common.toml"]
+
+[terrain.envs]
+LAYER = "base"
+
+[biomes.test.envs]
+TEST = "base"
+"#,
+        )?;
+        write(
+            shared.join("common.toml"),
+            r#"
+[terrain.aliases]
+ll = "ls -l"
+"#,
+        )?;
+        write(
+            service.join("extra.toml"),
+            r#"
+[[terrain.constructors.background]]
+exe = "/bin/echo"
+args = ["extra"]
+"#,
+        )?;
+
+        let fragments = load_includes(
+            &service.join(TERRAIN_TOML),
+            &["../shared/base.toml".to_string(), "extra.toml".to_string()],
+            Path::new(""),
+        )?;
+
+        let paths: Vec<_> = fragments.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                shared.join("common.toml").canonicalize()?,
+                shared.join("base.toml").canonicalize()?,
+                service.join("extra.toml").canonicalize()?,
+            ]
+        );
+        assert_eq!(fragments[0].terrain.aliases().get("ll").unwrap(), "ls -l");
+        assert_eq!(fragments[1].terrain.envs().get("LAYER").unwrap(), "base");
+        assert_eq!(
+            fragments[1]
+                .biomes
+                .get("test")
+                .unwrap()
+                .envs()
+                .get("TEST")
+                .unwrap(),
+            "base"
+        );
+        assert_eq!(fragments[2].terrain.constructors().background().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn throws_error_for_cyclic_includes() -> Result<()> {
+        let root = tempdir()?;
+        write(root.path().join("a.toml"), "include = [\"b.toml\"]")?;
+        write(root.path().join("b.toml"), "include = [\"a.toml\"]")?;
+
+        let err = load_includes(
+            &root.path().join(TERRAIN_TOML),
+            &["a.toml".to_string()],
+            Path::new(""),
+        )
+        .expect_err("expected error for cyclic include");
+
+        let a = root.path().join("a.toml").canonicalize()?;
+        let b = root.path().join("b.toml").canonicalize()?;
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "terrains have cyclic `include`: {} -> {} -> {} -> {}",
+                root.path().join(TERRAIN_TOML).display(),
+                a.display(),
+                b.display(),
+                a.display()
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn throws_error_for_invalid_fragment() -> Result<()> {
+        let root = tempdir()?;
+        write(
+            root.path().join("invalid.toml"),
+            r#"
+[terrain.envs]
+"INVALID ENV" = "value"
+"#,
+        )?;
+
+        let err = load_includes(
+            &root.path().join(TERRAIN_TOML),
+            &["invalid.toml".to_string()],
+            Path::new(""),
+        )
+        .expect_err("expected validation error");
+
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "included terrain {:?} had validation errors",
+                root.path().join("invalid.toml").canonicalize()?
+            )
+        );
+        Ok(())
+    }
+}
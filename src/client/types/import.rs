@@ -0,0 +1,593 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use regex::{Captures, Regex};
+
+use crate::client::args::ImportFormat;
+use crate::common::constants::PATH;
+use crate::common::types::command::Command;
+
+/// devcontainer lifecycle commands that are run every time and can be constructors
+const DEVCONTAINER_COMMANDS: [&str; 2] = ["postCreateCommand", "postStartCommand"];
+
+/// devcontainer properties that are read for environment variables
+const DEVCONTAINER_ENVS: [&str; 2] = ["containerEnv", "remoteEnv"];
+
+/// value that could not be translated to terrain and the reason
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Skipped {
+    item: String,
+    reason: String,
+}
+
+/// values imported from environment definition of another tool
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Imported {
+    pub(crate) envs: BTreeMap<String, String>,
+    pub(crate) aliases: BTreeMap<String, String>,
+    pub(crate) constructors: Vec<Command>,
+    pub(crate) skipped: Vec<Skipped>,
+}
+
+impl Imported {
+    pub(crate) fn from_file(format: &ImportFormat, path: &Path) -> Result<Self> {
+        let contents = read_to_string(path).context(format!("failed to read {path:?}"))?;
+        match format {
+            ImportFormat::Direnv => {
+                let dir = path
+                    .canonicalize()
+                    .context(format!("failed to get absolute path of {path:?}"))?;
+                Ok(Self::direnv(
+                    &contents,
+                    dir.parent().unwrap_or(Path::new("/")),
+                ))
+            }
+            ImportFormat::Dotenv => Ok(Self::dotenv(&contents)),
+            ImportFormat::Compose => Self::compose(&contents),
+            ImportFormat::Devcontainer => Self::devcontainer(&contents),
+        }
+    }
+
+    fn skip(&mut self, item: String, reason: &str) {
+        self.skipped.push(Skipped {
+            item,
+            reason: reason.to_string(),
+        });
+    }
+
+    /// `.env` file, `KEY=VALUE` per line with optional `export`
+    fn dotenv(contents: &str) -> Self {
+        let mut imported = Imported::default();
+        for (idx, line) in lines(contents) {
+            let item = format!("line {idx}: `{line}`");
+            let assignment = line.strip_prefix("export ").unwrap_or(line);
+            match assign(assignment) {
+                Ok((key, value)) => {
+                    imported.envs.insert(key, value);
+                }
+                Err(reason) => imported.skip(item, reason),
+            }
+        }
+        imported
+    }
+
+    /// `.envrc` of direnv, exported variables, `PATH_add` and aliases are translated
+    ///
+    /// directories in `PATH_add` are relative to the directory of `.envrc`.
+    fn direnv(contents: &str, envrc_dir: &Path) -> Self {
+        let mut imported = Imported::default();
+        let mut paths: Vec<String> = vec![];
+
+        for (idx, line) in lines(contents) {
+            let item = format!("line {idx}: `{line}`");
+
+            if let Some(assignment) = line.strip_prefix("export ") {
+                match assign(assignment) {
+                    Ok((key, value)) => {
+                        imported.envs.insert(key, value);
+                    }
+                    Err(reason) => imported.skip(item, reason),
+                }
+            } else if let Some(dir) = line.strip_prefix("PATH_add ") {
+                match shell_value(dir) {
+                    Ok(dir) if !dir.contains("${") => {
+                        paths.insert(0, envrc_dir.join(dir).display().to_string());
+                    }
+                    Ok(_) => imported.skip(item, "variables in `PATH_add` are not supported"),
+                    Err(reason) => imported.skip(item, reason),
+                }
+            } else if let Some(alias) = line.strip_prefix("alias ") {
+                match assign(alias) {
+                    Ok((name, value)) => {
+                        imported.aliases.insert(name, value);
+                    }
+                    Err(reason) => imported.skip(item, reason),
+                }
+            } else if assign(line).is_ok() {
+                imported.skip(item, "variable is not exported");
+            } else {
+                imported.skip(
+                    item,
+                    "direnv functions and shell statements are not supported",
+                );
+            }
+        }
+
+        if !paths.is_empty() {
+            paths.push(format!("${{{PATH}}}"));
+            imported.envs.insert(PATH.to_string(), paths.join(":"));
+        }
+        imported
+    }
+
+    /// `environment` of all the services in `docker-compose.yml`, variables defined
+    /// with different values by services are not imported
+    fn compose(contents: &str) -> Result<Self> {
+        let compose: serde_yaml::Value =
+            serde_yaml::from_str(contents).context("failed to parse compose file")?;
+        let Some(services) = compose.get("services").and_then(|s| s.as_mapping()) else {
+            bail!("compose file does not define any `services`");
+        };
+
+        let mut imported = Imported::default();
+        let mut defined_by: BTreeMap<String, (String, String)> = BTreeMap::new();
+        let mut conflicts: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+        for (name, service) in services {
+            let name = name.as_str().unwrap_or_default();
+            if service.get("env_file").is_some() {
+                imported.skip(
+                    format!("service '{name}' `env_file`"),
+                    "import the file with `--from dotenv`",
+                );
+            }
+
+            let environment: Vec<(String, Option<String>)> = match service.get("environment") {
+                Some(serde_yaml::Value::Mapping(envs)) => envs
+                    .iter()
+                    .map(|(k, v)| (yaml_string(k).unwrap_or_default(), yaml_string(v)))
+                    .collect(),
+                Some(serde_yaml::Value::Sequence(envs)) => envs
+                    .iter()
+                    .filter_map(|env| env.as_str())
+                    .map(|env| match env.split_once('=') {
+                        Some((k, v)) => (k.to_string(), Some(v.to_string())),
+                        None => (env.to_string(), None),
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+
+            for (key, value) in environment {
+                let item = format!("service '{name}' `{key}`");
+                let Some(value) = value else {
+                    imported.skip(item, "value is passed through from host");
+                    continue;
+                };
+                if value.contains("$$") {
+                    imported.skip(item, "escaped `$$` is not supported");
+                    continue;
+                }
+                let value = match braced(&value) {
+                    Ok(value) => value,
+                    Err(reason) => {
+                        imported.skip(item, reason);
+                        continue;
+                    }
+                };
+
+                match defined_by.get(&key) {
+                    Some((_, defined)) if defined != &value => {
+                        conflicts.entry(key).or_default().push(name.to_string());
+                    }
+                    Some(_) => {}
+                    None => {
+                        defined_by.insert(key, (name.to_string(), value));
+                    }
+                }
+            }
+        }
+
+        defined_by.into_iter().for_each(|(key, (service, value))| {
+            if let Some(services) = conflicts.get(&key) {
+                imported.skip(
+                    format!(
+                        "`{key}` of services '{service}', '{}'",
+                        services.join("', '")
+                    ),
+                    "services define different values",
+                );
+            } else {
+                imported.envs.insert(key, value);
+            }
+        });
+        Ok(imported)
+    }
+
+    /// `containerEnv`, `remoteEnv` and lifecycle commands of `devcontainer.json`
+    fn devcontainer(contents: &str) -> Result<Self> {
+        let devcontainer: serde_json::Value =
+            json5::from_str(contents).context("failed to parse devcontainer.json")?;
+        let Some(properties) = devcontainer.as_object() else {
+            bail!("devcontainer.json should be an object");
+        };
+
+        let mut imported = Imported::default();
+        for (property, value) in properties {
+            if DEVCONTAINER_ENVS.contains(&property.as_str()) {
+                value
+                    .as_object()
+                    .into_iter()
+                    .flatten()
+                    .for_each(|(key, value)| {
+                        let item = format!("{property} `{key}`");
+                        match value.as_str().map(devcontainer_value) {
+                            Some(Ok(value)) => {
+                                imported.envs.insert(key.clone(), value);
+                            }
+                            Some(Err(reason)) => imported.skip(item, reason),
+                            None => imported.skip(item, "value is not a string"),
+                        }
+                    });
+            } else if DEVCONTAINER_COMMANDS.contains(&property.as_str()) {
+                match devcontainer_commands(value) {
+                    Some(commands) => imported.constructors.extend(commands),
+                    None => imported.skip(property.clone(), "command should be string or array"),
+                }
+            } else if property != "name" && property != "$schema" {
+                imported.skip(property.clone(), "property has no equivalent in terrain");
+            }
+        }
+        Ok(imported)
+    }
+}
+
+impl Display for Imported {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "imported {} environment variable(s), {} alias(es) and {} constructor(s)",
+            self.envs.len(),
+            self.aliases.len(),
+            self.constructors.len()
+        )?;
+
+        if !self.skipped.is_empty() {
+            writeln!(f, "could not translate:")?;
+            for skipped in &self.skipped {
+                writeln!(f, "{: <4}{} - {}", "", skipped.item, skipped.reason)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// non-empty lines that are not comments with their line numbers
+fn lines(contents: &str) -> impl Iterator<Item = (usize, &str)> {
+    contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn is_identifier(name: &str) -> bool {
+    Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$")
+        .expect("identifier regex to be parsed")
+        .is_match(name)
+}
+
+/// `KEY=VALUE` where value is quoted as in shell
+fn assign(assignment: &str) -> Result<(String, String), &'static str> {
+    let Some((key, value)) = assignment.split_once('=') else {
+        return Err("expected `KEY=VALUE`");
+    };
+    if !is_identifier(key) {
+        return Err("name is not a valid identifier");
+    }
+    Ok((key.to_string(), shell_value(value)?))
+}
+
+/// removes quotes from value, single quoted value is used as is
+fn shell_value(raw: &str) -> Result<String, &'static str> {
+    let raw = raw.trim();
+    if let Some(quoted) = raw.strip_prefix('\'') {
+        let Some(value) = quoted.strip_suffix('\'') else {
+            return Err("unterminated quote, multi-line values are not supported");
+        };
+        if value.contains("${") {
+            return Err("single quoted value contains `${` that would be substituted by terrain");
+        }
+        return Ok(value.to_string());
+    }
+
+    let value = if let Some(quoted) = raw.strip_prefix('"') {
+        let Some(value) = quoted.strip_suffix('"') else {
+            return Err("unterminated quote, multi-line values are not supported");
+        };
+        value.replace("\\\"", "\"")
+    } else {
+        // unquoted values can be followed by comment
+        raw.split(" #")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    braced(&value)
+}
+
+/// converts `$VAR` references to `${VAR}` that is substituted by terrain
+fn braced(value: &str) -> Result<String, &'static str> {
+    if value.contains("$(") || value.contains('`') {
+        return Err("command substitution is not supported");
+    }
+
+    let mut unsupported = false;
+    let braced = Regex::new(r"\$\{([^}]*)}|\$([A-Za-z_][A-Za-z0-9_]*)")
+        .expect("variable reference regex to be parsed")
+        .replace_all(value, |captures: &Captures| match captures.get(1) {
+            Some(inner) => {
+                unsupported |= !is_identifier(inner.as_str());
+                captures[0].to_string()
+            }
+            None => format!("${{{}}}", &captures[2]),
+        })
+        .to_string();
+
+    if unsupported {
+        return Err("parameter expansion other than `${VAR}` is not supported");
+    }
+    Ok(braced)
+}
+
+/// converts `${localEnv:VAR}` and `${containerEnv:VAR}` to `${VAR}`, workspace
+/// folder and id variables have no equivalent in terrain
+fn devcontainer_value(value: &str) -> Result<String, &'static str> {
+    let unsupported = "devcontainer variables other than `localEnv` are not supported";
+    if Regex::new(r"\$\{(?:\w+WorkspaceFolder\w*|devcontainerId)}")
+        .expect("devcontainer workspace regex to be parsed")
+        .is_match(value)
+    {
+        return Err(unsupported);
+    }
+
+    let value = Regex::new(r"\$\{(?:localEnv|containerEnv):([A-Za-z_][A-Za-z0-9_]*)}")
+        .expect("devcontainer variable regex to be parsed")
+        .replace_all(value, "$${$1}")
+        .to_string();
+    braced(&value).map_err(|_| unsupported)
+}
+
+/// string commands are run by shell, array commands are run directly and object
+/// contains named commands of either form
+fn devcontainer_commands(value: &serde_json::Value) -> Option<Vec<Command>> {
+    match value {
+        serde_json::Value::String(command) => Some(vec![Command::new(
+            "/bin/sh".to_string(),
+            vec!["-c".to_string(), command.clone()],
+            None,
+        )]),
+        serde_json::Value::Array(command) => {
+            let mut args: Vec<String> = command
+                .iter()
+                .map(|arg| arg.as_str().map(str::to_string))
+                .collect::<Option<_>>()?;
+            if args.is_empty() {
+                return None;
+            }
+            let exe = args.remove(0);
+            Some(vec![Command::new(exe, args, None)])
+        }
+        serde_json::Value::Object(commands) => commands
+            .values()
+            .map(devcontainer_commands)
+            .collect::<Option<Vec<_>>>()
+            .map(|commands| commands.into_iter().flatten().collect()),
+        _ => None,
+    }
+}
+
+fn yaml_string(value: &serde_yaml::Value) -> Option<String> {
+    match value {
+        serde_yaml::Value::String(value) => Some(value.clone()),
+        serde_yaml::Value::Number(value) => Some(value.to_string()),
+        serde_yaml::Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+
+    use super::{Imported, Skipped};
+    use crate::common::types::command::Command;
+
+    fn skipped(item: &str, reason: &str) -> Skipped {
+        Skipped {
+            item: item.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    fn map(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn imports_dotenv() {
+        let imported = Imported::dotenv(
+            r#"
+# database
+DATABASE_URL="postgres://localhost/${DB_NAME}"
+export DB_NAME=app # inline comment
+LITERAL='$HOME'
+HOME_DIR=$HOME/app
+DEFAULTED=${PORT:-8080}
+COMMIT=$(git rev-parse HEAD)
+not an assignment
+"#,
+        );
+
+        assert_eq!(
+            imported,
+            Imported {
+                envs: map(&[
+                    ("DATABASE_URL", "postgres://localhost/${DB_NAME}"),
+                    ("DB_NAME", "app"),
+                    ("HOME_DIR", "${HOME}/app"),
+                    ("LITERAL", "$HOME"),
+                ]),
+                skipped: vec![
+                    skipped(
+                        "line 7: `DEFAULTED=${PORT:-8080}`",
+                        "parameter expansion other than `${VAR}` is not supported"
+                    ),
+                    skipped(
+                        "line 8: `COMMIT=$(git rev-parse HEAD)`",
+                        "command substitution is not supported"
+                    ),
+                    skipped("line 9: `not an assignment`", "expected `KEY=VALUE`"),
+                ],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn imports_direnv() {
+        let imported = Imported::direnv(
+            r#"
+export RUST_LOG=debug
+PATH_add bin
+PATH_add "node_modules/.bin"
+alias test='cargo test'
+LOCAL=value
+dotenv .env.local
+use nix
+"#,
+            Path::new("/project"),
+        );
+
+        assert_eq!(
+            imported,
+            Imported {
+                envs: map(&[
+                    ("PATH", "/project/node_modules/.bin:/project/bin:${PATH}"),
+                    ("RUST_LOG", "debug"),
+                ]),
+                aliases: map(&[("test", "cargo test")]),
+                skipped: vec![
+                    skipped("line 6: `LOCAL=value`", "variable is not exported"),
+                    skipped(
+                        "line 7: `dotenv .env.local`",
+                        "direnv functions and shell statements are not supported"
+                    ),
+                    skipped(
+                        "line 8: `use nix`",
+                        "direnv functions and shell statements are not supported"
+                    ),
+                ],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn imports_compose_environment() -> Result<()> {
+        let imported = Imported::compose(
+            r#"
+services:
+  api:
+    image: api
+    env_file: .env
+    environment:
+      LOG_LEVEL: info
+      PORT: 8080
+      REGION: eu
+      TOKEN:
+  worker:
+    image: worker
+    environment:
+      - LOG_LEVEL=info
+      - REGION=us
+      - PRICE=$$5
+"#,
+        )?;
+
+        assert_eq!(
+            imported,
+            Imported {
+                envs: map(&[("LOG_LEVEL", "info"), ("PORT", "8080")]),
+                skipped: vec![
+                    skipped(
+                        "service 'api' `env_file`",
+                        "import the file with `--from dotenv`"
+                    ),
+                    skipped("service 'api' `TOKEN`", "value is passed through from host"),
+                    skipped("service 'worker' `PRICE`", "escaped `$$` is not supported"),
+                    skipped(
+                        "`REGION` of services 'api', 'worker'",
+                        "services define different values"
+                    ),
+                ],
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn imports_devcontainer() -> Result<()> {
+        let imported = Imported::devcontainer(
+            r#"{
+    "name": "app",
+    "containerEnv": { "TOKEN": "${localEnv:TOKEN}" },
+    "remoteEnv": {
+        "PATH": "${containerEnv:PATH}:/workspace/bin",
+        "ROOT": "${containerWorkspaceFolder}",
+    },
+    "postCreateCommand": "npm install",
+    "postStartCommand": { "server": ["npm", "start"] },
+    "forwardPorts": [3000],
+}"#,
+        )?;
+
+        assert_eq!(
+            imported,
+            Imported {
+                envs: map(&[("PATH", "${PATH}:/workspace/bin"), ("TOKEN", "${TOKEN}")]),
+                constructors: vec![
+                    Command::new(
+                        "/bin/sh".to_string(),
+                        vec!["-c".to_string(), "npm install".to_string()],
+                        None
+                    ),
+                    Command::new("npm".to_string(), vec!["start".to_string()], None),
+                ],
+                skipped: vec![
+                    skipped("forwardPorts", "property has no equivalent in terrain"),
+                    skipped(
+                        "remoteEnv `ROOT`",
+                        "devcontainer variables other than `localEnv` are not supported"
+                    ),
+                ],
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context as AnyhowContext, Result};
+use prost::Message;
+use prost_types::Any;
+use tracing::warn;
+
+#[mockall_double::double]
+use crate::client::types::client::Client;
+use crate::client::types::context::Context;
+use crate::client::types::proto::ProtoRequest;
+
+const OFFLINE_DIR_NAME: &str = "offline";
+const REQUEST_EXTENSION: &str = "pb";
+const CLAIMED_EXTENSION: &str = "claimed";
+
+/// requests recorded while terrainiumd was unreachable, they are sent to the
+/// daemon in the order they were recorded once it is reachable again
+pub(crate) struct OfflineQueue {
+    dir: PathBuf,
+}
+
+impl OfflineQueue {
+    pub(crate) fn new(home_dir: &Path) -> Self {
+        Self {
+            dir: Context::config_dir(home_dir).join(OFFLINE_DIR_NAME),
+        }
+    }
+
+    /// stores request without background commands, commands are not run when
+    /// the session is replayed later
+    pub(crate) fn record(&self, request: ProtoRequest) -> Result<PathBuf> {
+        fs::create_dir_all(&self.dir)
+            .context(format!("failed to create offline directory {:?}", self.dir))?;
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("failed to get current time")?
+            .as_nanos();
+        let path = self.dir.join(format!("{nanos:020}.{REQUEST_EXTENSION}"));
+
+        let request = request.offline().to_any()?;
+        fs::write(&path, request.encode_to_vec())
+            .context(format!("failed to record offline request at {path:?}"))?;
+        Ok(path)
+    }
+
+    /// paths of recorded requests, oldest first
+    pub(crate) fn pending(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .context(format!("failed to read offline directory {:?}", self.dir))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == REQUEST_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// sends recorded requests with a new client from `connect` for each one
+    ///
+    /// replay stops if client cannot connect, so remaining requests are kept.
+    /// requests rejected by daemon are dropped so they do not block the queue.
+    /// each request is claimed before it is sent, so requests are not sent twice
+    /// when multiple shells replay the queue at once.
+    pub(crate) async fn replay<F, Fut>(&self, mut connect: F) -> Result<usize>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<Client>>,
+    {
+        let mut replayed = 0;
+        for path in self.pending()? {
+            let claimed = path.with_extension(CLAIMED_EXTENSION);
+            match fs::rename(&path, &claimed) {
+                Ok(()) => {}
+                // already claimed by another shell
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(err).context(format!("failed to claim offline request {path:?}"));
+                }
+            }
+
+            match read(&claimed) {
+                Ok(request) => {
+                    let mut client = match connect().await {
+                        Ok(client) => client,
+                        Err(err) => {
+                            fs::rename(&claimed, &path)
+                                .context(format!("failed to restore offline request {path:?}"))?;
+                            return Err(err);
+                        }
+                    };
+                    if let Err(err) = client.request(request).await {
+                        warn!("dropping offline request {path:?} rejected by daemon: {err:#}");
+                    } else {
+                        replayed += 1;
+                    }
+                }
+                Err(err) => warn!("dropping invalid offline request {path:?}: {err:#}"),
+            }
+            fs::remove_file(&claimed)
+                .context(format!("failed to remove offline request {claimed:?}"))?;
+        }
+        Ok(replayed)
+    }
+}
+
+fn read(path: &Path) -> Result<ProtoRequest> {
+    let bytes = fs::read(path).context("failed to read request")?;
+    let any = Any::decode(bytes.as_slice()).context("failed to decode request")?;
+    ProtoRequest::try_from(any)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::fs;
+
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::OfflineQueue;
+    use crate::client::test_utils::assertions::client::ExpectClient;
+    use crate::client::types::proto::ProtoRequest;
+    use crate::client::types::terrain::AutoApply;
+    use crate::common::test_utils::{
+        TEST_SESSION_ID, expected_activate_request_example_biome,
+        expected_deactivate_request_example_biome,
+    };
+    use crate::common::types::pb;
+
+    fn activate() -> pb::Activate {
+        expected_activate_request_example_biome(true, false, &AutoApply::default())
+    }
+
+    #[tokio::test]
+    async fn records_and_replays_requests_in_order_without_commands() {
+        let home_dir = tempdir().unwrap();
+        let queue = OfflineQueue::new(home_dir.path());
+
+        assert!(activate().constructors.is_some());
+        queue.record(ProtoRequest::Activate(activate())).unwrap();
+        queue
+            .record(ProtoRequest::Deactivate(
+                expected_deactivate_request_example_biome(TEST_SESSION_ID),
+            ))
+            .unwrap();
+        assert_eq!(queue.pending().unwrap().len(), 2);
+
+        let mut clients = VecDeque::from([
+            ExpectClient::send(ProtoRequest::Activate(pb::Activate {
+                constructors: None,
+                ..activate()
+            }))
+            .successfully(),
+            ExpectClient::send(ProtoRequest::Deactivate(pb::Deactivate {
+                destructors: None,
+                ..expected_deactivate_request_example_biome(TEST_SESSION_ID)
+            }))
+            .with_returning_error("session not found"),
+        ]);
+
+        let replayed = queue
+            .replay(|| {
+                let client = clients.pop_front().expect("client for request");
+                async { Ok(client) }
+            })
+            .await
+            .unwrap();
+
+        // rejected request is dropped but not counted as replayed
+        assert_eq!(replayed, 1);
+        assert!(queue.pending().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn keeps_requests_if_daemon_is_unreachable() {
+        let home_dir = tempdir().unwrap();
+        let queue = OfflineQueue::new(home_dir.path());
+        queue.record(ProtoRequest::Activate(activate())).unwrap();
+
+        let err = queue
+            .replay(|| async { Err(anyhow!("failed to connect to the daemon")) })
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "failed to connect to the daemon");
+        assert_eq!(queue.pending().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn skips_requests_claimed_by_another_replay() {
+        let home_dir = tempdir().unwrap();
+        let queue = OfflineQueue::new(home_dir.path());
+        queue.record(ProtoRequest::Activate(activate())).unwrap();
+        let second = queue
+            .record(ProtoRequest::Deactivate(
+                expected_deactivate_request_example_biome(TEST_SESSION_ID),
+            ))
+            .unwrap();
+
+        let mut clients =
+            VecDeque::from([ExpectClient::send(ProtoRequest::Activate(pb::Activate {
+                constructors: None,
+                ..activate()
+            }))
+            .successfully()]);
+
+        let replayed = queue
+            .replay(|| {
+                // another shell claims the second request while first one is sent
+                fs::rename(&second, second.with_extension("claimed")).unwrap();
+                let client = clients.pop_front().expect("client for request");
+                async { Ok(client) }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(replayed, 1);
+        assert!(queue.pending().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs::{read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::client::types::context::{get_central_dir_location, get_terrain_dir};
+use crate::client::types::terrain::AutoApply;
+
+/// name of the file in central directory that caches the probed values of terrain,
+/// values are only cached if central directory already exists
+pub(crate) const PROBE_CACHE: &str = "probe.json";
+
+/// values of terrain required before terrain is entered, read without validating terrain
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub(crate) struct Probe {
+    auto_apply: AutoApply,
+    default_biome: Option<String>,
+    inherit_parent: bool,
+}
+
+/// probed values are valid as long as terrain.toml is not modified
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ProbeCache {
+    toml_path: PathBuf,
+    modified: u128,
+    size: u64,
+    probe: Probe,
+}
+
+impl Probe {
+    pub(crate) fn auto_apply(&self) -> &AutoApply {
+        &self.auto_apply
+    }
+
+    pub(crate) fn default_biome(&self) -> Option<&String> {
+        self.default_biome.as_ref()
+    }
+
+    /// reads values from cache if terrain.toml was not modified since it was cached,
+    /// otherwise parses terrain.toml and caches the values
+    pub(crate) fn get(home_dir: &Path, terrain_dir: &Path, toml_path: &Path) -> Result<Self> {
+        let metadata = toml_path
+            .metadata()
+            .context(format!("failed to read metadata of {toml_path:?}"))?;
+        let modified = metadata
+            .modified()
+            .context(format!("failed to get modification time of {toml_path:?}"))?
+            .duration_since(UNIX_EPOCH)
+            .context("modification time is before unix epoch")?
+            .as_nanos();
+        let size = metadata.len();
+
+        let cache_path = get_central_dir_location(home_dir, terrain_dir).join(PROBE_CACHE);
+        let cached = read_to_string(&cache_path)
+            .ok()
+            .and_then(|cache| serde_json::from_str::<ProbeCache>(&cache).ok());
+
+        if let Some(cached) = cached
+            && cached.toml_path == toml_path
+            && cached.modified == modified
+            && cached.size == size
+        {
+            debug!("using cached probe for {toml_path:?}");
+            return Ok(cached.probe);
+        }
+
+        let probe: Probe = toml::from_str(
+            &read_to_string(toml_path).context(format!("failed to read {toml_path:?}"))?,
+        )
+        .context(format!("failed to parse {toml_path:?}"))?;
+
+        let cache = ProbeCache {
+            toml_path: toml_path.to_path_buf(),
+            modified,
+            size,
+            probe: probe.clone(),
+        };
+        // failing to cache only makes next probe slower
+        if let Err(err) = store(&cache_path, &cache) {
+            debug!("failed to cache probe for {toml_path:?}: {err:#}");
+        }
+
+        Ok(probe)
+    }
+
+    /// terrain directories this terrain inherits, nearest first
+    pub(crate) fn parent_dirs(&self, home_dir: &Path, terrain_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut parent_dirs = vec![];
+        let mut probe = self.clone();
+        let mut dir = terrain_dir.to_path_buf();
+
+        while probe.inherit_parent {
+            let Some((parent_dir, toml_path)) = dir
+                .parent()
+                .and_then(|parent| get_terrain_dir(home_dir, parent))
+            else {
+                break;
+            };
+            probe = Self::get(home_dir, &parent_dir, &toml_path)?;
+            parent_dirs.push(parent_dir.clone());
+            dir = parent_dir;
+        }
+
+        Ok(parent_dirs)
+    }
+}
+
+/// central directory is not created for probed terrains, so probing directories
+/// does not leave a directory behind for each of them
+fn store(cache_path: &Path, cache: &ProbeCache) -> Result<()> {
+    if !cache_path.parent().is_some_and(Path::exists) {
+        debug!("central directory does not exist, not caching probe in {cache_path:?}");
+        return Ok(());
+    }
+    let cache = serde_json::to_string(cache).context("failed to serialize probe")?;
+    write(cache_path, cache).context("failed to write probe cache")
+}
@@ -1,14 +1,73 @@
+use anyhow::{Context, Result, bail};
+use prost_types::Any;
+
 use crate::common::types::pb;
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq)]
 pub enum ProtoRequest {
     Activate(pb::Activate),
     Deactivate(pb::Deactivate),
     Execute(pb::Execute),
+    Ping(pb::Ping),
     Status(pb::StatusRequest),
+    Switch(pb::Switch),
 }
 
 pub enum ProtoResponse {
     Success,
     Status(Box<pb::StatusResponse>),
+    Pong(pb::Pong),
+}
+
+impl ProtoRequest {
+    pub(crate) fn to_any(&self) -> Result<Any> {
+        match self {
+            ProtoRequest::Activate(activate) => Any::from_msg(activate),
+            ProtoRequest::Deactivate(deactivate) => Any::from_msg(deactivate),
+            ProtoRequest::Execute(commands) => Any::from_msg(commands),
+            ProtoRequest::Ping(ping) => Any::from_msg(ping),
+            ProtoRequest::Status(status) => Any::from_msg(status),
+            ProtoRequest::Switch(switch) => Any::from_msg(switch),
+        }
+        .context(format!("failed to convert request {self:?} to any"))
+    }
+
+    /// request without background commands, so replaying it after the session
+    /// has moved on only records the session in daemon
+    pub(crate) fn offline(self) -> Self {
+        match self {
+            ProtoRequest::Activate(activate) => ProtoRequest::Activate(pb::Activate {
+                constructors: None,
+                ..activate
+            }),
+            ProtoRequest::Deactivate(deactivate) => ProtoRequest::Deactivate(pb::Deactivate {
+                destructors: None,
+                ..deactivate
+            }),
+            ProtoRequest::Switch(switch) => ProtoRequest::Switch(pb::Switch {
+                destructors: None,
+                constructors: None,
+                ..switch
+            }),
+            request => request,
+        }
+    }
+}
+
+impl TryFrom<Any> for ProtoRequest {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Any) -> Result<Self> {
+        let request = match value.type_url.as_str() {
+            "/terrainium.v1.Activate" => ProtoRequest::Activate(value.to_msg()?),
+            "/terrainium.v1.Deactivate" => ProtoRequest::Deactivate(value.to_msg()?),
+            "/terrainium.v1.Execute" => ProtoRequest::Execute(value.to_msg()?),
+            "/terrainium.v1.Ping" => ProtoRequest::Ping(value.to_msg()?),
+            "/terrainium.v1.StatusRequest" => ProtoRequest::Status(value.to_msg()?),
+            "/terrainium.v1.Switch" => ProtoRequest::Switch(value.to_msg()?),
+            type_url => bail!("unknown request type: {type_url}"),
+        };
+        Ok(request)
+    }
 }
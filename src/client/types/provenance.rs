@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+use crate::client::types::commands::Commands;
+use crate::common::constants::DOES_NOT_EXIST;
+use crate::common::types::command::Command;
+
+/// layer a value is defined in i.e. `terrain` or `biomes.<biome_name>` of a file
+#[derive(Debug, Clone)]
+pub struct Origin {
+    file: Option<PathBuf>,
+    layer: String,
+    value: String,
+}
+
+impl Origin {
+    fn new(file: Option<&Path>, layer: &str, value: String) -> Self {
+        Origin {
+            file: file.map(Path::to_path_buf),
+            layer: layer.to_string(),
+            value,
+        }
+    }
+
+    /// layer qualified with the file if value is not defined in terrain.toml
+    /// of current terrain
+    pub fn label(&self) -> String {
+        match &self.file {
+            Some(file) => format!("{}:{}", file.display(), self.layer),
+            None => self.layer.clone(),
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: \"{}\"", self.label(), self.value)
+    }
+}
+
+/// where the environment variable reference `${...}` was resolved from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolved {
+    /// environment variable of the terrain with its unsubstituted value
+    Env(String),
+    /// environment variable of the system
+    System(String),
+    /// reference is not substituted as it is not defined
+    Undefined,
+}
+
+/// environment variable reference `${...}` substituted in a value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Substitution {
+    reference: String,
+    resolved: Resolved,
+}
+
+impl Substitution {
+    pub(crate) fn new(reference: String, resolved: Resolved) -> Self {
+        Substitution {
+            reference,
+            resolved,
+        }
+    }
+}
+
+impl Display for Substitution {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reference = &self.reference;
+        match &self.resolved {
+            Resolved::Env(value) => {
+                write!(f, "${{{reference}}} -> \"{value}\" from env {reference}")
+            }
+            Resolved::System(value) => {
+                write!(f, "${{{reference}}} -> \"{value}\" from system environment")
+            }
+            Resolved::Undefined => write!(f, "${{{reference}}} is not defined"),
+        }
+    }
+}
+
+/// origins of the commands in the same order as the commands
+#[derive(Debug, Clone, Default)]
+pub struct CommandOrigins {
+    foreground: Vec<Origin>,
+    background: Vec<Origin>,
+}
+
+impl CommandOrigins {
+    fn new(file: Option<&Path>, layer: &str, commands: &Commands) -> Self {
+        let origins = |commands: &Vec<Command>| {
+            commands
+                .iter()
+                .map(|command| Origin::new(file, layer, command.to_string()))
+                .collect()
+        };
+        CommandOrigins {
+            foreground: origins(commands.foreground()),
+            background: origins(commands.background()),
+        }
+    }
+
+    fn merge(&self, another: &CommandOrigins) -> CommandOrigins {
+        let mut merged = self.clone();
+        merged.foreground.extend(another.foreground.iter().cloned());
+        merged.background.extend(another.background.iter().cloned());
+        merged
+    }
+
+    fn origins_mut(&mut self) -> impl Iterator<Item = &mut Origin> {
+        self.foreground.iter_mut().chain(self.background.iter_mut())
+    }
+
+    pub fn foreground(&self) -> &Vec<Origin> {
+        &self.foreground
+    }
+
+    pub fn background(&self) -> &Vec<Origin> {
+        &self.background
+    }
+}
+
+/// layers every value of the biome passed through while merging, and the environment
+/// variable references that were substituted in its environment variables
+///
+/// origins of a value are ordered by precedence, last origin is the one that is used.
+#[derive(Debug, Clone, Default)]
+pub struct Provenance {
+    tracked: bool,
+    envs: BTreeMap<String, Vec<Origin>>,
+    aliases: BTreeMap<String, Vec<Origin>>,
+    constructors: CommandOrigins,
+    destructors: CommandOrigins,
+    substitutions: BTreeMap<String, Vec<Substitution>>,
+}
+
+impl Provenance {
+    pub(crate) fn new(
+        file: Option<&Path>,
+        layer: &str,
+        envs: &BTreeMap<String, String>,
+        aliases: &BTreeMap<String, String>,
+        constructors: &Commands,
+        destructors: &Commands,
+    ) -> Self {
+        let origins = |map: &BTreeMap<String, String>| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), vec![Origin::new(file, layer, v.clone())]))
+                .collect()
+        };
+        Provenance {
+            tracked: true,
+            envs: origins(envs),
+            aliases: origins(aliases),
+            constructors: CommandOrigins::new(file, layer, constructors),
+            destructors: CommandOrigins::new(file, layer, destructors),
+            substitutions: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn is_tracked(&self) -> bool {
+        self.tracked
+    }
+
+    /// values in `another` take precedence, constructors and destructors of
+    /// `another` are placed after the ones in `self`
+    pub(crate) fn merge(&self, another: &Provenance) -> Provenance {
+        let merge = |map: &BTreeMap<String, Vec<Origin>>,
+                     another: &BTreeMap<String, Vec<Origin>>| {
+            let mut merged = map.clone();
+            another.iter().for_each(|(key, origins)| {
+                merged
+                    .entry(key.clone())
+                    .or_default()
+                    .extend(origins.iter().cloned());
+            });
+            merged
+        };
+
+        Provenance {
+            tracked: true,
+            envs: merge(&self.envs, &another.envs),
+            aliases: merge(&self.aliases, &another.aliases),
+            constructors: self.constructors.merge(&another.constructors),
+            destructors: self.destructors.merge(&another.destructors),
+            substitutions: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn set_destructors(&mut self, first: &Provenance, second: &Provenance) {
+        self.destructors = first.destructors.merge(&second.destructors);
+    }
+
+    /// sets the file for values defined in terrain.toml of the terrain, used when
+    /// values are merged in terrain of another directory
+    pub(crate) fn qualified(mut self, file: &Path) -> Self {
+        self.envs
+            .values_mut()
+            .chain(self.aliases.values_mut())
+            .flatten()
+            .chain(self.constructors.origins_mut())
+            .chain(self.destructors.origins_mut())
+            .filter(|origin| origin.file.is_none())
+            .for_each(|origin| origin.file = Some(file.to_path_buf()));
+        self
+    }
+
+    pub(crate) fn append_envs(&mut self, envs: &BTreeMap<String, String>, layer: &str) {
+        envs.iter().for_each(|(key, value)| {
+            self.envs
+                .entry(key.clone())
+                .or_default()
+                .push(Origin::new(None, layer, value.clone()));
+        });
+    }
+
+    pub(crate) fn rename_env(&mut self, key: &str, fixed: &str) {
+        if let Some(origins) = self.envs.remove(key) {
+            self.envs.insert(fixed.to_string(), origins);
+        }
+    }
+
+    pub(crate) fn rename_alias(&mut self, key: &str, fixed: &str) {
+        if let Some(origins) = self.aliases.remove(key) {
+            self.aliases.insert(fixed.to_string(), origins);
+        }
+    }
+
+    pub(crate) fn set_substitutions(&mut self, key: &str, substitutions: Vec<Substitution>) {
+        if substitutions.is_empty() {
+            self.substitutions.remove(key);
+        } else {
+            self.substitutions.insert(key.to_string(), substitutions);
+        }
+    }
+
+    pub fn env(&self, key: &str) -> &[Origin] {
+        self.envs.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn alias(&self, key: &str) -> &[Origin] {
+        self.aliases.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn substitutions(&self, key: &str) -> &[Substitution] {
+        self.substitutions
+            .get(key)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn constructors(&self) -> &CommandOrigins {
+        &self.constructors
+    }
+
+    pub fn destructors(&self) -> &CommandOrigins {
+        &self.destructors
+    }
+}
+
+/// renders `key="value"` of the keys in `filter` (all keys if empty) followed by
+/// the explanation of the key, used by `terrain explain` and `terrain get --explain`
+pub(crate) fn explain_pairs(
+    header: &str,
+    values: &BTreeMap<String, String>,
+    filter: &[String],
+    explanation: impl Fn(&str) -> String,
+) -> String {
+    let keys: Vec<&String> = if filter.is_empty() {
+        values.keys().collect()
+    } else {
+        filter.iter().collect()
+    };
+
+    let body: String = keys
+        .into_iter()
+        .map(|key| match values.get(key) {
+            Some(value) => format!("{: <4}{key}=\"{value}\"{}\n", "", explanation(key)),
+            None => format!("{: <4}{key}=\"{DOES_NOT_EXIST}\"\n", ""),
+        })
+        .collect();
+
+    format!("{header}\n{body}")
+}
+
+/// renders commands with the layer each command is defined in
+pub(crate) fn explain_commands(
+    header: &str,
+    commands: &Commands,
+    origins: &CommandOrigins,
+) -> String {
+    // origins are in the same order as merged commands
+    let explained = |commands: &Vec<Command>, origins: &Vec<Origin>| {
+        commands
+            .iter()
+            .zip(origins)
+            .map(|(command, origin)| format!("{: <8}{command} <- {}\n", "", origin.label()))
+            .collect::<String>()
+    };
+
+    let mut result = format!("{header}\n{: <4}foreground:\n", "");
+    result += &explained(commands.foreground(), origins.foreground());
+    result += &format!("{: <4}background:\n", "");
+    result += &explained(commands.background(), origins.background());
+    result
+}
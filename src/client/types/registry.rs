@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, read_dir, read_to_string, remove_dir_all, write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as AnyhowContext, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::client::types::context::Context;
+use crate::client::types::terrain::Terrain;
+use crate::common::constants::TERRAIN_TOML;
+use crate::common::types::styles::{heading, sub_value, value, warning};
+use crate::common::utils::timestamp;
+
+/// name of the file in central directory that registers the terrain for `terrain list`
+pub(crate) const REGISTRY_ENTRY: &str = "registry.json";
+
+/// terrain known on this machine, registered in its central directory by
+/// `init`, `enter` and `generate`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Registration {
+    name: String,
+    terrain_dir: PathBuf,
+    toml_path: PathBuf,
+    central: bool,
+    default_biome: Option<String>,
+    last_entered: Option<String>,
+    #[serde(skip)]
+    central_dir: PathBuf,
+}
+
+impl Registration {
+    /// registers the terrain of context, if `entered` is set last entered time is updated
+    /// otherwise previously recorded time is kept
+    pub(crate) fn register(context: &Context, terrain: &Terrain, entered: bool) -> Result<()> {
+        let central_dir = context.central_dir();
+        let entry_path = central_dir.join(REGISTRY_ENTRY);
+
+        let last_entered = if entered {
+            Some(timestamp())
+        } else {
+            Self::read(central_dir)
+                .ok()
+                .and_then(|registration| registration.last_entered)
+        };
+
+        let registration = Registration {
+            name: terrain.name().clone(),
+            terrain_dir: context.terrain_dir().to_path_buf(),
+            toml_path: context.toml_path().to_path_buf(),
+            central: context.toml_path() == central_dir.join(TERRAIN_TOML),
+            default_biome: terrain.default_biome().clone(),
+            last_entered,
+            central_dir: central_dir.to_path_buf(),
+        };
+
+        create_dir_all(central_dir).context("failed to create central directory")?;
+        let entry =
+            serde_json::to_string_pretty(&registration).context("failed to serialize entry")?;
+        write(&entry_path, entry).context(format!("failed to write registry entry {entry_path:?}"))
+    }
+
+    fn read(central_dir: &Path) -> Result<Self> {
+        let entry_path = central_dir.join(REGISTRY_ENTRY);
+        let entry = read_to_string(&entry_path)
+            .context(format!("failed to read registry entry {entry_path:?}"))?;
+        let mut registration: Registration = serde_json::from_str(&entry)
+            .context(format!("failed to parse registry entry {entry_path:?}"))?;
+        registration.central_dir = central_dir.to_path_buf();
+        Ok(registration)
+    }
+
+    /// all the terrains registered in central directories under `terrains_dir`,
+    /// sorted by name and directory
+    ///
+    /// central directories without registry entry are skipped, they are registered
+    /// when terrain is entered or its scripts are generated.
+    pub fn all(terrains_dir: &Path) -> Result<Vec<Self>> {
+        if !terrains_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut registrations = vec![];
+        for dir in read_dir(terrains_dir).context("failed to read terrains directory")? {
+            let central_dir = dir.context("failed to read terrains directory")?.path();
+            if !central_dir.join(REGISTRY_ENTRY).exists() {
+                continue;
+            }
+
+            match Self::read(&central_dir) {
+                Ok(registration) => registrations.push(registration),
+                Err(err) => warn!("skipping invalid registry entry: {err:#}"),
+            }
+        }
+
+        registrations.sort_by(|a, b| {
+            a.name
+                .cmp(&b.name)
+                .then_with(|| a.terrain_dir.cmp(&b.terrain_dir))
+        });
+        Ok(registrations)
+    }
+
+    /// terrain directory or terrain.toml no longer exists
+    pub fn is_stale(&self) -> bool {
+        !self.terrain_dir.exists() || !self.toml_path.exists()
+    }
+
+    /// removes central directory of the terrain i.e. registry entry, scripts and
+    /// terrain.toml if terrain is central
+    pub fn prune(&self) -> Result<()> {
+        remove_dir_all(&self.central_dir).context(format!(
+            "failed to remove central directory {:?}",
+            self.central_dir
+        ))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn terrain_dir(&self) -> &Path {
+        &self.terrain_dir
+    }
+}
+
+impl Display for Registration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = if self.central { "central" } else { "local" };
+        let stale = if self.is_stale() {
+            format!(" {}", warning("stale"))
+        } else {
+            String::new()
+        };
+
+        write!(
+            f,
+            r#"{}  {}({}){stale}
+{}  {}
+{}  {}
+{}  {}
+{}  {}
+"#,
+            heading("terrain"),
+            value(&self.name),
+            sub_value(kind),
+            heading("location"),
+            value(&self.terrain_dir.display().to_string()),
+            heading("TOML"),
+            value(&self.toml_path.display().to_string()),
+            heading("default biome"),
+            value(self.default_biome.as_deref().unwrap_or("none")),
+            heading("last entered"),
+            value(self.last_entered.as_deref().unwrap_or("never")),
+        )
+    }
+}
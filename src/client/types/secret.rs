@@ -0,0 +1,402 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs::read_to_string;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+
+use crate::common::execute::Execute;
+#[mockall_double::double]
+use crate::common::execute::Executor;
+use crate::common::types::command::Command;
+use crate::common::utils::expand_tilde;
+
+const FILE_PREFIX: &str = "file:";
+const CMD_PREFIX: &str = "cmd:";
+const ENV_FILE_PREFIX: &str = "env-file:";
+const LITERAL_PREFIX: &str = "literal:";
+
+/// reference to a value that is resolved by a secret provider
+/// instead of being stored in terrain.toml
+///
+/// - `file:<path>` - contents of the file, leading and trailing whitespaces are trimmed.
+/// - `cmd:<command>` - stdout of the command run by `sh -c`, e.g. `cmd:pass show db/password`.
+/// - `env-file:<path>[#KEY]` - value of `KEY` from dotenv style file, if `KEY` is not
+///   specified name of the environment variable is used.
+///
+/// values prefixed with `literal:` are not references, see [unescaped].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Secret {
+    File(String),
+    Cmd(String),
+    EnvFile { path: String, key: String },
+}
+
+/// resolved value of the secret along with warnings generated by provider
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ResolvedSecret {
+    pub(crate) value: String,
+    pub(crate) warnings: Vec<String>,
+}
+
+impl Secret {
+    pub(crate) fn parse(env: &str, value: &str) -> Option<Self> {
+        if let Some(path) = value.strip_prefix(FILE_PREFIX) {
+            Some(Secret::File(path.trim().to_string()))
+        } else if let Some(cmd) = value.strip_prefix(CMD_PREFIX) {
+            Some(Secret::Cmd(cmd.trim().to_string()))
+        } else {
+            value.strip_prefix(ENV_FILE_PREFIX).map(|env_file| {
+                let (path, key) = env_file.split_once('#').unwrap_or((env_file, env));
+                Secret::EnvFile {
+                    path: path.trim().to_string(),
+                    key: key.trim().to_string(),
+                }
+            })
+        }
+    }
+
+    pub(crate) fn provider(&self) -> &'static str {
+        match self {
+            Secret::File(_) => "file",
+            Secret::Cmd(_) => "cmd",
+            Secret::EnvFile { .. } => "env-file",
+        }
+    }
+
+    pub(crate) fn resolve(
+        &self,
+        executor: &Arc<Executor>,
+        envs: Option<Arc<BTreeMap<String, String>>>,
+        terrain_dir: &Path,
+    ) -> Result<ResolvedSecret> {
+        match self {
+            Secret::File(path) => {
+                let path = secret_path(path, terrain_dir);
+                let warnings = permission_warnings(&path)?;
+                let value = read_to_string(&path)
+                    .context(format!("failed to read secret file {path:?}"))?
+                    .trim()
+                    .to_string();
+                Ok(ResolvedSecret { value, warnings })
+            }
+            Secret::Cmd(cmd) => {
+                if cmd.is_empty() {
+                    bail!("command for secret provider 'cmd' cannot be empty");
+                }
+                let command = Command::new(
+                    "/bin/sh".to_string(),
+                    vec!["-c".to_string(), cmd.to_string()],
+                    Some(terrain_dir.to_path_buf()),
+                );
+                let output = executor
+                    .get_output(envs, command)
+                    .context(format!("failed to run secret command '{cmd}'"))?;
+                if !output.status.success() {
+                    bail!(
+                        "secret command '{cmd}' exited with code {:?}: {}",
+                        output.status.code(),
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                let value = String::from_utf8(output.stdout)
+                    .context(format!(
+                        "output of secret command '{cmd}' is not valid utf-8"
+                    ))?
+                    .trim()
+                    .to_string();
+                Ok(ResolvedSecret {
+                    value,
+                    warnings: vec![],
+                })
+            }
+            Secret::EnvFile { path, key } => {
+                let path = secret_path(path, terrain_dir);
+                let warnings = permission_warnings(&path)?;
+                let contents =
+                    read_to_string(&path).context(format!("failed to read env file {path:?}"))?;
+                let value = parse_env_file(&contents)
+                    .remove(key)
+                    .context(format!("'{key}' is not defined in env file {path:?}"))?;
+                Ok(ResolvedSecret { value, warnings })
+            }
+        }
+    }
+}
+
+impl Display for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Secret::File(path) => write!(f, "{FILE_PREFIX}{path}"),
+            Secret::Cmd(cmd) => write!(f, "{CMD_PREFIX}{cmd}"),
+            Secret::EnvFile { path, key } => write!(f, "{ENV_FILE_PREFIX}{path}#{key}"),
+        }
+    }
+}
+
+/// value without `literal:` prefix, used to escape values that start with prefix of a
+/// secret provider but are not secrets e.g. `DATABASE_URL = "literal:file:./dev.db"`
+pub(crate) fn unescaped(value: &str) -> &str {
+    value.strip_prefix(LITERAL_PREFIX).unwrap_or(value)
+}
+
+/// relative paths are resolved from terrain directory
+fn secret_path(path: &str, terrain_dir: &Path) -> PathBuf {
+    let path = expand_tilde(path);
+    if path.is_absolute() {
+        path
+    } else {
+        terrain_dir.join(path)
+    }
+}
+
+fn permission_warnings(path: &Path) -> Result<Vec<String>> {
+    let metadata = path
+        .metadata()
+        .context(format!("failed to read metadata of secret file {path:?}"))?;
+
+    let mode = metadata.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Ok(vec![format!(
+            "secret file {path:?} is accessible by group or others (mode {:o}), consider \
+             running `chmod 600 {}`",
+            mode & 0o777,
+            path.display()
+        )]);
+    }
+    Ok(vec![])
+}
+
+/// parses dotenv style file contents i.e. `KEY=VALUE` per line
+///
+/// empty lines, comments starting with `#` and `export ` prefix are ignored,
+/// and surrounding quotes of values are removed.
+pub(crate) fn parse_env_file(contents: &str) -> BTreeMap<String, String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs::{Permissions, set_permissions, write};
+    use std::os::unix::fs::PermissionsExt;
+    use std::sync::Arc;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::test_utils::assertions::executor::{AssertExecutor, ExpectedCommand};
+    use crate::client::types::secret::{ResolvedSecret, Secret, parse_env_file, unescaped};
+    use crate::common::execute::MockExecutor;
+    use crate::common::types::command::Command;
+
+    #[test]
+    fn parses_secret_references() {
+        assert_eq!(
+            Secret::parse("TOKEN", "file:~/.token"),
+            Some(Secret::File("~/.token".to_string()))
+        );
+        assert_eq!(
+            Secret::parse("TOKEN", "cmd:pass show token"),
+            Some(Secret::Cmd("pass show token".to_string()))
+        );
+        assert_eq!(
+            Secret::parse("TOKEN", "env-file:.env"),
+            Some(Secret::EnvFile {
+                path: ".env".to_string(),
+                key: "TOKEN".to_string()
+            })
+        );
+        assert_eq!(
+            Secret::parse("TOKEN", "env-file:.env#API_TOKEN"),
+            Some(Secret::EnvFile {
+                path: ".env".to_string(),
+                key: "API_TOKEN".to_string()
+            })
+        );
+        assert_eq!(Secret::parse("TOKEN", "some value"), None);
+        assert_eq!(Secret::parse("DATABASE_URL", "literal:file:./dev.db"), None);
+    }
+
+    #[test]
+    fn unescapes_literal_values() {
+        assert_eq!(unescaped("literal:file:./dev.db"), "file:./dev.db");
+        assert_eq!(unescaped("literal:literal:value"), "literal:value");
+        assert_eq!(unescaped("file:./dev.db"), "file:./dev.db");
+    }
+
+    #[test]
+    fn resolves_file_secret() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let secret_file = terrain_dir.path().join("token");
+        write(&secret_file, "secret-value\n")?;
+        set_permissions(&secret_file, Permissions::from_mode(0o600))?;
+
+        let resolved = Secret::File("token".to_string()).resolve(
+            &Arc::new(MockExecutor::new()),
+            None,
+            terrain_dir.path(),
+        )?;
+
+        assert_eq!(
+            resolved,
+            ResolvedSecret {
+                value: "secret-value".to_string(),
+                warnings: vec![],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn warns_when_secret_file_is_readable_by_others() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let secret_file = terrain_dir.path().join("token");
+        write(&secret_file, "secret-value")?;
+        set_permissions(&secret_file, Permissions::from_mode(0o644))?;
+
+        let resolved = Secret::File(secret_file.to_string_lossy().to_string()).resolve(
+            &Arc::new(MockExecutor::new()),
+            None,
+            terrain_dir.path(),
+        )?;
+
+        assert_eq!(resolved.value, "secret-value");
+        assert_eq!(
+            resolved.warnings,
+            vec![format!(
+                "secret file {secret_file:?} is accessible by group or others (mode 644), \
+                 consider running `chmod 600 {}`",
+                secret_file.display()
+            )]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_cmd_secret() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let envs = Some(Arc::new(BTreeMap::<String, String>::new()));
+
+        let executor = AssertExecutor::to()
+            .get_output_for(
+                envs.clone(),
+                ExpectedCommand {
+                    command: Command::new(
+                        "/bin/sh".to_string(),
+                        vec!["-c".to_string(), "pass show token".to_string()],
+                        Some(terrain_dir.path().to_path_buf()),
+                    ),
+                    exit_code: 0,
+                    should_fail_to_execute: false,
+                    output: "secret-value\n".to_string(),
+                },
+                1,
+            )
+            .successfully();
+
+        let resolved = Secret::Cmd("pass show token".to_string()).resolve(
+            &Arc::new(executor),
+            envs,
+            terrain_dir.path(),
+        )?;
+
+        assert_eq!(resolved.value, "secret-value");
+        Ok(())
+    }
+
+    #[test]
+    fn returns_error_when_cmd_secret_fails() -> Result<()> {
+        let terrain_dir = tempdir()?;
+
+        let executor = AssertExecutor::to()
+            .get_output_for(
+                None,
+                ExpectedCommand {
+                    command: Command::new(
+                        "/bin/sh".to_string(),
+                        vec!["-c".to_string(), "pass show token".to_string()],
+                        Some(terrain_dir.path().to_path_buf()),
+                    ),
+                    exit_code: 1,
+                    should_fail_to_execute: false,
+                    output: "token is not in the password store.".to_string(),
+                },
+                1,
+            )
+            .successfully();
+
+        let err = Secret::Cmd("pass show token".to_string())
+            .resolve(&Arc::new(executor), None, terrain_dir.path())
+            .expect_err("expected error to be thrown");
+
+        assert_eq!(
+            err.to_string(),
+            "secret command 'pass show token' exited with code Some(1): token is not in the \
+             password store."
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_env_file_secret() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        let env_file = terrain_dir.path().join(".env");
+        write(
+            &env_file,
+            "# comment\nexport API_TOKEN=\"secret-value\"\nOTHER=value\n",
+        )?;
+        set_permissions(&env_file, Permissions::from_mode(0o600))?;
+
+        let executor = Arc::new(MockExecutor::new());
+        let resolved = Secret::parse("API_TOKEN", "env-file:.env")
+            .unwrap()
+            .resolve(&executor, None, terrain_dir.path())?;
+        assert_eq!(resolved.value, "secret-value");
+
+        let err = Secret::parse("TOKEN", "env-file:.env")
+            .unwrap()
+            .resolve(&executor, None, terrain_dir.path())
+            .expect_err("expected error to be thrown");
+        assert_eq!(
+            err.to_string(),
+            format!("'TOKEN' is not defined in env file {env_file:?}")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parses_env_file() {
+        let contents = r#"
+# comment
+KEY1=value1
+export KEY2="value 2"
+KEY3='value3'
+INVALID
+"#;
+        let expected: BTreeMap<String, String> = BTreeMap::from([
+            ("KEY1".to_string(), "value1".to_string()),
+            ("KEY2".to_string(), "value 2".to_string()),
+            ("KEY3".to_string(), "value3".to_string()),
+        ]);
+        assert_eq!(parse_env_file(contents), expected);
+    }
+}
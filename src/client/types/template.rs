@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fs::{read_dir, read_to_string};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use crate::client::types::biome::Biome;
+use crate::client::types::fragment::parse_biomes;
+use crate::client::types::terrain::{AutoApply, Terrain};
+use crate::common::constants::NONE;
+
+/// name of the directory in config directory that contains user templates
+pub const TEMPLATES_DIR_NAME: &str = "templates";
+
+const BUILT_IN: [(&str, &str); 5] = [
+    ("rust", include_str!("../../../templates/init/rust.toml")),
+    ("node", include_str!("../../../templates/init/node.toml")),
+    (
+        "python-venv",
+        include_str!("../../../templates/init/python-venv.toml"),
+    ),
+    ("go", include_str!("../../../templates/init/go.toml")),
+    (
+        "docker-compose",
+        include_str!("../../../templates/init/docker-compose.toml"),
+    ),
+];
+
+/// files that indicate the type of project and the template used for it
+const PROJECT_FILES: [(&str, &str); 8] = [
+    ("Cargo.toml", "rust"),
+    ("package.json", "node"),
+    ("pyproject.toml", "python-venv"),
+    ("go.mod", "go"),
+    ("docker-compose.yml", "docker-compose"),
+    ("docker-compose.yaml", "docker-compose"),
+    ("compose.yml", "docker-compose"),
+    ("compose.yaml", "docker-compose"),
+];
+
+/// files that pin the version of a tool, version is set as environment variable
+const VERSION_FILES: [(&str, &str); 2] = [
+    (".nvmrc", "NODE_VERSION"),
+    (".python-version", "PYTHON_VERSION"),
+];
+
+/// starting values for `terrain init`
+///
+/// templates use the same format as included files, i.e. `terrain` and `biomes`
+/// where all the fields of a biome are optional.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    terrain: Biome,
+    biomes: BTreeMap<String, Biome>,
+    /// description of where the values are taken from, written as comment
+    source: String,
+}
+
+impl Template {
+    /// template for `terrain init`, if both template name and detect are specified
+    /// detected values take precedence
+    pub fn select(
+        templates_dir: &Path,
+        terrain_dir: &Path,
+        name: Option<&str>,
+        detect: bool,
+    ) -> Result<Option<Self>> {
+        let template = name
+            .map(|name| Self::get(templates_dir, name))
+            .transpose()?;
+        if !detect {
+            return Ok(template);
+        }
+
+        let detected = Self::detect(templates_dir, terrain_dir)?;
+        Ok(Some(match template {
+            Some(template) => template.merge(detected),
+            None => detected,
+        }))
+    }
+
+    /// template with specified name, user templates from `templates_dir` take
+    /// precedence over built-in templates with the same name
+    pub fn get(templates_dir: &Path, name: &str) -> Result<Self> {
+        let user_template = templates_dir.join(format!("{name}.toml"));
+        let contents = if user_template.exists() {
+            read_to_string(&user_template)
+                .context(format!("failed to read template {user_template:?}"))?
+        } else if let Some((_, contents)) = BUILT_IN.iter().find(|(n, _)| *n == name) {
+            contents.to_string()
+        } else {
+            bail!(
+                "template '{name}' does not exist, available templates: {}",
+                Self::names(templates_dir).join(", ")
+            );
+        };
+
+        let (terrain, biomes) =
+            parse_biomes(&contents).context(format!("failed to parse template '{name}'"))?;
+        Ok(Template {
+            terrain,
+            biomes,
+            source: format!("template '{name}'"),
+        })
+    }
+
+    /// names of the built-in and user templates
+    pub fn names(templates_dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = BUILT_IN.iter().map(|(n, _)| n.to_string()).collect();
+        if let Ok(entries) = read_dir(templates_dir) {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .for_each(|name| {
+                    if !names.contains(&name) {
+                        names.push(name);
+                    }
+                });
+        }
+        names
+    }
+
+    /// merges templates for the project files present in `terrain_dir` and sets
+    /// versions from version files as environment variables
+    pub fn detect(templates_dir: &Path, terrain_dir: &Path) -> Result<Self> {
+        let mut detected: Vec<&str> = vec![];
+        let mut used: Vec<&str> = vec![];
+        let mut template: Option<Template> = None;
+
+        for (file, name) in PROJECT_FILES {
+            if !terrain_dir.join(file).exists() || used.contains(&name) {
+                continue;
+            }
+            let found = Self::get(templates_dir, name)?;
+            template = Some(match template {
+                Some(template) => template.merge(found),
+                None => found,
+            });
+            detected.push(file);
+            used.push(name);
+        }
+
+        let mut versions = BTreeMap::new();
+        for (file, env) in VERSION_FILES {
+            let path = terrain_dir.join(file);
+            if !path.exists() {
+                continue;
+            }
+            let version = read_to_string(&path).context(format!("failed to read {path:?}"))?;
+            versions.insert(env.to_string(), version.trim().to_string());
+            detected.push(file);
+        }
+
+        if detected.is_empty() {
+            bail!(
+                "failed to detect project type of {terrain_dir:?}, use `--template` to select \
+                 one of the templates: {}",
+                Self::names(templates_dir).join(", ")
+            );
+        }
+
+        let mut template = template.unwrap_or_else(|| {
+            let mut terrain = Biome::default();
+            terrain.set_name(NONE.to_string());
+            Template {
+                terrain,
+                biomes: BTreeMap::new(),
+                source: String::new(),
+            }
+        });
+        template.terrain.append_envs(versions, "detected");
+        template.source = format!("detected {}", detected.join(", "));
+        Ok(template)
+    }
+
+    /// values in `another` take precedence
+    fn merge(self, another: Template) -> Template {
+        let mut biomes = self.biomes;
+        another.biomes.into_iter().for_each(|(name, biome)| {
+            let merged = match biomes.get(&name) {
+                Some(existing) => existing.merge(&biome),
+                None => biome,
+            };
+            biomes.insert(name, merged);
+        });
+
+        let source = if self.source.is_empty() {
+            another.source
+        } else {
+            format!("{} and {}", self.source, another.source)
+        };
+
+        Template {
+            terrain: self.terrain.merge(&another.terrain),
+            biomes,
+            source,
+        }
+    }
+
+    pub fn terrain(&self) -> Terrain {
+        Terrain::new(
+            self.terrain.clone(),
+            self.biomes.clone(),
+            None,
+            AutoApply::default(),
+        )
+    }
+
+    /// terrain.toml contents with comment stating where values are taken from
+    pub fn to_toml(&self, terrain_dir: &Path) -> Result<String> {
+        let toml = self.terrain().to_toml(terrain_dir)?;
+        Ok(format!(
+            "# created by `terrain init` from {}\n{toml}",
+            self.source
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs::{create_dir_all, write};
+    use std::path::Path;
+
+    use anyhow::Result;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::Template;
+    use crate::client::types::terrain::Terrain;
+
+    #[test]
+    fn detects_project_files_and_versions() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        write(terrain_dir.path().join("Cargo.toml"), "")?;
+        write(terrain_dir.path().join("package.json"), "{}")?;
+        write(terrain_dir.path().join(".nvmrc"), "v20.11.0\n")?;
+
+        // user template overrides built-in template
+        let templates_dir = tempdir()?;
+        create_dir_all(templates_dir.path())?;
+        write(
+            templates_dir.path().join("node.toml"),
+            r#"
+[terrain.aliases]
+nt = "npm run test:unit"
+"#,
+        )?;
+
+        let template = Template::detect(templates_dir.path(), terrain_dir.path())?;
+        let terrain = template.terrain();
+
+        let mut expected_envs = BTreeMap::new();
+        expected_envs.insert("NODE_VERSION".to_string(), "v20.11.0".to_string());
+        expected_envs.insert("RUST_BACKTRACE".to_string(), "1".to_string());
+        assert_eq!(terrain.terrain().envs(), &expected_envs);
+        assert_eq!(
+            terrain.terrain().aliases().get("nt").unwrap(),
+            "npm run test:unit"
+        );
+        assert!(terrain.terrain().aliases().contains_key("cbuild"));
+
+        let toml = template.to_toml(terrain_dir.path())?;
+        assert!(toml.starts_with(
+            "# created by `terrain init` from detected Cargo.toml, package.json, .nvmrc\n"
+        ));
+        assert_eq!(Terrain::from_toml(toml)?.terrain(), terrain.terrain());
+
+        Ok(())
+    }
+
+    #[test]
+    fn detected_values_take_precedence_over_template() -> Result<()> {
+        let terrain_dir = tempdir()?;
+        write(terrain_dir.path().join(".python-version"), "3.12\n")?;
+
+        let template = Template::select(Path::new(""), terrain_dir.path(), Some("go"), true)?
+            .expect("template to be selected");
+
+        let toml = template.to_toml(terrain_dir.path())?;
+        assert!(toml.starts_with(
+            "# created by `terrain init` from template 'go' and detected .python-version\n"
+        ));
+        let terrain = template.terrain();
+        assert_eq!(
+            terrain.terrain().envs().get("PYTHON_VERSION").unwrap(),
+            "3.12"
+        );
+        assert_eq!(terrain.terrain().envs().get("GO111MODULE").unwrap(), "on");
+
+        Ok(())
+    }
+
+    #[test]
+    fn fails_for_unknown_template_and_undetected_project() {
+        let err = Template::get(Path::new(""), "cobol").expect_err("template to not exist");
+        assert_eq!(
+            err.to_string(),
+            "template 'cobol' does not exist, available templates: rust, node, python-venv, go, \
+             docker-compose"
+        );
+
+        let terrain_dir = tempdir().unwrap();
+        let err = Template::detect(Path::new(""), terrain_dir.path())
+            .expect_err("project to not be detected");
+        assert!(err.to_string().starts_with("failed to detect project type"));
+    }
+}
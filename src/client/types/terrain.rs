@@ -1,27 +1,31 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Display;
-use std::fs::{read_to_string, write};
-use std::path::Path;
+use std::fs::{exists, read_to_string, remove_file, write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use anyhow::{Context as AnyhowContext, Result, bail};
 use clap::ValueEnum;
+use home::home_dir;
 #[cfg(feature = "terrain-schema")]
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use toml_edit::DocumentMut;
-use tracing::info;
+use toml_edit::{DocumentMut, ImDocument};
+use tracing::{info, warn};
 
 use crate::client::args::BiomeArg;
 use crate::client::types::biome::Biome;
 use crate::client::types::commands::Commands;
-use crate::client::types::context::Context;
+use crate::client::types::context::{Context, get_terrain_dir};
+use crate::client::types::fragment::{INCLUDES_STATE, includes_state, load_includes};
 use crate::client::validation::{
-    Target, ValidationFixAction, ValidationMessageLevel, ValidationResults,
+    AllowComments, IdentifierType, ReportedResult, Rule, Severity, Target, ValidationFixAction,
+    ValidationMessageLevel, ValidationResult, ValidationResults,
 };
 use crate::common::constants::{
-    AUTO_APPLY_ALL, AUTO_APPLY_BACKGROUND, AUTO_APPLY_ENABLED, AUTO_APPLY_OFF, AUTO_APPLY_REPLACE,
-    BACKGROUND, BIOMES, CONSTRUCTORS, DESTRUCTORS, EXAMPLE_BIOME, FOREGROUND, NONE, TERRAIN,
+    AUTO_APPLY, AUTO_APPLY_ALL, AUTO_APPLY_BACKGROUND, AUTO_APPLY_ENABLED, AUTO_APPLY_OFF,
+    AUTO_APPLY_REPLACE, BACKGROUND, BIOMES, CONSTRUCTORS, DEFAULT_BIOME, DESTRUCTORS,
+    EXAMPLE_BIOME, FOREGROUND, NONE, TERRAIN, TERRAIN_SOURCES,
 };
 use crate::common::types::command::Command;
 
@@ -41,6 +45,41 @@ impl AutoApply {
     pub fn is_background_enabled(&self) -> bool {
         matches!(self, AutoApply::Background) || matches!(self, AutoApply::All)
     }
+
+    /// auto apply used when terrain is applied on top of already active parent terrain,
+    /// active shell is never replaced as it belongs to the parent terrain
+    pub fn nested(&self) -> Self {
+        match self {
+            AutoApply::All => AutoApply::Background,
+            AutoApply::Replace => AutoApply::Enabled,
+            other => other.clone(),
+        }
+    }
+
+    /// auto apply used when terrain is entered while another terrain is active
+    ///
+    /// if a terrain is already active, terrain is only applied if it inherits the
+    /// active terrain, i.e. when moving from parent into child terrain.
+    /// moving to the active terrain, its parent or unrelated terrain does not apply anything.
+    pub(crate) fn with_active_terrain(
+        &self,
+        active_terrain_dir: Option<&str>,
+        parent_dirs: &[impl AsRef<Path>],
+    ) -> Self {
+        match active_terrain_dir {
+            Some(active) if !active.is_empty() => {
+                if parent_dirs
+                    .iter()
+                    .any(|dir| dir.as_ref() == Path::new(active))
+                {
+                    self.nested()
+                } else {
+                    AutoApply::Off
+                }
+            }
+            _ => self.clone(),
+        }
+    }
 }
 
 impl Display for AutoApply {
@@ -70,27 +109,101 @@ pub struct Terrain {
     name: String,
     default_biome: Option<String>,
     auto_apply: AutoApply,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    include: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    inherit_parent: bool,
     terrain: Biome,
     biomes: BTreeMap<String, Biome>,
+    /// severity of validation rules, overrides severity set in terrainium.toml
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    validation: BTreeMap<Rule, Severity>,
+
+    #[serde(skip)]
+    included: Vec<PathBuf>,
+    #[serde(skip)]
+    parent: Option<Box<ParentTerrain>>,
+    #[serde(skip)]
+    config_validation: BTreeMap<Rule, Severity>,
+    #[serde(skip)]
+    allowed: AllowComments,
+}
+
+/// terrain of the nearest ancestor directory layered beneath the terrain
+#[derive(Clone)]
+struct ParentTerrain {
+    dir: PathBuf,
+    toml_path: PathBuf,
+    terrain: Terrain,
+}
+
+impl ParentTerrain {
+    /// parent is merged for the biome with the same name if it exists
+    fn merged(&self, biome_name: &str) -> Result<Biome> {
+        let selected = if self.terrain.selectable_biomes().contains(biome_name) {
+            BiomeArg::Some(biome_name.to_string())
+        } else {
+            BiomeArg::None
+        };
+
+        let mut merged = self.terrain.merged(&selected)?.qualified(&self.toml_path);
+        // commands of parent terrain run in parent terrain directory by default
+        merged.substitute_cwd(&self.dir).context(format!(
+            "failed to substitute cwd for parent terrain {:?}",
+            self.dir
+        ))?;
+        Ok(merged)
+    }
 }
 
 impl Terrain {
+    /// validates terrain and fixes the fixable values only in memory, terrain.toml is not
+    /// modified, run `terrain validate --fix` to write the fixes.
+    ///
+    /// returned toml document is the unmodified terrain.toml
     pub fn get_validated_and_fixed_terrain(context: &Context) -> Result<(Self, DocumentMut)> {
+        let (unvalidated_terrain, toml) = Self::read(context)?;
+        let (terrain, fixed_toml) =
+            Self::validate_and_fix(context.terrain_dir(), unvalidated_terrain, toml.clone())?;
+
+        if fixed_toml.is_some() {
+            warn!(
+                "terrain.toml has values that can be fixed, run 'terrain validate --fix' to update it"
+            );
+        }
+
+        Ok((terrain.with_parent(context.terrain_dir())?, toml))
+    }
+
+    /// returns current contents of terrain.toml and the contents after fixing the
+    /// fixable values, fixed contents are `None` if there is nothing to fix
+    pub fn get_fixed_toml(context: &Context) -> Result<(String, Option<String>)> {
+        let (unvalidated_terrain, toml) = Self::read(context)?;
+        let current = toml.to_string();
+        let (_, fixed_toml) =
+            Self::validate_and_fix(context.terrain_dir(), unvalidated_terrain, toml)?;
+        Ok((current, fixed_toml.map(|fixed| fixed.to_string())))
+    }
+
+    fn read(context: &Context) -> Result<(Self, DocumentMut)> {
         let terrain_toml =
             read_to_string(context.toml_path()).context("failed to read terrain.toml")?;
         let toml = terrain_toml
             .parse::<DocumentMut>()
             .context("failed to parse terrain toml")?;
-        let unvalidated_terrain = Self::from_toml(terrain_toml)?;
-        Self::store_and_get_fixed_terrain(context, unvalidated_terrain, toml)
+        let unvalidated_terrain = Self::from_toml(terrain_toml)?
+            .with_includes(context.toml_path(), context.terrain_dir())?
+            .with_validation_config(context.config().validation());
+        Ok((unvalidated_terrain, toml))
     }
 
-    pub fn store_and_get_fixed_terrain(
-        context: &Context,
+    /// fixed toml is returned only if terrain had fixable values
+    pub fn validate_and_fix(
+        terrain_dir: &Path,
         unvalidated_terrain: Terrain,
         terrain_toml: DocumentMut,
-    ) -> Result<(Self, DocumentMut)> {
-        let validation_results = unvalidated_terrain.validate(context.terrain_dir());
+    ) -> Result<(Self, Option<DocumentMut>)> {
+        let validation_results = unvalidated_terrain.configured(terrain_dir);
         validation_results.print_validation_message();
 
         if validation_results
@@ -102,15 +215,13 @@ impl Terrain {
         }
 
         if !validation_results.is_fixable() {
-            return Ok((unvalidated_terrain, terrain_toml));
+            return Ok((unvalidated_terrain, None));
         }
 
-        info!("updating the terrain with fixable values");
+        info!("fixing the fixable values of terrain");
         let (fixed, fixed_toml) =
             Terrain::fix_invalid_values(&unvalidated_terrain, terrain_toml, validation_results);
-        write(context.toml_path(), fixed_toml.to_string())
-            .context("failed to write fixed terrain.toml")?;
-        Ok((fixed, fixed_toml))
+        Ok((fixed, Some(fixed_toml)))
     }
 
     pub fn new(
@@ -131,9 +242,16 @@ impl Terrain {
             name,
             schema: schema_url(),
             auto_apply,
+            include: vec![],
+            inherit_parent: false,
             terrain,
             biomes,
             default_biome,
+            validation: BTreeMap::new(),
+            included: vec![],
+            parent: None,
+            config_validation: BTreeMap::new(),
+            allowed: AllowComments::default(),
         }
     }
 
@@ -165,13 +283,308 @@ impl Terrain {
         self.auto_apply = auto_apply;
     }
 
+    /// absolute paths of all the files included in terrain, including nested includes
+    pub fn included(&self) -> &Vec<PathBuf> {
+        &self.included
+    }
+
+    /// merges fragments specified in `include` beneath the terrain
+    ///
+    /// values from terrain.toml take precedence over included files, later entries in
+    /// `include` take precedence over earlier ones, and a fragment takes precedence
+    /// over the files it includes.
+    pub(crate) fn with_includes(mut self, toml_path: &Path, terrain_dir: &Path) -> Result<Self> {
+        if self.include.is_empty() {
+            return Ok(self);
+        }
+
+        let fragments = load_includes(toml_path, &self.include, terrain_dir)?;
+
+        let mut terrain = Biome::default();
+        let mut biomes: BTreeMap<String, Biome> = BTreeMap::new();
+        fragments.iter().for_each(|fragment| {
+            terrain = fragment.terrain.merge_included(&terrain);
+            fragment.biomes.iter().for_each(|(name, biome)| {
+                let merged = match biomes.get(name) {
+                    Some(included) => biome.merge_included(included),
+                    None => biome.clone(),
+                };
+                biomes.insert(name.clone(), merged);
+            });
+        });
+
+        self.terrain = self.terrain.merge_included(&terrain);
+        biomes.into_iter().for_each(|(name, included)| {
+            let merged = match self.biomes.get(&name) {
+                Some(biome) => biome.merge_included(&included),
+                None => included,
+            };
+            self.biomes.insert(name, merged);
+        });
+        self.included = fragments
+            .into_iter()
+            .map(|fragment| fragment.path)
+            .collect();
+
+        Ok(self)
+    }
+
+    /// directories of the ancestor terrains layered beneath this terrain, nearest first
+    pub fn parent_dirs(&self) -> Vec<&Path> {
+        let mut dirs = vec![];
+        let mut parent = &self.parent;
+        while let Some(p) = parent {
+            dirs.push(p.dir.as_path());
+            parent = &p.terrain.parent;
+        }
+        dirs
+    }
+
+    /// layers the terrain of nearest ancestor directory beneath the terrain
+    /// if `inherit_parent` is set
+    ///
+    /// parent terrain is validated but never written, biomes defined only in
+    /// parent terrain can be selected but are not added to the biomes of terrain.
+    pub(crate) fn with_parent(mut self, terrain_dir: &Path) -> Result<Self> {
+        if !self.inherit_parent {
+            return Ok(self);
+        }
+
+        let (parent_dir, toml_path, parent_toml) = self.parent_toml(terrain_dir)?;
+        let toml = parent_toml
+            .parse::<DocumentMut>()
+            .context(format!("failed to parse parent terrain {toml_path:?}"))?;
+        let unvalidated = Self::from_toml(parent_toml)
+            .context(format!("failed to parse parent terrain {toml_path:?}"))?
+            .with_includes(&toml_path, &parent_dir)?
+            .with_validation_config(&self.config_validation);
+
+        let results = unvalidated.configured(&parent_dir);
+        results
+            .clone()
+            .qualified(&toml_path)
+            .print_validation_message();
+        if results
+            .results_ref()
+            .iter()
+            .any(|r| r.level == ValidationMessageLevel::Error)
+        {
+            bail!("parent terrain {toml_path:?} had validation errors");
+        }
+
+        let parent = if results.is_fixable() {
+            Self::fix_invalid_values(&unvalidated, toml, results).0
+        } else {
+            unvalidated.clone()
+        };
+        let parent = parent.with_parent(&parent_dir)?;
+
+        self.parent = Some(Box::new(ParentTerrain {
+            dir: parent_dir,
+            toml_path,
+            terrain: parent,
+        }));
+        Ok(self)
+    }
+
+    /// directory, path and contents of terrain.toml of the parent terrain
+    fn parent_toml(&self, terrain_dir: &Path) -> Result<(PathBuf, PathBuf, String)> {
+        let home_dir = home_dir().context("failed to get home directory")?;
+        let Some((parent_dir, toml_path)) = terrain_dir
+            .parent()
+            .and_then(|parent| get_terrain_dir(&home_dir, parent))
+        else {
+            bail!("`inherit_parent` is set but parent terrain for {terrain_dir:?} does not exist");
+        };
+
+        let parent_toml = read_to_string(&toml_path)
+            .context(format!("failed to read parent terrain {toml_path:?}"))?;
+        Ok((parent_dir, toml_path, parent_toml))
+    }
+
+    /// validation results of parent terrains without printing them or failing on
+    /// errors, located in terrain.toml of the respective parent terrain
+    pub(crate) fn parent_reports(&self, terrain_dir: &Path) -> Result<Vec<ReportedResult>> {
+        if !self.inherit_parent {
+            return Ok(vec![]);
+        }
+
+        let (parent_dir, toml_path, parent_toml) = self.parent_toml(terrain_dir)?;
+        let toml = ImDocument::parse(parent_toml.as_str())
+            .context(format!("failed to parse parent terrain {toml_path:?}"))?;
+        let parent = Self::from_toml(parent_toml.clone())
+            .context(format!("failed to parse parent terrain {toml_path:?}"))?;
+        let parent = match parent.with_includes(&toml_path, &parent_dir) {
+            Ok(parent) => parent.with_validation_config(&self.config_validation),
+            Err(err) => {
+                return Ok(vec![
+                    ValidationResult::invalid_include(&err).report(&toml_path, &toml),
+                ]);
+            }
+        };
+
+        let mut reports: Vec<ReportedResult> = parent
+            .configured(&parent_dir)
+            .results_ref()
+            .iter()
+            .map(|result| result.report(&toml_path, &toml))
+            .collect();
+        reports.extend(parent.parent_reports(&parent_dir)?);
+        Ok(reports)
+    }
+
+    /// all the files terrain is loaded from i.e. terrain.toml, included files and
+    /// files of parent terrains
+    pub(crate) fn sources(&self, toml_path: &Path) -> Vec<PathBuf> {
+        let mut sources = vec![toml_path.to_path_buf()];
+        sources.extend(self.included.iter().cloned());
+        if let Some(parent) = &self.parent {
+            sources.extend(parent.terrain.sources(&parent.toml_path));
+        }
+        sources
+    }
+
+    /// stores the paths of sources, used by shell to detect changes in active terrain
+    pub(crate) fn store_sources(&self, toml_path: &Path, scripts_dir: &Path) -> Result<()> {
+        let sources: String = self
+            .sources(toml_path)
+            .iter()
+            .map(|source| format!("{}\n", source.display()))
+            .collect();
+        write(scripts_dir.join(TERRAIN_SOURCES), sources).context("failed to write sources")
+    }
+
+    /// checks if included files were modified since the scripts were last generated
+    pub(crate) fn includes_changed(&self, scripts_dir: &Path) -> Result<bool> {
+        let state_file = scripts_dir.join(INCLUDES_STATE);
+        if self.included.is_empty() {
+            return exists(&state_file).context("failed to check if includes state exists");
+        }
+
+        let current = includes_state(&self.included)?;
+        let stored = read_to_string(&state_file).unwrap_or_default();
+        Ok(current != stored)
+    }
+
+    /// stores the modification time of included files, used to detect changes
+    pub(crate) fn store_includes_state(&self, scripts_dir: &Path) -> Result<()> {
+        let state_file = scripts_dir.join(INCLUDES_STATE);
+        if self.included.is_empty() {
+            if exists(&state_file).context("failed to check if includes state exists")? {
+                remove_file(&state_file).context("failed to remove includes state")?;
+            }
+            return Ok(());
+        }
+
+        write(&state_file, includes_state(&self.included)?)
+            .context("failed to write includes state")
+    }
+
+    /// names of the biomes that can be selected, including biomes defined only in
+    /// parent terrains
+    pub(crate) fn selectable_biomes(&self) -> BTreeSet<String> {
+        let mut biomes: BTreeSet<String> = self.biomes.keys().cloned().collect();
+        if let Some(parent) = &self.parent {
+            biomes.extend(parent.terrain.selectable_biomes());
+        }
+        biomes
+    }
+
+    /// names of the biomes defined in parent terrains, parent terrains are read if
+    /// they are not layered yet as terrain is validated before layering them
+    fn parent_biomes(&self, terrain_dir: &Path) -> BTreeSet<String> {
+        if let Some(parent) = &self.parent {
+            return parent.terrain.selectable_biomes();
+        }
+        if !self.inherit_parent {
+            return BTreeSet::new();
+        }
+
+        // errors in parent terrain are reported when it is layered
+        let Ok((parent_dir, toml_path, parent_toml)) = self.parent_toml(terrain_dir) else {
+            return BTreeSet::new();
+        };
+        Self::from_toml(parent_toml)
+            .and_then(|parent| parent.with_includes(&toml_path, &parent_dir))
+            .map(|parent| {
+                let mut biomes: BTreeSet<String> = parent.biomes.keys().cloned().collect();
+                biomes.extend(parent.parent_biomes(&parent_dir));
+                biomes
+            })
+            .unwrap_or_default()
+    }
+
+    /// name of the selected biome if it is defined only in parent terrains
+    fn parent_only_biome<'a>(&'a self, selected_biome: &'a BiomeArg) -> Option<&'a String> {
+        let name = match selected_biome {
+            BiomeArg::None => return None,
+            BiomeArg::Default => self.default_biome.as_ref()?,
+            BiomeArg::Some(name) => name,
+        };
+        let parent = self.parent.as_ref()?;
+        (!self.biomes.contains_key(name) && parent.terrain.selectable_biomes().contains(name))
+            .then_some(name)
+    }
+
     pub fn merged(&self, selected_biome: &BiomeArg) -> Result<Biome> {
+        let layers = self.layers(selected_biome)?;
+        let mut merged = layers
+            .iter()
+            .skip(1)
+            .fold(self.terrain.clone(), |merged, layer| merged.merge(layer));
+        if let Some(name) = self.parent_only_biome(selected_biome) {
+            merged.set_name(name.clone());
+        }
+
+        match &self.parent {
+            Some(parent) => Ok(merged.merge_parent(&parent.merged(&merged.name())?)),
+            None => Ok(merged),
+        }
+    }
+
+    /// biomes in the order they are merged for selected biome
+    ///
+    /// main terrain is always first and selected biome is last, biomes that are
+    /// extended by selected biome are placed in between in depth first order of
+    /// `extends`. if a biome is extended more than once only first occurrence is used.
+    /// if selected biome is defined only in parent terrain, only main terrain is used.
+    pub(crate) fn layers(&self, selected_biome: &BiomeArg) -> Result<Vec<&Biome>> {
+        if self.parent_only_biome(selected_biome).is_some() {
+            return Ok(vec![&self.terrain]);
+        }
         let selected = self.select_biome(selected_biome)?;
-        if selected == &self.terrain {
-            Ok(self.terrain.clone())
-        } else {
-            Ok(self.terrain.merge(selected))
+        let mut layers = vec![&self.terrain];
+        if selected != &self.terrain {
+            self.linearize(selected, &mut vec![], &mut layers)?;
         }
+        Ok(layers)
+    }
+
+    fn linearize<'a>(
+        &'a self,
+        biome: &'a Biome,
+        stack: &mut Vec<String>,
+        layers: &mut Vec<&'a Biome>,
+    ) -> Result<()> {
+        let name = biome.name();
+        if stack.contains(&name) {
+            stack.push(name);
+            bail!("biomes have cyclic `extends`: {}", stack.join(" -> "));
+        }
+
+        stack.push(name.clone());
+        for parent in biome.extends() {
+            let Some(parent_biome) = self.biomes.get(parent) else {
+                bail!("biome '{name}' extends biome '{parent}' that does not exist");
+            };
+            self.linearize(parent_biome, stack, layers)?;
+        }
+        stack.pop();
+
+        if !layers.iter().any(|layer| layer.name() == name) {
+            layers.push(biome);
+        }
+        Ok(())
     }
 
     pub(crate) fn select_biome(&self, selected: &BiomeArg) -> Result<&Biome> {
@@ -201,15 +614,201 @@ impl Terrain {
     pub fn validate<'a>(&'a self, terrain_dir: &'a Path) -> ValidationResults<'a> {
         // validate terrain
         let mut results = self.terrain.validate(NONE, terrain_dir);
+        results.append(self.validate_extends());
 
         // all biomes
         self.biomes.iter().for_each(|(biome_name, biome)| {
             results.append(biome.validate(biome_name, terrain_dir))
         });
 
+        results.append(self.validate_default_biome(terrain_dir));
+        results.append(self.validate_unused_biomes());
+        results.append(self.validate_redundant_overrides());
+        results.append(self.validate_auto_apply_sudo());
+
         results
     }
 
+    /// validation results after applying severity of rules set in `[validation]` section
+    /// of terrain.toml and terrainium.toml, and `# terrainium:allow(<rule-id>)` comments
+    pub(crate) fn configured<'a>(&'a self, terrain_dir: &'a Path) -> ValidationResults<'a> {
+        self.apply_validation_config(self.validate(terrain_dir))
+    }
+
+    /// applies severity of rules and allow comments of this terrain to `results`
+    pub(crate) fn apply_validation_config<'a>(
+        &self,
+        results: ValidationResults<'a>,
+    ) -> ValidationResults<'a> {
+        let mut severities = self.config_validation.clone();
+        severities.extend(
+            self.validation
+                .iter()
+                .map(|(rule, severity)| (*rule, *severity)),
+        );
+        results.configured(&severities, &self.allowed)
+    }
+
+    /// severity of rules set in terrainium.toml, terrain.toml takes precedence
+    pub(crate) fn with_validation_config(mut self, validation: &BTreeMap<Rule, Severity>) -> Self {
+        self.config_validation = validation.clone();
+        self
+    }
+
+    fn validate_default_biome<'a>(&self, terrain_dir: &Path) -> ValidationResults<'a> {
+        let mut results = HashSet::new();
+
+        if let Some(default_biome) = &self.default_biome
+            && !self.biomes.contains_key(default_biome)
+            && !self.parent_biomes(terrain_dir).contains(default_biome)
+        {
+            results.insert(ValidationResult {
+                level: ValidationMessageLevel::Error,
+                message: format!("default biome '{default_biome}' is not defined in biomes."),
+                r#for: format!("{NONE}({DEFAULT_BIOME})"),
+                rule: Rule::MissingDefaultBiome,
+                fix_action: ValidationFixAction::None,
+            });
+        }
+
+        ValidationResults::new(false, results)
+    }
+
+    /// biomes that are neither default biome nor extended by other biomes,
+    /// are only used when selected explicitly
+    fn validate_unused_biomes<'a>(&self) -> ValidationResults<'a> {
+        let results = self
+            .biomes
+            .keys()
+            .filter(|name| self.default_biome.as_ref() != Some(name))
+            .filter(|name| {
+                !self
+                    .biomes
+                    .values()
+                    .any(|biome| biome.extends().contains(&name.as_str()))
+            })
+            .map(|name| ValidationResult {
+                level: ValidationMessageLevel::Info,
+                message: format!(
+                    "biome '{name}' is not default biome and is not extended by other biomes, \
+                     it is only used when selected with '--biome {name}'."
+                ),
+                r#for: name.clone(),
+                rule: Rule::UnusedBiome,
+                fix_action: ValidationFixAction::None,
+            })
+            .collect();
+
+        ValidationResults::new(false, results)
+    }
+
+    /// envs and aliases of biomes that have the same value as main terrain
+    fn validate_redundant_overrides<'a>(&self) -> ValidationResults<'a> {
+        let mut results = HashSet::new();
+
+        self.biomes.iter().for_each(|(biome_name, biome)| {
+            let overrides = [
+                (IdentifierType::Env, biome.envs(), self.terrain.envs()),
+                (
+                    IdentifierType::Alias,
+                    biome.aliases(),
+                    self.terrain.aliases(),
+                ),
+            ];
+            overrides
+                .into_iter()
+                .for_each(|(data_type, values, terrain_values)| {
+                    values
+                        .iter()
+                        .filter(|(k, v)| terrain_values.get(*k) == Some(v))
+                        .for_each(|(k, _)| {
+                            results.insert(ValidationResult {
+                                level: ValidationMessageLevel::Warn,
+                                message: format!(
+                                    "{data_type} '{k}' has same value as in main terrain, \
+                                 it can be removed from biome '{biome_name}'."
+                                ),
+                                r#for: format!("{biome_name}({data_type}:{k})"),
+                                rule: Rule::RedundantOverride,
+                                fix_action: ValidationFixAction::None,
+                            });
+                        });
+                });
+        });
+
+        ValidationResults::new(false, results)
+    }
+
+    /// sudo commands block the shell for password when terrain is applied on `cd`
+    fn validate_auto_apply_sudo<'a>(&self) -> ValidationResults<'a> {
+        let mut results = HashSet::new();
+
+        if self.auto_apply == AutoApply::Off {
+            return ValidationResults::new(false, results);
+        }
+
+        std::iter::once((NONE, &self.terrain))
+            .chain(
+                self.biomes
+                    .iter()
+                    .map(|(name, biome)| (name.as_str(), biome)),
+            )
+            .for_each(|(biome_name, biome)| {
+                [biome.constructors(), biome.destructors()]
+                    .into_iter()
+                    .flat_map(|commands| commands.foreground().iter().chain(commands.background()))
+                    .filter(|command| command.is_sudo())
+                    .for_each(|command| {
+                        results.insert(ValidationResult {
+                            level: ValidationMessageLevel::Warn,
+                            message: format!(
+                                "auto_apply is '{}' but biome '{biome_name}' runs sudo command \
+                                 exe: '{}' args: '{}', changing directory will wait for \
+                                 password.",
+                                self.auto_apply,
+                                command.exe(),
+                                command.args().join(" ")
+                            ),
+                            r#for: format!("{NONE}({AUTO_APPLY})"),
+                            rule: Rule::AutoApplySudo,
+                            fix_action: ValidationFixAction::None,
+                        });
+                    });
+            });
+
+        ValidationResults::new(false, results)
+    }
+
+    fn validate_extends<'a>(&self) -> ValidationResults<'a> {
+        let mut results = HashSet::new();
+
+        if !self.terrain.extends().is_empty() {
+            results.insert(ValidationResult {
+                level: ValidationMessageLevel::Error,
+                message: "main terrain cannot extend biomes, `extends` can only be used in \
+                          biomes"
+                    .to_string(),
+                r#for: format!("{NONE}(extends)"),
+                rule: Rule::InvalidExtends,
+                fix_action: ValidationFixAction::None,
+            });
+        }
+
+        self.biomes.iter().for_each(|(biome_name, biome)| {
+            if let Err(err) = self.linearize(biome, &mut vec![], &mut vec![]) {
+                results.insert(ValidationResult {
+                    level: ValidationMessageLevel::Error,
+                    message: err.to_string(),
+                    r#for: format!("{biome_name}(extends)"),
+                    rule: Rule::InvalidExtends,
+                    fix_action: ValidationFixAction::None,
+                });
+            }
+        });
+
+        ValidationResults::new(false, results)
+    }
+
     pub fn fix_invalid_values(
         terrain: &Terrain,
         mut toml: DocumentMut,
@@ -324,6 +923,11 @@ impl Terrain {
     pub fn from_toml(toml_str: String) -> Result<Self> {
         let mut terrain: Self =
             toml::from_str(&toml_str).context("failed to parse terrain from toml")?;
+        terrain.allowed = AllowComments::from_toml(
+            &toml_str
+                .parse::<DocumentMut>()
+                .context("failed to parse terrain toml")?,
+        );
 
         terrain.terrain.set_name(NONE.to_string());
         terrain.biomes.iter_mut().for_each(|(name, biome)| {
@@ -433,23 +1037,25 @@ pub mod tests {
     use std::env::VarError;
     use std::fs::{copy, create_dir_all, metadata, read_to_string, set_permissions, write};
     use std::os::unix::fs::{PermissionsExt, symlink};
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
 
     use pretty_assertions::assert_eq;
     use serial_test::serial;
     use tempfile::tempdir;
     use toml_edit::DocumentMut;
 
+    use crate::client::args::BiomeArg;
     use crate::client::test_utils::constants::{
         WITH_EXAMPLE_TERRAIN_TOML_COMMENTS, WITH_EXAMPLE_TERRAIN_TOML_COMMENTS_SPACES,
     };
     use crate::client::test_utils::{restore_env_var, set_env_var};
-    use crate::client::types::biome::Biome;
+    use crate::client::types::biome::{Biome, Extends};
     use crate::client::types::commands::Commands;
+    use crate::client::types::config::Config;
     use crate::client::types::context::Context;
     use crate::client::types::terrain::{AutoApply, Terrain};
     use crate::client::validation::{
-        Target, ValidationFixAction, ValidationMessageLevel, ValidationResult,
+        Rule, Target, ValidationFixAction, ValidationMessageLevel, ValidationResult,
     };
     use crate::common::constants::{NONE, TERRAIN_TOML};
     use crate::common::execute::MockExecutor;
@@ -563,7 +1169,9 @@ pub mod tests {
         let path = PathBuf::new();
         let messages = terrain.validate(&path).results();
 
-        assert_eq!(messages.len(), 44);
+        // 23 results are from redundant override and unused biome rules as
+        // test_biome has same values as main terrain
+        assert_eq!(messages.len(), 67);
 
         [NONE, "test_biome"].iter().for_each(|biome_name| {
             ["env", "alias"].iter().for_each(|identifier_type| {
@@ -572,6 +1180,7 @@ pub mod tests {
                         level: ValidationMessageLevel::Error,
                         message: "empty identifier is not allowed".to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate empty identifier message for \
@@ -584,6 +1193,7 @@ pub mod tests {
                         message: "identifier 'TEST WITH SPACES' is invalid as it contains spaces"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier with spaces message for \
@@ -607,6 +1217,7 @@ pub mod tests {
                         message: "trimming spaces from identifier: ' WITH_LEADING_SPACES'"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action,
                     }),
                     "failed to validate trimming leading spaces from identifier message for \
@@ -630,6 +1241,7 @@ pub mod tests {
                         message: "trimming spaces from identifier: 'WITH_TRAILING_SPACES '"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action,
                     }),
                     "failed to validate trimming trailing spaces from identifier message for \
@@ -642,6 +1254,7 @@ pub mod tests {
                         message: "identifier '1STARTING_WITH_NUM' cannot start with number"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier starting with number message for \
@@ -655,6 +1268,7 @@ pub mod tests {
                                   identifier name can only include [a-zA-Z0-9_] characters."
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier with invalid chars for \
@@ -667,6 +1281,7 @@ pub mod tests {
                         message: "identifier '1INVALID-#. (' is invalid as it contains spaces"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier with spaces message for ' 1INVALID-#. ( ' and \
@@ -689,6 +1304,7 @@ pub mod tests {
                         level: ValidationMessageLevel::Warn,
                         message: "trimming spaces from identifier: ' 1INVALID-#. ( '".to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action: fix_action.clone(),
                     }),
                     "failed to validate trimming spaces environment variable message for ' \
@@ -700,6 +1316,7 @@ pub mod tests {
                         level: ValidationMessageLevel::Warn,
                         message: "trimming spaces from identifier: ' 1INVALID-#. ( '".to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action,
                     }),
                     "failed to validate trimming spaces environment variable message for ' \
@@ -711,6 +1328,7 @@ pub mod tests {
                         level: ValidationMessageLevel::Error,
                         message: "identifier '1INVALID-#. (' cannot start with number".to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier starting with number for '1INVALID-#. (' and \
@@ -724,6 +1342,7 @@ pub mod tests {
                                   identifier name can only include [a-zA-Z0-9_] characters."
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::InvalidIdentifier,
                         fix_action: ValidationFixAction::None,
                     }),
                     "failed to validate identifier with invalid chars for '1INVALID-#. (' and \
@@ -952,7 +1571,9 @@ pub mod tests {
 
         let messages = terrain.validate(test_dir.path()).results();
 
-        assert_eq!(messages.len(), 160);
+        // 2 results are from redundant override and unused biome rules as
+        // test_biome has same values as main terrain
+        assert_eq!(messages.len(), 162);
         [NONE, "test_biome"].iter().for_each(|biome_name| {
             ["constructor", "destructor"]
                 .iter()
@@ -970,6 +1591,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate empty exe for \
@@ -983,6 +1605,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate whitespace not being present in exe for \
@@ -1002,6 +1625,7 @@ pub mod tests {
                                      be run."
                                 ),
                                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                                rule: Rule::ExeNotInPath,
                                 fix_action: ValidationFixAction::None,
                             };
 
@@ -1025,6 +1649,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidCwd,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate relative cwd does not exist for \
@@ -1041,6 +1666,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidCwd,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate absolute cwd does not exist for \
@@ -1058,6 +1684,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidCwd,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate absolute cwd does not exist for \
@@ -1075,6 +1702,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidCwd,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate cwd not a directory exist for \
@@ -1094,6 +1722,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidCwd,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate symlink cwd file for \
@@ -1117,6 +1746,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::UntrimmedExe,
                                     fix_action,
                                 }),
                                 "failed to validate exe leading spaces for \
@@ -1140,6 +1770,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::UntrimmedExe,
                                     fix_action,
                                 }),
                                 "failed to validate exe trailing for \
@@ -1157,6 +1788,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate exe not having execute permission for \
@@ -1175,6 +1807,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate symlink exe not having execute permission for \
@@ -1192,6 +1825,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate exe being not in present in relative path for \
@@ -1209,6 +1843,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate exe being not in present in relative path for \
@@ -1226,6 +1861,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::InvalidExe,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate exe absolute path not being present for \
@@ -1243,6 +1879,7 @@ pub mod tests {
                                         r#for: format!(
                                             "{biome_name}({operation_type}:{commands_type})"
                                         ),
+                                        rule: Rule::SudoCommand,
                                         fix_action: ValidationFixAction::None,
                                     }),
                                     "failed to validate exe containing sudo for \
@@ -1260,6 +1897,7 @@ pub mod tests {
                                         r#for: format!(
                                             "{biome_name}({operation_type}:{commands_type})"
                                         ),
+                                        rule: Rule::SudoCommand,
                                         fix_action: ValidationFixAction::None,
                                     }),
                                     "failed to validate exe containing sudo for \
@@ -1281,6 +1919,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::UndefinedVariable,
                                     fix_action: ValidationFixAction::None,
                                 }),
                                 "failed to validate cwd with env var for \
@@ -1368,7 +2007,9 @@ pub mod tests {
         }
         let messages = before.validate(test_dir.path()).results();
 
-        assert_eq!(messages.len(), 24);
+        // 5 results are from redundant override and unused biome rules as
+        // test_biome has same values as main terrain
+        assert_eq!(messages.len(), 29);
         [NONE, "test_biome"].iter().for_each(|biome_name| {
             ["env", "alias"].iter().for_each(|identifier_type| {
                 let fix_action = if identifier_type == &"env" {
@@ -1388,6 +2029,7 @@ pub mod tests {
                         message: "trimming spaces from identifier: ' WITH_LEADING_SPACES'"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action,
                     }),
                     "failed to validate trimming leading spaces from identifier message for \
@@ -1411,6 +2053,7 @@ pub mod tests {
                         message: "trimming spaces from identifier: 'WITH_TRAILING_SPACES '"
                             .to_string(),
                         r#for: format!("{biome_name}({identifier_type})"),
+                        rule: Rule::UntrimmedIdentifier,
                         fix_action,
                     }),
                     "failed to validate trimming trailing spaces from identifier message for \
@@ -1441,6 +2084,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::UntrimmedExe,
                                     fix_action,
                                 }),
                                 "failed to validate exe leading spaces for \
@@ -1464,6 +2108,7 @@ pub mod tests {
                                     r#for: format!(
                                         "{biome_name}({operation_type}:{commands_type})"
                                     ),
+                                    rule: Rule::UntrimmedExe,
                                     fix_action,
                                 }),
                                 "failed to validate exe trailing for \
@@ -1483,7 +2128,14 @@ pub mod tests {
             Terrain::fix_invalid_values(&terrain, toml, before.validate(test_dir.path()));
         let fixed_result = fixed.validate(test_dir.path());
 
-        assert!(fixed_result.results().is_empty());
+        // only results of test_biome having same values as main terrain remain
+        assert!(!fixed_result.is_fixable());
+        assert!(
+            fixed_result
+                .results()
+                .iter()
+                .all(|result| matches!(result.rule, Rule::RedundantOverride | Rule::UnusedBiome))
+        );
 
         unsafe {
             restore_env_var("PATH", real_path);
@@ -1506,11 +2158,702 @@ pub mod tests {
             MockExecutor::new(),
         );
 
-        Terrain::get_validated_and_fixed_terrain(&context).expect("terrain to fixed");
+        let (terrain, _) =
+            Terrain::get_validated_and_fixed_terrain(&context).expect("terrain to fixed");
+
+        // fixes are only applied in memory
+        let actual = read_to_string(&terrain_toml).unwrap();
+        let unfixed = read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS_SPACES).unwrap();
+        assert_eq!(unfixed, actual);
+        assert!(terrain.terrain().envs().contains_key("PAGER"));
+
+        let (current, fixed) = Terrain::get_fixed_toml(&context).expect("terrain to be fixed");
+        assert_eq!(unfixed, current);
+        assert_eq!(
+            read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS).unwrap(),
+            fixed.expect("fixed toml to be present")
+        );
+    }
+
+    fn biome_with_env(name: &str, env: (&str, &str), extends: Option<Extends>) -> Biome {
+        let mut biome = Biome::default();
+        biome.set_name(name.to_string());
+        biome.add_envs(vec![env]);
+        biome.add_fg_constructors(vec![Command::new(
+            "/bin/echo".to_string(),
+            vec![name.to_string()],
+            None,
+        )]);
+        biome.set_extends(extends);
+        biome
+    }
+
+    fn terrain_with_extends(biomes: Vec<Biome>) -> Terrain {
+        let mut terrain = Terrain::default();
+        terrain.terrain_mut().add_envs(vec![("LAYER", "terrain")]);
+        biomes
+            .into_iter()
+            .for_each(|biome| add_biome(&mut terrain, biome.name(), biome));
+        terrain
+    }
+
+    #[test]
+    fn parses_extends_from_toml() {
+        let toml = r#"
+name = "terrainium"
+auto_apply = "off"
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.base]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.test]
+extends = "base"
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.test-integration]
+extends = ["base", "test"]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#;
+        let terrain = Terrain::from_toml(toml.to_string()).expect("terrain to be parsed");
+
+        assert!(terrain.biomes().get("base").unwrap().extends().is_empty());
+        assert_eq!(
+            terrain.biomes().get("test").unwrap().extends(),
+            vec!["base"]
+        );
+        assert_eq!(
+            terrain.biomes().get("test-integration").unwrap().extends(),
+            vec!["base", "test"]
+        );
+    }
+
+    #[test]
+    fn merges_extended_biomes_in_order() {
+        let terrain = terrain_with_extends(vec![
+            biome_with_env("base", ("LAYER", "base"), None),
+            biome_with_env(
+                "cache",
+                ("CACHE", "cache"),
+                Some(Extends::One("base".to_string())),
+            ),
+            biome_with_env(
+                "test",
+                ("TEST", "test"),
+                Some(Extends::Many(vec!["base".to_string(), "cache".to_string()])),
+            ),
+        ]);
+
+        let selected = BiomeArg::Some("test".to_string());
+        let layers: Vec<String> = terrain
+            .layers(&selected)
+            .unwrap()
+            .iter()
+            .map(|layer| layer.name())
+            .collect();
+        assert_eq!(layers, vec![NONE, "base", "cache", "test"]);
+
+        let merged = terrain.merged(&selected).unwrap();
+        assert_eq!(merged.name(), "test");
+
+        let mut expected_envs = BTreeMap::new();
+        expected_envs.insert("CACHE".to_string(), "cache".to_string());
+        expected_envs.insert("LAYER".to_string(), "base".to_string());
+        expected_envs.insert("TEST".to_string(), "test".to_string());
+        assert_eq!(merged.envs(), &expected_envs);
+
+        let commands: Vec<String> = merged
+            .constructors()
+            .foreground()
+            .iter()
+            .map(|command| command.args().join(" "))
+            .collect();
+        assert_eq!(commands, vec!["base", "cache", "test"]);
+    }
+
+    #[test]
+    fn merges_includes_beneath_terrain() {
+        let dir = tempdir().expect("tempdir to be created");
+        write(
+            dir.path().join("base.toml"),
+            r#"
+[terrain.envs]
+LAYER = "base"
+BASE = "base"
+
+[[terrain.constructors.foreground]]
+exe = "/bin/echo"
+args = ["base"]
+
+[biomes.shared.envs]
+SHARED = "base"
+"#,
+        )
+        .expect("fragment to be written");
+        write(
+            dir.path().join("override.toml"),
+            r#"
+[terrain.envs]
+LAYER = "override"
+"#,
+        )
+        .expect("fragment to be written");
+
+        let toml_path = dir.path().join(TERRAIN_TOML);
+        write(
+            &toml_path,
+            r#"
+name = "includes"
+auto_apply = "off"
+include = ["base.toml", "./override.toml"]
+
+[terrain]
+envs = { LAYER = "local" }
+aliases = {}
+constructors = { foreground = [{ exe = "/bin/echo", args = ["local"] }], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+"#,
+        )
+        .expect("terrain to be written");
+
+        let terrain = Terrain::from_toml(read_to_string(&toml_path).unwrap())
+            .expect("terrain to be parsed")
+            .with_includes(&toml_path, dir.path())
+            .expect("includes to be merged");
+
+        assert_eq!(
+            terrain.included(),
+            &vec![
+                dir.path().join("base.toml").canonicalize().unwrap(),
+                dir.path().join("override.toml").canonicalize().unwrap()
+            ]
+        );
+
+        let mut expected_envs = BTreeMap::new();
+        expected_envs.insert("BASE".to_string(), "base".to_string());
+        expected_envs.insert("LAYER".to_string(), "local".to_string());
+        assert_eq!(terrain.terrain().envs(), &expected_envs);
+        assert_eq!(terrain.terrain().name(), NONE);
+
+        let commands: Vec<String> = terrain
+            .terrain()
+            .constructors()
+            .foreground()
+            .iter()
+            .map(|command| command.args().join(" "))
+            .collect();
+        assert_eq!(commands, vec!["base", "local"]);
+
+        let shared = terrain
+            .biomes()
+            .get("shared")
+            .expect("biome to be included");
+        assert_eq!(shared.name(), "shared");
+        assert_eq!(shared.envs().get("SHARED").unwrap(), "base");
+
+        let scripts_dir = tempdir().expect("tempdir to be created");
+        assert!(terrain.includes_changed(scripts_dir.path()).unwrap());
+        terrain
+            .store_includes_state(scripts_dir.path())
+            .expect("includes state to be stored");
+        assert!(!terrain.includes_changed(scripts_dir.path()).unwrap());
+
+        let without_includes = Terrain::example();
+        assert!(
+            without_includes
+                .includes_changed(scripts_dir.path())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn inherits_parent_terrain() {
+        let parent_dir = tempdir().expect("tempdir to be created");
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir).expect("child dir to be created");
+
+        write(
+            parent_dir.path().join(TERRAIN_TOML),
+            r#"
+name = "parent"
+auto_apply = "off"
+
+[terrain]
+envs = { LAYER = "parent", PARENT = "parent" }
+aliases = { p = "echo parent" }
+constructors = { foreground = [{ exe = "/bin/echo", args = ["parent"] }], background = [] }
+destructors = { foreground = [{ exe = "/bin/echo", args = ["parent"] }], background = [] }
+
+[biomes.dev]
+envs = { DEV = "parent" }
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#,
+        )
+        .expect("parent terrain to be written");
+
+        let child_toml = r#"
+name = "child"
+auto_apply = "enabled"
+inherit_parent = true
+
+[terrain]
+envs = { LAYER = "child" }
+aliases = {}
+constructors = { foreground = [{ exe = "/bin/echo", args = ["child"] }], background = [] }
+destructors = { foreground = [{ exe = "/bin/echo", args = ["child"] }], background = [] }
+
+[biomes]
+"#;
+        let terrain = Terrain::from_toml(child_toml.to_string())
+            .expect("terrain to be parsed")
+            .with_parent(&child_dir)
+            .expect("parent terrain to be inherited");
+
+        assert_eq!(terrain.parent_dirs(), vec![parent_dir.path()]);
+        assert!(!terrain.biomes().contains_key("dev"));
+        assert!(terrain.selectable_biomes().contains("dev"));
+
+        let merged = terrain.merged(&BiomeArg::None).unwrap();
+        let mut expected_envs = BTreeMap::new();
+        expected_envs.insert("LAYER".to_string(), "child".to_string());
+        expected_envs.insert("PARENT".to_string(), "parent".to_string());
+        assert_eq!(merged.envs(), &expected_envs);
+        assert_eq!(merged.aliases().get("p").unwrap(), "echo parent");
+
+        let args = |commands: &Vec<Command>| -> Vec<String> {
+            commands.iter().map(|c| c.args().join(" ")).collect()
+        };
+        assert_eq!(
+            args(merged.constructors().foreground()),
+            vec!["parent", "child"]
+        );
+        assert_eq!(
+            args(merged.destructors().foreground()),
+            vec!["child", "parent"]
+        );
+        // commands from parent run in parent terrain directory
+        assert_eq!(
+            merged.constructors().foreground()[0].cwd(),
+            &Some(parent_dir.path().to_path_buf())
+        );
+        assert_eq!(merged.constructors().foreground()[1].cwd(), &None);
+
+        let dev = terrain.merged(&BiomeArg::Some("dev".to_string())).unwrap();
+        assert_eq!(dev.name(), "dev");
+        assert_eq!(dev.envs().get("DEV").unwrap(), "parent");
+        assert_eq!(dev.envs().get("LAYER").unwrap(), "child");
+    }
+
+    #[test]
+    fn default_biome_can_be_defined_in_parent_terrain() {
+        let parent_dir = tempdir().expect("tempdir to be created");
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir).expect("child dir to be created");
+
+        write(
+            parent_dir.path().join(TERRAIN_TOML),
+            r#"
+name = "parent"
+auto_apply = "off"
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.dev]
+envs = { DEV = "parent" }
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#,
+        )
+        .expect("parent terrain to be written");
+
+        let child_toml = r#"
+name = "child"
+auto_apply = "off"
+default_biome = "dev"
+inherit_parent = true
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+"#;
+        let terrain = Terrain::from_toml(child_toml.to_string()).expect("terrain to be parsed");
+
+        // terrain is validated before parent is layered
+        assert!(
+            !terrain
+                .validate(&child_dir)
+                .results()
+                .iter()
+                .any(|result| result.rule == Rule::MissingDefaultBiome)
+        );
+
+        let terrain = terrain
+            .with_parent(&child_dir)
+            .expect("parent terrain to be inherited");
+        assert!(
+            !terrain
+                .validate(&child_dir)
+                .results()
+                .iter()
+                .any(|result| result.rule == Rule::MissingDefaultBiome)
+        );
 
-        let actual = read_to_string(terrain_toml).unwrap();
-        let expected = read_to_string(WITH_EXAMPLE_TERRAIN_TOML_COMMENTS).unwrap();
-        assert_eq!(expected, actual);
+        let merged = terrain.merged(&BiomeArg::Default).unwrap();
+        assert_eq!(merged.name(), "dev");
+        assert_eq!(merged.envs().get("DEV").unwrap(), "parent");
+    }
+
+    #[test]
+    fn sources_include_included_and_parent_terrains() {
+        let parent_dir = tempdir().expect("tempdir to be created");
+        let child_dir = parent_dir.path().join("child");
+        create_dir_all(&child_dir).expect("child dir to be created");
+
+        write(
+            parent_dir.path().join(TERRAIN_TOML),
+            r#"
+name = "parent"
+auto_apply = "off"
+include = ["shared.toml"]
+
+[terrain]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes]
+"#,
+        )
+        .expect("parent terrain to be written");
+        write(
+            parent_dir.path().join("shared.toml"),
+            "[terrain.envs]\nSHARED = \"shared\"\n",
+        )
+        .expect("included terrain to be written");
+
+        let mut terrain = Terrain::example();
+        terrain.inherit_parent = true;
+        let terrain = terrain
+            .with_parent(&child_dir)
+            .expect("parent terrain to be inherited");
+
+        let child_toml = child_dir.join(TERRAIN_TOML);
+        assert_eq!(
+            terrain.sources(&child_toml),
+            vec![
+                child_toml.clone(),
+                parent_dir.path().join(TERRAIN_TOML),
+                parent_dir
+                    .path()
+                    .join("shared.toml")
+                    .canonicalize()
+                    .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn throws_error_if_parent_terrain_does_not_exist() {
+        let mut terrain = Terrain::example();
+        terrain.inherit_parent = true;
+
+        let err = terrain
+            .with_parent(Path::new("/"))
+            .err()
+            .expect("error to be thrown");
+        assert_eq!(
+            err.to_string(),
+            "`inherit_parent` is set but parent terrain for \"/\" does not exist"
+        );
+    }
+
+    #[test]
+    fn validates_extends() {
+        let mut terrain = terrain_with_extends(vec![
+            biome_with_env("a", ("A", "a"), Some(Extends::One("b".to_string()))),
+            biome_with_env("b", ("B", "b"), Some(Extends::One("a".to_string()))),
+            biome_with_env(
+                "c",
+                ("C", "c"),
+                Some(Extends::One("does_not_exist".to_string())),
+            ),
+        ]);
+        terrain
+            .terrain_mut()
+            .set_extends(Some(Extends::One("a".to_string())));
+
+        let results = terrain.validate(Path::new("")).results();
+
+        assert!(results.contains(&ValidationResult {
+            level: ValidationMessageLevel::Error,
+            message: "biomes have cyclic `extends`: a -> b -> a".to_string(),
+            r#for: "a(extends)".to_string(),
+            rule: Rule::InvalidExtends,
+            fix_action: ValidationFixAction::None,
+        }));
+        assert!(results.contains(&ValidationResult {
+            level: ValidationMessageLevel::Error,
+            message: "biomes have cyclic `extends`: b -> a -> b".to_string(),
+            r#for: "b(extends)".to_string(),
+            rule: Rule::InvalidExtends,
+            fix_action: ValidationFixAction::None,
+        }));
+        assert!(results.contains(&ValidationResult {
+            level: ValidationMessageLevel::Error,
+            message: "biome 'c' extends biome 'does_not_exist' that does not exist".to_string(),
+            r#for: "c(extends)".to_string(),
+            rule: Rule::InvalidExtends,
+            fix_action: ValidationFixAction::None,
+        }));
+        assert!(
+            results.contains(&ValidationResult {
+                level: ValidationMessageLevel::Error,
+                message: "main terrain cannot extend biomes, `extends` can only be used in biomes"
+                    .to_string(),
+                r#for: "none(extends)".to_string(),
+                rule: Rule::InvalidExtends,
+                fix_action: ValidationFixAction::None,
+            })
+        );
+
+        assert_eq!(
+            terrain
+                .merged(&BiomeArg::Some("a".to_string()))
+                .expect_err("expected error for cyclic extends")
+                .to_string(),
+            "biomes have cyclic `extends`: a -> b -> a"
+        );
+    }
+
+    #[test]
+    fn validates_lint_rules() {
+        let mut main = Biome::default();
+        main.add_envs(vec![("EDITOR", "vim")]);
+
+        let mut biome = Biome::default();
+        biome.set_name("lint".to_string());
+        biome.add_envs(vec![
+            ("EDITOR", "vim"),
+            ("HOME", "/tmp"),
+            ("PATH", "${PATH}:/opt/bin"),
+        ]);
+        biome.set_aliases(BTreeMap::from([
+            ("cd".to_string(), "pushd".to_string()),
+            ("echo".to_string(), "echo -n".to_string()),
+        ]));
+        let echo = Command::new("/bin/echo".to_string(), vec!["hi".to_string()], None);
+        let sudo = Command::new("sudo".to_string(), vec!["whoami".to_string()], None);
+        // only exe named sudo runs sudo
+        let sudoku = Command::new("/opt/sudoku".to_string(), vec![], None);
+        biome.add_fg_constructors(vec![echo.clone(), echo, sudo, sudoku]);
+
+        let terrain = Terrain::new(
+            main,
+            BTreeMap::from([("lint".to_string(), biome)]),
+            Some("does_not_exist".to_string()),
+            AutoApply::Enabled,
+        );
+
+        let results = terrain.validate(Path::new("")).results();
+        let messages = |rule: Rule| -> Vec<String> {
+            let mut messages: Vec<String> = results
+                .iter()
+                .filter(|result| result.rule == rule)
+                .map(|result| format!("{}: {}", result.r#for, result.message))
+                .collect();
+            messages.sort();
+            messages
+        };
+
+        assert_eq!(
+            messages(Rule::MissingDefaultBiome),
+            vec!["none(default_biome): default biome 'does_not_exist' is not defined in biomes."]
+        );
+        assert_eq!(
+            messages(Rule::UnusedBiome),
+            vec![
+                "lint: biome 'lint' is not default biome and is not extended by other biomes, it \
+                 is only used when selected with '--biome lint'."
+            ]
+        );
+        assert_eq!(
+            messages(Rule::RedundantOverride),
+            vec![
+                "lint(env:EDITOR): env 'EDITOR' has same value as in main terrain, it can be removed \
+                 from biome 'lint'."
+            ]
+        );
+        assert_eq!(
+            messages(Rule::CriticalEnvOverride),
+            vec![
+                "lint(env:HOME): environment variable 'HOME' overrides the value set by shell or \
+                 terrainium, which might break the shell. reference '${HOME}' in value to extend \
+                 it instead."
+            ]
+        );
+        // alias wrapping the builtin it shadows is allowed
+        assert_eq!(
+            messages(Rule::AliasShadowsCommand),
+            vec!["lint(alias:cd): alias 'cd' shadows shell builtin."]
+        );
+        assert_eq!(
+            messages(Rule::DuplicateCommand),
+            vec![
+                "lint(constructor:foreground): command exe: '/bin/echo' args: 'hi' is repeated \
+                 in foreground constructors."
+            ]
+        );
+        assert_eq!(
+            messages(Rule::AutoApplySudo),
+            vec![
+                "none(auto_apply): auto_apply is 'enabled' but biome 'lint' runs sudo command \
+                 exe: 'sudo' args: 'whoami', changing directory will wait for password."
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_env_rules_at_location_of_env() {
+        let toml = r#"name = "terrainium"
+auto_apply = "off"
+default_biome = "lint"
+
+[terrain]
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[terrain.envs]
+EDITOR = "vim"
+
+[biomes.lint]
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.lint.envs]
+EDITOR = "vim"
+HOME = "/tmp"
+"#;
+        let terrain = Terrain::from_toml(toml.to_string()).expect("terrain to be parsed");
+        let document = toml_edit::ImDocument::parse(toml).expect("toml to be parsed");
+        let toml_path = Path::new(TERRAIN_TOML);
+
+        let results = terrain.validate(Path::new(""));
+        let location = |rule: Rule| {
+            results
+                .results_ref()
+                .iter()
+                .find(|result| result.rule == rule)
+                .map(|result| result.report(toml_path, &document))
+                .and_then(|reported| reported.location)
+                .map(|location| location.line)
+        };
+
+        assert_eq!(location(Rule::RedundantOverride), Some(19));
+        assert_eq!(location(Rule::CriticalEnvOverride), Some(20));
+    }
+
+    #[test]
+    fn applies_validation_config_and_allow_comments() {
+        let toml = r#"
+name = "terrainium"
+auto_apply = "off"
+
+[validation]
+redundant-override = "error"
+alias-shadows-command = "off"
+
+[terrain]
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[terrain.envs]
+EDITOR = "vim"
+
+[biomes.lint]
+aliases = { cd = "pushd" }
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+
+[biomes.lint.envs]
+EDITOR = "vim"
+# terrainium:allow(critical-env-override)
+HOME = "/tmp"
+SHELL = "/bin/sh" # terrainium:allow(does-not-exist)
+
+# terrainium:allow(unused-biome)
+[biomes.other]
+envs = {}
+aliases = {}
+constructors = { foreground = [], background = [] }
+destructors = { foreground = [], background = [] }
+"#;
+        let config: Config = toml::from_str(
+            "auto_apply = true\n[validation]\nunused-biome = \"warn\"\nredundant-override = \"info\"",
+        )
+        .expect("config to be parsed");
+        let terrain = Terrain::from_toml(toml.to_string())
+            .expect("terrain to be parsed")
+            .with_validation_config(config.validation());
+
+        let results = terrain.configured(Path::new("")).results();
+        let mut results: Vec<(Rule, ValidationMessageLevel, String)> = results
+            .into_iter()
+            .map(|result| (result.rule, result.level, result.r#for))
+            .collect();
+        results.sort();
+
+        // severity in terrain.toml takes precedence over terrainium.toml
+        assert_eq!(
+            results,
+            vec![
+                (
+                    Rule::RedundantOverride,
+                    ValidationMessageLevel::Error,
+                    "lint(env:EDITOR)".to_string()
+                ),
+                (
+                    Rule::UnusedBiome,
+                    ValidationMessageLevel::Warn,
+                    "lint".to_string()
+                ),
+                (
+                    Rule::CriticalEnvOverride,
+                    ValidationMessageLevel::Warn,
+                    "lint(env:SHELL)".to_string()
+                ),
+            ]
+        );
     }
 
     fn get_test_fix_action<'a>(
@@ -1,17 +1,91 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Formatter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+use anyhow::anyhow;
+use clap::ValueEnum;
 use regex::Regex;
+#[cfg(feature = "terrain-schema")]
+use schemars::JsonSchema;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Serialize};
+use toml_edit::{DocumentMut, ImDocument, Item, TableLike};
 use tracing::{debug, error, info, warn};
 
-use crate::common::types::command::{Command, CommandsType, OperationType};
+use crate::common::constants::{
+    AUTO_APPLY, BIOMES, DEFAULT_BIOME, INCLUDE, NONE, PATH, TERRAIN, TERRAIN_AUTO_APPLY,
+    TERRAIN_DIR, TERRAIN_INIT_SCRIPT, TERRAIN_NAME, TERRAIN_SELECTED_BIOME, TERRAIN_SESSION_ID,
+};
+use crate::common::types::command::{Command, CommandsType, OperationType, is_exe_in_path};
+
+/// environment variables that break the shell or terrainium when overridden
+const CRITICAL_ENVS: [&str; 14] = [
+    "HOME",
+    "SHELL",
+    "USER",
+    "LOGNAME",
+    "PWD",
+    "OLDPWD",
+    "TERM",
+    PATH,
+    TERRAIN_DIR,
+    TERRAIN_NAME,
+    TERRAIN_SESSION_ID,
+    TERRAIN_SELECTED_BIOME,
+    TERRAIN_AUTO_APPLY,
+    TERRAIN_INIT_SCRIPT,
+];
+
+/// zsh builtins and reserved words that are commonly shadowed by mistake
+const SHELL_BUILTINS: [&str; 36] = [
+    "alias",
+    "autoload",
+    "bg",
+    "bindkey",
+    "builtin",
+    "cd",
+    "command",
+    "declare",
+    "echo",
+    "emulate",
+    "eval",
+    "exec",
+    "exit",
+    "export",
+    "fg",
+    "functions",
+    "history",
+    "jobs",
+    "kill",
+    "local",
+    "print",
+    "printf",
+    "pwd",
+    "read",
+    "return",
+    "set",
+    "setopt",
+    "source",
+    "test",
+    "trap",
+    "type",
+    "typeset",
+    "unalias",
+    "unset",
+    "whence",
+    "which",
+];
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
+#[derive(Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
 pub enum ValidationMessageLevel {
     Error,
     Warn,
     Info,
+    /// not accepted by `--fail-on` as every run would fail
+    #[value(skip)]
     Debug,
 }
 
@@ -34,6 +108,159 @@ impl std::fmt::Display for ValidationMessageLevel {
     }
 }
 
+/// rule that produced the validation result, id of the rule is used to
+/// configure or suppress the rule
+#[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    InvalidIdentifier,
+    UntrimmedIdentifier,
+    InvalidExtends,
+    InvalidInclude,
+    InvalidExe,
+    UntrimmedExe,
+    ExeNotInPath,
+    InvalidCwd,
+    SudoCommand,
+    UndefinedVariable,
+    SecretProvider,
+    RedundantOverride,
+    AliasShadowsCommand,
+    DuplicateCommand,
+    MissingDefaultBiome,
+    UnusedBiome,
+    CriticalEnvOverride,
+    AutoApplySudo,
+}
+
+impl Rule {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::InvalidIdentifier => "invalid-identifier",
+            Rule::UntrimmedIdentifier => "untrimmed-identifier",
+            Rule::InvalidExtends => "invalid-extends",
+            Rule::InvalidInclude => "invalid-include",
+            Rule::InvalidExe => "invalid-exe",
+            Rule::UntrimmedExe => "untrimmed-exe",
+            Rule::ExeNotInPath => "exe-not-in-path",
+            Rule::InvalidCwd => "invalid-cwd",
+            Rule::SudoCommand => "sudo-command",
+            Rule::UndefinedVariable => "undefined-variable",
+            Rule::SecretProvider => "secret-provider",
+            Rule::RedundantOverride => "redundant-override",
+            Rule::AliasShadowsCommand => "alias-shadows-command",
+            Rule::DuplicateCommand => "duplicate-command",
+            Rule::MissingDefaultBiome => "missing-default-biome",
+            Rule::UnusedBiome => "unused-biome",
+            Rule::CriticalEnvOverride => "critical-env-override",
+            Rule::AutoApplySudo => "auto-apply-sudo",
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::deserialize(s.into_deserializer())
+            .map_err(|err: serde::de::value::Error| anyhow!("invalid rule id '{s}': {err}"))
+    }
+}
+
+/// severity of the rule set in `[validation]` section of terrain.toml or terrainium.toml
+#[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    fn level(&self) -> Option<ValidationMessageLevel> {
+        match self {
+            Severity::Off => None,
+            Severity::Info => Some(ValidationMessageLevel::Info),
+            Severity::Warn => Some(ValidationMessageLevel::Warn),
+            Severity::Error => Some(ValidationMessageLevel::Error),
+        }
+    }
+}
+
+/// rules suppressed by `# terrainium:allow(<rule-id>, ...)` comments, keyed by toml path
+/// of the table or key the comment is attached to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct AllowComments(BTreeMap<Vec<String>, BTreeSet<Rule>>);
+
+impl AllowComments {
+    /// collects comments placed above or on the same line as tables and keys
+    pub(crate) fn from_toml(toml: &DocumentMut) -> Self {
+        let allow = Regex::new(r"terrainium:allow\(([^)]*)\)").unwrap();
+        let mut allowed = Self::default();
+        allowed.collect(&allow, toml.as_table(), &mut vec![]);
+        allowed
+    }
+
+    fn collect(&mut self, allow: &Regex, table: &dyn TableLike, path: &mut Vec<String>) {
+        table.iter().for_each(|(name, item)| {
+            let Some((key, _)) = table.get_key_value(name) else {
+                return;
+            };
+            path.push(name.to_string());
+
+            let decor = match item {
+                Item::Table(table) => Some(table.decor()),
+                Item::Value(value) => Some(value.decor()),
+                _ => None,
+            };
+            let comments = [key.leaf_decor().prefix()]
+                .into_iter()
+                .chain(decor.into_iter().flat_map(|d| [d.prefix(), d.suffix()]))
+                .flatten()
+                .filter_map(|raw| raw.as_str());
+
+            comments
+                .flat_map(|comment| allow.captures_iter(comment))
+                .flat_map(|captures| {
+                    captures[1]
+                        .split(',')
+                        .map(|id| id.trim().to_string())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|id| !id.is_empty())
+                .for_each(|id| match Rule::from_str(&id) {
+                    Ok(rule) => {
+                        self.0.entry(path.clone()).or_default().insert(rule);
+                    }
+                    Err(err) => warn!("ignoring allow comment for '{}': {err}", path.join(".")),
+                });
+
+            if let Some(table) = item.as_table_like() {
+                self.collect(allow, table, path);
+            }
+            path.pop();
+        });
+    }
+
+    /// rule allowed for a table is allowed for all the values inside it
+    fn allows(&self, path: &[&str], rule: &Rule) -> bool {
+        self.0.iter().any(|(allowed, rules)| {
+            rules.contains(rule)
+                && allowed.len() <= path.len()
+                && allowed.iter().zip(path).all(|(a, p)| a == p)
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub(crate) enum Target<'a> {
     Env(&'a str),
@@ -45,6 +272,17 @@ pub(crate) enum Target<'a> {
 }
 
 impl<'a> Target<'a> {
+    /// name of env or alias, or executable of the command
+    pub(crate) fn key(&self) -> &'a str {
+        match self {
+            Target::Env(key) | Target::Alias(key) => key,
+            Target::ForegroundConstructor(command)
+            | Target::BackgroundConstructor(command)
+            | Target::ForegroundDestructor(command)
+            | Target::BackgroundDestructor(command) => command.exe(),
+        }
+    }
+
     pub(crate) fn from_identifier(identifier: &IdentifierType, value: &'a str) -> Self {
         match identifier {
             IdentifierType::Env => Target::Env(value),
@@ -85,10 +323,22 @@ pub struct ValidationResult<'a> {
     pub(crate) level: ValidationMessageLevel,
     pub(crate) message: String,
     pub(crate) r#for: String,
+    pub(crate) rule: Rule,
     pub(crate) fix_action: ValidationFixAction<'a>,
 }
 
 impl ValidationResult<'_> {
+    /// included files of terrain could not be read, parsed or merged
+    pub(crate) fn invalid_include(err: &anyhow::Error) -> Self {
+        Self {
+            level: ValidationMessageLevel::Error,
+            message: format!("{err:#}"),
+            r#for: format!("{NONE}({INCLUDE})"),
+            rule: Rule::InvalidInclude,
+            fix_action: ValidationFixAction::None,
+        }
+    }
+
     pub fn level(&self) -> &ValidationMessageLevel {
         &self.level
     }
@@ -97,9 +347,155 @@ impl ValidationResult<'_> {
         &self.message
     }
 
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
     pub fn target(&self) -> &str {
         &self.r#for
     }
+
+    /// parts of `r#for` and toml path of the value result is for
+    fn subject(&self) -> Subject<'_> {
+        // r#for is of format `[<parent terrain path>:]<biome>[(<target>[:<key>])]`
+        let (biome, target) = match self.r#for.rsplit_once('(') {
+            Some((biome, target)) => (biome, target.trim_end_matches(')')),
+            None => (self.r#for.as_str(), ""),
+        };
+        let (file, biome) = match biome.rsplit_once(':') {
+            Some((file, biome)) => (Some(file), biome),
+            None => (None, biome),
+        };
+        let (target, key) = match target.split_once(':') {
+            Some((target @ ("env" | "alias"), key)) => (target, Some(key)),
+            _ => match &self.fix_action {
+                ValidationFixAction::None => (target, None),
+                ValidationFixAction::Trim { target: fixed, .. } => (target, Some(fixed.key())),
+            },
+        };
+
+        let mut path = if biome == NONE {
+            vec![TERRAIN]
+        } else {
+            vec![BIOMES, biome]
+        };
+        match target.split_once(':') {
+            Some((operation, commands)) => {
+                path.push(if operation == "constructor" {
+                    "constructors"
+                } else {
+                    "destructors"
+                });
+                path.push(commands);
+            }
+            None => match target {
+                "env" => path.push("envs"),
+                "alias" => path.push("aliases"),
+                // terrain settings are not inside the biome tables
+                DEFAULT_BIOME | AUTO_APPLY | INCLUDE => path = vec![target],
+                "" => {}
+                other => path.push(other),
+            },
+        }
+        if let Some(key) = key.filter(|_| matches!(target, "env" | "alias")) {
+            path.push(key);
+        }
+
+        Subject {
+            file,
+            biome,
+            target,
+            key,
+            path,
+        }
+    }
+
+    /// converts result into owned and serializable form, `toml` is used to find the
+    /// location of the value in terrain.toml
+    pub(crate) fn report(&self, toml_path: &Path, toml: &ImDocument<&str>) -> ReportedResult {
+        let subject = self.subject();
+        let file = subject.file.map_or(toml_path.to_path_buf(), PathBuf::from);
+        let fix = match &self.fix_action {
+            ValidationFixAction::None => None,
+            ValidationFixAction::Trim { .. } => Some("trim"),
+        };
+
+        // values from parent terrains are not present in terrain.toml
+        let location = if file == toml_path {
+            Location::find(toml, &subject.path)
+        } else {
+            None
+        };
+
+        ReportedResult {
+            level: self.level.clone(),
+            rule: self.rule,
+            message: self.message.clone(),
+            file,
+            biome: subject.biome.to_string(),
+            target: subject.target.to_string(),
+            key: subject.key.map(str::to_string),
+            fix: fix.map(str::to_string),
+            location,
+        }
+    }
+}
+
+struct Subject<'a> {
+    file: Option<&'a str>,
+    biome: &'a str,
+    target: &'a str,
+    key: Option<&'a str>,
+    path: Vec<&'a str>,
+}
+
+/// location of the value in terrain.toml, line and column start from 1
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Location {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+impl Location {
+    /// finds the deepest table or key present in `path`
+    fn find(toml: &ImDocument<&str>, path: &[&str]) -> Option<Self> {
+        let mut table: &dyn TableLike = toml.as_table();
+        let mut span = None;
+
+        for segment in path {
+            let Some((key, item)) = table.get_key_value(segment) else {
+                break;
+            };
+            span = key.span().or(item.span()).or(span);
+            match item.as_table_like() {
+                Some(next) => table = next,
+                None => break,
+            }
+        }
+
+        span.map(|span| {
+            let before = &toml.raw()[..span.start];
+            let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+            Location {
+                line: before.matches('\n').count() + 1,
+                column: before[line_start..].chars().count() + 1,
+            }
+        })
+    }
+}
+
+/// validation result in the format used by `terrain validate --format`
+#[derive(Debug, Serialize, PartialEq)]
+pub(crate) struct ReportedResult {
+    pub(crate) level: ValidationMessageLevel,
+    pub(crate) rule: Rule,
+    pub(crate) message: String,
+    pub(crate) file: PathBuf,
+    pub(crate) biome: String,
+    pub(crate) target: String,
+    pub(crate) key: Option<String>,
+    pub(crate) fix: Option<String>,
+    pub(crate) location: Option<Location>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -117,7 +513,7 @@ impl<'a> ValidationResults<'a> {
         self.fixable
     }
 
-    pub fn results_ref(&self) -> &HashSet<ValidationResult> {
+    pub fn results_ref(&self) -> &HashSet<ValidationResult<'_>> {
         &self.results
     }
 
@@ -128,6 +524,43 @@ impl<'a> ValidationResults<'a> {
         self.results.extend(other.results);
     }
 
+    /// drops the results of rules that are turned off or allowed by comments, and
+    /// changes the level of results of rules that have severity set
+    pub(crate) fn configured(
+        self,
+        severities: &BTreeMap<Rule, Severity>,
+        allowed: &AllowComments,
+    ) -> Self {
+        let results: HashSet<ValidationResult<'a>> = self
+            .results
+            .into_iter()
+            .filter(|result| !allowed.allows(&result.subject().path, &result.rule))
+            .filter_map(|result| match severities.get(&result.rule) {
+                Some(severity) => severity
+                    .level()
+                    .map(|level| ValidationResult { level, ..result }),
+                None => Some(result),
+            })
+            .collect();
+        let fixable = results
+            .iter()
+            .any(|result| result.fix_action != ValidationFixAction::None);
+        Self::new(fixable, results)
+    }
+
+    /// prefixes the targets of results with the file they were found in
+    pub(crate) fn qualified(self, path: &Path) -> Self {
+        let results = self
+            .results
+            .into_iter()
+            .map(|result| ValidationResult {
+                r#for: format!("{}:{}", path.display(), result.r#for),
+                ..result
+            })
+            .collect();
+        Self::new(self.fixable, results)
+    }
+
     pub fn print_validation_message(&self) {
         let messages = &self.results;
 
@@ -161,7 +594,7 @@ pub struct ValidationError<'a> {
 }
 
 impl ValidationError<'_> {
-    pub fn results(&self) -> &ValidationResults {
+    pub fn results(&self) -> &ValidationResults<'_> {
         &self.results
     }
 }
@@ -217,6 +650,7 @@ pub(crate) fn validate_identifiers<'a>(
                 level: ValidationMessageLevel::Error,
                 message: "empty identifier is not allowed".to_string(),
                 r#for: for_str.clone(),
+                rule: Rule::InvalidIdentifier,
                 fix_action: ValidationFixAction::None,
             });
             return;
@@ -228,6 +662,7 @@ pub(crate) fn validate_identifiers<'a>(
                 level: ValidationMessageLevel::Warn,
                 message: format!("trimming spaces from identifier: '{k}'"),
                 r#for: for_str.clone(),
+                rule: Rule::UntrimmedIdentifier,
                 fix_action: ValidationFixAction::Trim {
                     biome_name,
                     target: Target::from_identifier(&data_type, k),
@@ -243,6 +678,7 @@ pub(crate) fn validate_identifiers<'a>(
                 level: ValidationMessageLevel::Error,
                 message: format!("identifier '{k}' is invalid as it contains spaces",),
                 r#for: for_str.clone(),
+                rule: Rule::InvalidIdentifier,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -252,6 +688,7 @@ pub(crate) fn validate_identifiers<'a>(
                 level: ValidationMessageLevel::Error,
                 message: format!("identifier '{k}' cannot start with number",),
                 r#for: for_str.clone(),
+                rule: Rule::InvalidIdentifier,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -264,9 +701,93 @@ pub(crate) fn validate_identifiers<'a>(
                      include [a-zA-Z0-9_] characters."
                 ),
                 r#for: for_str.clone(),
+                rule: Rule::InvalidIdentifier,
                 fix_action: ValidationFixAction::None,
             });
         }
     });
     ValidationResults::new(fixable, messages)
 }
+
+/// environment variables overriding variables required by shell or terrainium,
+/// extending the variable by referencing it (e.g. `PATH = "${PATH}:/opt/bin"`) is allowed
+pub(crate) fn validate_critical_envs<'a>(
+    envs: &'a BTreeMap<String, String>,
+    biome_name: &'a str,
+) -> ValidationResults<'a> {
+    let results = envs
+        .iter()
+        .filter(|(k, v)| {
+            CRITICAL_ENVS.contains(&k.trim()) && !v.contains(&format!("${{{}}}", k.trim()))
+        })
+        .map(|(k, _)| ValidationResult {
+            level: ValidationMessageLevel::Warn,
+            message: format!(
+                "environment variable '{k}' overrides the value set by shell or terrainium, \
+                 which might break the shell. reference '${{{k}}}' in value to extend it instead."
+            ),
+            r#for: format!("{biome_name}({}:{k})", IdentifierType::Env),
+            rule: Rule::CriticalEnvOverride,
+            fix_action: ValidationFixAction::None,
+        })
+        .collect();
+    ValidationResults::new(false, results)
+}
+
+/// aliases with same name as shell builtin or executable in PATH, aliases that
+/// wrap the command they shadow (e.g. `ls = "ls --color"`) are allowed
+pub(crate) fn validate_alias_shadowing<'a>(
+    aliases: &'a BTreeMap<String, String>,
+    biome_name: &'a str,
+) -> ValidationResults<'a> {
+    let results = aliases
+        .iter()
+        .filter(|(k, v)| v.split_whitespace().next() != Some(k.trim()))
+        .filter_map(|(k, _)| {
+            let alias = k.trim();
+            let shadowed = if alias.is_empty() {
+                // empty identifiers are reported by `validate_identifiers`
+                return None;
+            } else if SHELL_BUILTINS.contains(&alias) {
+                "shell builtin".to_string()
+            } else {
+                let exe = is_exe_in_path(alias).filter(|exe| exe.is_file())?;
+                format!("executable '{}'", exe.display())
+            };
+            Some(ValidationResult {
+                level: ValidationMessageLevel::Info,
+                message: format!("alias '{alias}' shadows {shadowed}."),
+                r#for: format!("{biome_name}({}:{k})", IdentifierType::Alias),
+                rule: Rule::AliasShadowsCommand,
+                fix_action: ValidationFixAction::None,
+            })
+        })
+        .collect();
+    ValidationResults::new(false, results)
+}
+
+/// commands that are repeated in the same list
+pub(crate) fn validate_duplicate_commands<'a>(
+    commands: &'a [Command],
+    biome_name: &'a str,
+    operation_type: &'a OperationType,
+    commands_type: &'a CommandsType,
+) -> ValidationResults<'a> {
+    let results = commands
+        .iter()
+        .enumerate()
+        .filter(|(idx, command)| commands[..*idx].contains(command))
+        .map(|(_, command)| ValidationResult {
+            level: ValidationMessageLevel::Warn,
+            message: format!(
+                "command exe: '{}' args: '{}' is repeated in {commands_type} {operation_type}s.",
+                command.exe(),
+                command.args().join(" ")
+            ),
+            r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+            rule: Rule::DuplicateCommand,
+            fix_action: ValidationFixAction::None,
+        })
+        .collect();
+    ValidationResults::new(false, results)
+}
@@ -23,16 +23,21 @@ pub const TERRAIN_INIT_FN: &str = "terrain_init";
 pub const TERRAIN_NAME: &str = "TERRAIN_NAME";
 pub const TERRAIN_SESSION_ID: &str = "TERRAIN_SESSION_ID";
 pub const TERRAIN_AUTO_APPLY: &str = "TERRAIN_AUTO_APPLY";
+pub const TERRAIN_RELOAD_CONSTRUCTORS: &str = "TERRAIN_RELOAD_CONSTRUCTORS";
 pub const TERRAIN_INIT_SCRIPT: &str = "TERRAIN_INIT_SCRIPT";
 pub const TERRAIN_SELECTED_BIOME: &str = "TERRAIN_SELECTED_BIOME";
+pub const TERRAIN_COMPLETE: &str = "TERRAIN_COMPLETE";
 pub const TERRAIN_TOML: &str = "terrain.toml";
 
 pub const TERRAINIUM_DEV: &str = "TERRAINIUM_DEV";
 pub const TERRAINIUM_CONF: &str = "terrainium.toml";
 pub const SHELL_INTEGRATION_SCRIPTS_DIR: &str = "shell_integration";
+pub const TERRAIN_SOURCES: &str = "terrain.sources";
+pub const TERRAIN_SWITCH: &str = "terrain.switch";
 
 pub const DEFAULT_BIOME: &str = "default_biome";
 pub const AUTO_APPLY: &str = "auto_apply";
+pub const INCLUDE: &str = "include";
 pub const AUTO_APPLY_ENABLED: &str = "enabled";
 pub const AUTO_APPLY_BACKGROUND: &str = "background";
 pub const AUTO_APPLY_REPLACE: &str = "replace";
@@ -46,6 +51,7 @@ pub const CONSTRUCTORS: &str = "constructors";
 pub const DESTRUCTORS: &str = "destructors";
 pub const FOREGROUND: &str = "foreground";
 pub const BACKGROUND: &str = "background";
+pub const EXTENDS: &str = "extends";
 
 pub const ZSH: &str = "zsh";
 pub const TRUE: &str = "true";
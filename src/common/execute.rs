@@ -5,6 +5,7 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 #[cfg(test)]
 use mockall::mock;
+use tokio::io::AsyncWriteExt;
 use tracing::{info, trace};
 
 use crate::common::types::command::Command;
@@ -37,6 +38,11 @@ pub trait Execute {
         envs: Option<Arc<BTreeMap<String, String>>>,
         command: Command,
     ) -> impl std::future::Future<Output = Result<ExitStatus>> + Send;
+    fn async_spawn_with_input(
+        &self,
+        command: Command,
+        input: String,
+    ) -> impl std::future::Future<Output = Result<ExitStatus>> + Send;
 }
 
 #[derive(Default, Debug, PartialEq)]
@@ -134,6 +140,28 @@ impl Execute for Executor {
         let mut child = command.spawn().context("failed to run command")?;
         child.wait().await.context("failed to wait for command")
     }
+
+    async fn async_spawn_with_input(&self, command: Command, input: String) -> Result<ExitStatus> {
+        trace!("running async process {command} with input");
+        let mut command: tokio::process::Command = command.into();
+        command.stdin(Stdio::piped());
+        command.stdout(Stdio::null());
+        command.stderr(Stdio::null());
+        let mut child = command.spawn().context("failed to run command")?;
+
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("failed to get stdin of command")?;
+        stdin
+            .write_all(input.as_bytes())
+            .await
+            .context("failed to write input to command")?;
+        // closing stdin so command does not wait for more input
+        drop(stdin);
+
+        child.wait().await.context("failed to wait for command")
+    }
 }
 
 #[cfg(test)]
@@ -160,6 +188,7 @@ mock! {
             envs: Option<Arc<BTreeMap<String, String>>>,
             command: Command,
         ) -> Result<ExitStatus>;
+        async fn async_spawn_with_input(&self, command: Command, input: String) -> Result<ExitStatus>;
     }
 }
 
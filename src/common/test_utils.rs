@@ -51,7 +51,9 @@ pub fn expected_activate_request_example_biome(
                         "${PWD}/tests/scripts/print_num_for_10_sec".to_string(),
                     ],
                     cwd: terrain_dir,
+                    notify: None,
                 }],
+                secrets: BTreeMap::new(),
             })
         } else {
             None
@@ -82,6 +84,7 @@ pub(crate) fn expected_execute_request_example_biome(
         timestamp: TEST_TIMESTAMP.to_string(),
         envs: expected_env_vars_example_biome(),
         commands,
+        secrets: BTreeMap::new(),
     }
 }
 
@@ -1,4 +1,6 @@
 pub mod command;
+pub mod daemon_status;
+pub mod event;
 // FIXME: remove in future when prost has update
 pub mod paths;
 #[allow(clippy::large_enum_variant)]
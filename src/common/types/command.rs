@@ -12,7 +12,7 @@ use tracing::debug;
 
 use crate::client::types::biome::Biome;
 use crate::client::validation::{
-    Target, ValidationFixAction, ValidationMessageLevel, ValidationResult, ValidationResults,
+    Rule, Target, ValidationFixAction, ValidationMessageLevel, ValidationResult, ValidationResults,
 };
 use crate::common::constants::PATH;
 use crate::common::types::pb;
@@ -55,7 +55,7 @@ impl Display for OperationType {
     }
 }
 
-fn is_exe_in_path(exe: &str) -> Option<PathBuf> {
+pub(crate) fn is_exe_in_path(exe: &str) -> Option<PathBuf> {
     if let Ok(path) = std::env::var(PATH) {
         for p in path.split(':') {
             let p = Path::new(p).join(exe);
@@ -99,12 +99,64 @@ fn is_executable(path: &Path) -> bool {
     mode & 0o111 != 0
 }
 
+/// status transitions of a background command that notify the user, set by
+/// `notify` of the command or `notifications.on` of terrainiumd.toml
+#[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
+#[derive(Debug, Default, Clone, Copy, Hash, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyOn {
+    #[default]
+    Never,
+    Failure,
+    Success,
+    Always,
+}
+
+impl NotifyOn {
+    pub fn on_failure(&self) -> bool {
+        matches!(self, NotifyOn::Failure | NotifyOn::Always)
+    }
+
+    pub fn on_success(&self) -> bool {
+        matches!(self, NotifyOn::Success | NotifyOn::Always)
+    }
+}
+
+impl From<NotifyOn> for pb::NotifyOn {
+    fn from(value: NotifyOn) -> Self {
+        match value {
+            NotifyOn::Never => pb::NotifyOn::Never,
+            NotifyOn::Failure => pb::NotifyOn::Failure,
+            NotifyOn::Success => pb::NotifyOn::Success,
+            NotifyOn::Always => pb::NotifyOn::Always,
+        }
+    }
+}
+
+impl TryFrom<pb::NotifyOn> for NotifyOn {
+    type Error = anyhow::Error;
+
+    fn try_from(value: pb::NotifyOn) -> Result<Self> {
+        match value {
+            pb::NotifyOn::Unspecified => anyhow::bail!("unspecified notify on value"),
+            pb::NotifyOn::Never => Ok(NotifyOn::Never),
+            pb::NotifyOn::Failure => Ok(NotifyOn::Failure),
+            pb::NotifyOn::Success => Ok(NotifyOn::Success),
+            pb::NotifyOn::Always => Ok(NotifyOn::Always),
+        }
+    }
+}
+
 #[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
 #[derive(Debug, PartialEq, Clone, Hash, Eq, Serialize, Deserialize)]
 pub struct Command {
     exe: String,
     args: Vec<String>,
     cwd: Option<PathBuf>,
+    /// notify when background command finishes, overrides `notifications.on`
+    /// of terrainiumd.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notify: Option<NotifyOn>,
 }
 
 impl Display for Command {
@@ -128,7 +180,16 @@ impl Display for Command {
 
 impl Command {
     pub fn new(exe: String, args: Vec<String>, cwd: Option<PathBuf>) -> Self {
-        Command { exe, args, cwd }
+        Command {
+            exe,
+            args,
+            cwd,
+            notify: None,
+        }
+    }
+
+    pub fn notify(&self) -> Option<NotifyOn> {
+        self.notify
     }
 
     pub fn trim_exe(mut self) -> Self {
@@ -136,6 +197,13 @@ impl Command {
         self
     }
 
+    /// exe is `sudo`, either by name or path
+    pub(crate) fn is_sudo(&self) -> bool {
+        Path::new(self.exe.trim())
+            .file_name()
+            .is_some_and(|name| name == "sudo")
+    }
+
     pub fn exe(&self) -> &str {
         &self.exe
     }
@@ -222,6 +290,7 @@ impl Command {
                 level: ValidationMessageLevel::Error,
                 message,
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::InvalidExe,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -255,6 +324,7 @@ impl Command {
                 level,
                 message,
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::ExeNotInPath,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -267,7 +337,7 @@ impl Command {
         operation_type: &OperationType,
         commands_type: &CommandsType,
         path: PathBuf,
-    ) -> Option<(String, ValidationMessageLevel)> {
+    ) -> Option<(String, ValidationMessageLevel, Rule)> {
         let envs_to_sub = Biome::get_envs_to_substitute(path.to_str().unwrap());
         if !envs_to_sub.is_empty() {
             Some((
@@ -281,6 +351,7 @@ impl Command {
                     self.args.join(" "),
                 ),
                 ValidationMessageLevel::Info,
+                Rule::UndefinedVariable,
             ))
         } else {
             Some((
@@ -291,12 +362,13 @@ impl Command {
                     self.args.join(" ")
                 ),
                 ValidationMessageLevel::Error,
+                Rule::InvalidCwd,
             ))
         }
     }
 
     /// validate that `cwd` is a valid directory or symlink to directory
-    fn validate_cwd_path(&self, path: PathBuf) -> Option<(String, ValidationMessageLevel)> {
+    fn validate_cwd_path(&self, path: PathBuf) -> Option<(String, ValidationMessageLevel, Rule)> {
         if path.is_symlink() {
             let res = resolve_symlink(path.as_path(), path.clone());
             if let Ok(resolved) = res {
@@ -311,6 +383,7 @@ impl Command {
                             self.args.join(" ")
                         ),
                         ValidationMessageLevel::Error,
+                        Rule::InvalidCwd,
                     ))
                 } else {
                     None
@@ -324,6 +397,7 @@ impl Command {
                         self.args.join(" ")
                     ),
                     ValidationMessageLevel::Error,
+                    Rule::InvalidCwd,
                 ))
             }
         } else if !path.is_dir() {
@@ -335,6 +409,7 @@ impl Command {
                     self.args.join(" ")
                 ),
                 ValidationMessageLevel::Error,
+                Rule::InvalidCwd,
             ))
         } else {
             None
@@ -347,7 +422,7 @@ impl Command {
         operation_type: &OperationType,
         commands_type: &CommandsType,
         terrain_dir: &Path,
-    ) -> Option<(String, ValidationMessageLevel)> {
+    ) -> Option<(String, ValidationMessageLevel, Rule)> {
         let cwd = self.cwd.clone().unwrap();
         let (cwd, exists) = if cwd.is_absolute() {
             let exists = cwd.exists();
@@ -384,6 +459,7 @@ impl Command {
                      {operation_type} is to be run.",
                 ),
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::InvalidExe,
                 fix_action: ValidationFixAction::None,
             });
             return ValidationResults::new(false, results);
@@ -399,6 +475,7 @@ impl Command {
                     &self.exe
                 ),
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::UntrimmedExe,
                 fix_action: ValidationFixAction::Trim {
                     biome_name,
                     target: Target::from_command(commands_type, operation_type, self),
@@ -412,6 +489,7 @@ impl Command {
                 level: ValidationMessageLevel::Error,
                 message: format!("exe '{}' contains whitespaces.", &self.exe),
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::InvalidExe,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -439,6 +517,7 @@ impl Command {
                 level: ValidationMessageLevel::Warn,
                 message,
                 r#for: format!("{biome_name}({operation_type}:{commands_type})"),
+                rule: Rule::SudoCommand,
                 fix_action: ValidationFixAction::None,
             });
         }
@@ -484,10 +563,11 @@ impl Command {
             return ValidationResults::new(fixable, results);
         }
 
-        let (message, level) = message_and_level.unwrap();
+        let (message, level, rule) = message_and_level.unwrap();
         results.insert(ValidationResult {
             level,
             message,
+            rule,
             r#for: format!("{biome_name}({operation_type}:{commands_type})"),
             fix_action: ValidationFixAction::None,
         });
@@ -518,11 +598,17 @@ impl From<Command> for tokio::process::Command {
 
 impl From<Command> for pb::Command {
     fn from(value: Command) -> Self {
-        let Command { exe, args, cwd, .. } = value;
+        let Command {
+            exe,
+            args,
+            cwd,
+            notify,
+        } = value;
         Self {
             exe,
             args,
             cwd: cwd.unwrap().to_string_lossy().to_string(),
+            notify: notify.map(|notify| pb::NotifyOn::from(notify).into()),
         }
     }
 }
@@ -533,6 +619,10 @@ impl From<pb::Command> for Command {
             exe: value.exe,
             args: value.args,
             cwd: Some(PathBuf::from(value.cwd)),
+            notify: value
+                .notify
+                .and_then(|notify| pb::NotifyOn::try_from(notify).ok())
+                .and_then(|notify| notify.try_into().ok()),
         }
     }
 }
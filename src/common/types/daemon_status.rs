@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::common::types::pb;
+use crate::common::types::styles::{heading, value};
+
+/// health of the running daemon returned for `Ping` request
+#[derive(Clone, Debug, PartialEq)]
+pub struct DaemonStatus {
+    version: String,
+    uptime: Duration,
+    pid: u32,
+    state_dir: String,
+    /// sessions with state loaded in daemon
+    sessions: usize,
+    /// background commands spawned by daemon that have not exited yet
+    running_commands: usize,
+}
+
+impl DaemonStatus {
+    pub fn new(
+        version: String,
+        uptime: Duration,
+        pid: u32,
+        state_dir: String,
+        sessions: usize,
+        running_commands: usize,
+    ) -> Self {
+        Self {
+            version,
+            uptime,
+            pid,
+            state_dir,
+            sessions,
+            running_commands,
+        }
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+}
+
+/// uptime in `1d 2h 3m 4s` format, omitting leading zero units
+fn uptime(uptime: &Duration) -> String {
+    let seconds = uptime.as_secs();
+    let units = [
+        (seconds / 86400, "d"),
+        ((seconds % 86400) / 3600, "h"),
+        ((seconds % 3600) / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(count, unit)| *count == 0 && *unit != "s")
+        .map(|(count, unit)| format!("{count}{unit}"))
+        .collect();
+    parts.join(" ")
+}
+
+impl Display for DaemonStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}{}", heading("version"), value(&self.version))?;
+        writeln!(f, "{}{}", heading("uptime"), value(&uptime(&self.uptime)))?;
+        writeln!(f, "{}{}", heading("pid"), value(&self.pid.to_string()))?;
+        writeln!(f, "{}{}", heading("state dir"), value(&self.state_dir))?;
+        writeln!(
+            f,
+            "{}{}",
+            heading("sessions"),
+            value(&self.sessions.to_string())
+        )?;
+        write!(
+            f,
+            "{}{}",
+            heading("running commands"),
+            value(&self.running_commands.to_string())
+        )
+    }
+}
+
+impl From<DaemonStatus> for pb::Pong {
+    fn from(value: DaemonStatus) -> Self {
+        let DaemonStatus {
+            version,
+            uptime,
+            pid,
+            state_dir,
+            sessions,
+            running_commands,
+        } = value;
+
+        Self {
+            version,
+            uptime_seconds: uptime.as_secs(),
+            pid,
+            state_dir,
+            sessions: sessions as u32,
+            running_commands: running_commands as u32,
+        }
+    }
+}
+
+impl From<pb::Pong> for DaemonStatus {
+    fn from(value: pb::Pong) -> Self {
+        let pb::Pong {
+            version,
+            uptime_seconds,
+            pid,
+            state_dir,
+            sessions,
+            running_commands,
+        } = value;
+
+        Self {
+            version,
+            uptime: Duration::from_secs(uptime_seconds),
+            pid,
+            state_dir,
+            sessions: sessions as usize,
+            running_commands: running_commands as usize,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn formats_uptime() {
+        assert_eq!(super::uptime(&Duration::from_secs(0)), "0s");
+        assert_eq!(super::uptime(&Duration::from_secs(65)), "1m 5s");
+        assert_eq!(super::uptime(&Duration::from_secs(3600)), "1h 0m 0s");
+        assert_eq!(super::uptime(&Duration::from_secs(90061)), "1d 1h 1m 1s");
+    }
+}
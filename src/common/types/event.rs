@@ -0,0 +1,238 @@
+use std::fmt::{Display, Formatter};
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::common::constants::{CONSTRUCTORS, DESTRUCTORS};
+use crate::common::types::pb;
+use crate::common::types::styles::{sub_value, value};
+use crate::common::types::terrain_state::CommandState;
+
+/// change of session or background command state streamed by daemon to subscribers
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Event {
+    terrain_name: String,
+    session_id: String,
+    #[serde(flatten)]
+    kind: EventKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    SessionStarted {
+        biome_name: String,
+        start_timestamp: String,
+    },
+    SessionEnded {
+        end_timestamp: String,
+    },
+    CommandStateChanged {
+        is_constructor: bool,
+        /// timestamp of the request that spawned the command
+        timestamp: String,
+        index: usize,
+        command_state: CommandState,
+    },
+}
+
+impl Event {
+    pub fn new(terrain_name: String, session_id: String, kind: EventKind) -> Self {
+        Self {
+            terrain_name,
+            session_id,
+            kind,
+        }
+    }
+
+    pub fn terrain_name(&self) -> &str {
+        &self.terrain_name
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    pub fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+
+    /// filters that are not set in `subscribe` match every event
+    pub fn matches(&self, subscribe: &pb::Subscribe) -> bool {
+        subscribe
+            .terrain_name
+            .as_ref()
+            .is_none_or(|terrain_name| terrain_name == &self.terrain_name)
+            && subscribe
+                .session_id
+                .as_ref()
+                .is_none_or(|session_id| session_id == &self.session_id)
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}({}) ",
+            value(&self.terrain_name),
+            sub_value(&self.session_id)
+        )?;
+        match &self.kind {
+            EventKind::SessionStarted { biome_name, .. } => {
+                write!(f, "session started with biome {}", value(biome_name))
+            }
+            EventKind::SessionEnded { .. } => write!(f, "session ended"),
+            EventKind::CommandStateChanged {
+                is_constructor,
+                index,
+                command_state,
+                ..
+            } => {
+                let commands = if *is_constructor {
+                    CONSTRUCTORS
+                } else {
+                    DESTRUCTORS
+                };
+                write!(
+                    f,
+                    "{commands}[{index}] {} {}",
+                    value(&command_state.command().to_string()),
+                    command_state.status()
+                )
+            }
+        }
+    }
+}
+
+impl From<Event> for pb::Event {
+    fn from(value: Event) -> Self {
+        let Event {
+            terrain_name,
+            session_id,
+            kind,
+        } = value;
+
+        let kind = match kind {
+            EventKind::SessionStarted {
+                biome_name,
+                start_timestamp,
+            } => pb::event::Kind::SessionStarted(pb::event::SessionStarted {
+                biome_name,
+                start_timestamp,
+            }),
+            EventKind::SessionEnded { end_timestamp } => {
+                pb::event::Kind::SessionEnded(pb::event::SessionEnded { end_timestamp })
+            }
+            EventKind::CommandStateChanged {
+                is_constructor,
+                timestamp,
+                index,
+                command_state,
+            } => pb::event::Kind::CommandStateChanged(pb::event::CommandStateChanged {
+                is_constructor,
+                timestamp,
+                index: index as u32,
+                command_state: Some(command_state.into()),
+            }),
+        };
+
+        Self {
+            terrain_name,
+            session_id,
+            kind: Some(kind),
+        }
+    }
+}
+
+impl TryFrom<pb::Event> for Event {
+    type Error = anyhow::Error;
+
+    fn try_from(value: pb::Event) -> Result<Self> {
+        let pb::Event {
+            terrain_name,
+            session_id,
+            kind,
+        } = value;
+
+        let kind = match kind {
+            None => bail!("event kind not found"),
+            Some(pb::event::Kind::SessionStarted(pb::event::SessionStarted {
+                biome_name,
+                start_timestamp,
+            })) => EventKind::SessionStarted {
+                biome_name,
+                start_timestamp,
+            },
+            Some(pb::event::Kind::SessionEnded(pb::event::SessionEnded { end_timestamp })) => {
+                EventKind::SessionEnded { end_timestamp }
+            }
+            Some(pb::event::Kind::CommandStateChanged(pb::event::CommandStateChanged {
+                is_constructor,
+                timestamp,
+                index,
+                command_state,
+            })) => EventKind::CommandStateChanged {
+                is_constructor,
+                timestamp,
+                index: index as usize,
+                command_state: command_state
+                    .context("command state not found")?
+                    .try_into()
+                    .context("failed to convert command state")?,
+            },
+        };
+
+        Ok(Self {
+            terrain_name,
+            session_id,
+            kind,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::{Event, EventKind};
+    use crate::common::test_utils::{TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::pb;
+
+    fn session_ended() -> Event {
+        Event::new(
+            TEST_TERRAIN_NAME.to_string(),
+            TEST_SESSION_ID.to_string(),
+            EventKind::SessionEnded {
+                end_timestamp: "timestamp".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn matches_subscribe_filters() {
+        let event = session_ended();
+        let subscribe = |terrain_name: Option<&str>, session_id: Option<&str>| pb::Subscribe {
+            terrain_name: terrain_name.map(str::to_string),
+            session_id: session_id.map(str::to_string),
+        };
+
+        assert!(event.matches(&subscribe(None, None)));
+        assert!(event.matches(&subscribe(Some(TEST_TERRAIN_NAME), None)));
+        assert!(event.matches(&subscribe(Some(TEST_TERRAIN_NAME), Some(TEST_SESSION_ID))));
+        assert!(!event.matches(&subscribe(Some("other"), None)));
+        assert!(!event.matches(&subscribe(None, Some("other"))));
+    }
+
+    #[test]
+    fn serializes_kind_as_event_tag() {
+        let event = session_ended();
+        assert_eq!(
+            serde_json::to_string(&event).unwrap(),
+            r#"{"terrain_name":"terrainium","session_id":"session_id","event":"session_ended","end_timestamp":"timestamp"}"#
+        );
+
+        let converted: Event = pb::Event::from(event.clone()).try_into().unwrap();
+        assert_eq!(converted, event);
+    }
+}
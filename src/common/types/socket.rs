@@ -1,3 +1,5 @@
+use std::io::ErrorKind;
+
 use anyhow::{Context, Result};
 use prost::Message;
 use prost_types::Any;
@@ -64,3 +66,44 @@ pub async fn socket_stop_write(socket: &mut impl Socket) -> Result<()> {
         .await
         .context("failed to shutdown socket")
 }
+
+/// writes payload prefixed with its length, so multiple payloads can be streamed
+/// on the same socket
+pub async fn socket_write_frame(socket: &mut impl Socket, payload: Any) -> Result<()> {
+    socket
+        .stream()
+        .write_all(payload.encode_length_delimited_to_vec().as_ref())
+        .await
+        .context("failed to write frame to socket")?;
+
+    socket
+        .stream()
+        .flush()
+        .await
+        .context("failed to flush the frame")
+}
+
+/// reads payload written by `socket_write_frame`, `None` if other side has closed
+/// the stream
+pub async fn socket_read_frame(socket: &mut impl Socket) -> Result<Option<Any>> {
+    let mut length: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = match socket.stream().read_u8().await {
+            Ok(byte) => byte,
+            Err(err) if shift == 0 && err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err).context("failed to read frame length"),
+        };
+        length |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let mut buf = vec![0; length as usize];
+    socket
+        .stream()
+        .read_exact(&mut buf)
+        .await
+        .context("failed to read frame")?;
+    Ok(Some(Any::decode(buf.as_ref())?))
+}
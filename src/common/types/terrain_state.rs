@@ -101,6 +101,16 @@ pub struct TerrainState {
     envs: BTreeMap<String, String>,
     constructors: BTreeMap<String, Vec<CommandState>>,
     destructors: BTreeMap<String, Vec<CommandState>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    biome_switches: Vec<BiomeSwitch>,
+}
+
+/// biome change made by `terrain switch` in an active session
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BiomeSwitch {
+    from_biome: String,
+    to_biome: String,
+    timestamp: String,
 }
 
 impl TerrainState {
@@ -116,6 +126,14 @@ impl TerrainState {
         self.terrain_name.as_str()
     }
 
+    pub fn biome_name(&self) -> &str {
+        self.biome_name.as_str()
+    }
+
+    pub fn start_timestamp(&self) -> &str {
+        self.start_timestamp.as_str()
+    }
+
     pub fn state_dir(&self, state_directory: &str) -> PathBuf {
         Self::get_state_dir(state_directory, self.terrain_name(), self.session_id())
     }
@@ -152,6 +170,18 @@ impl TerrainState {
         }
     }
 
+    /// number of running (or starting) and failed constructors of the session
+    pub fn constructors_health(&self) -> (usize, usize) {
+        self.constructors
+            .values()
+            .flatten()
+            .fold((0, 0), |(running, failed), state| match state.status {
+                CommandStatus::Starting | CommandStatus::Running => (running + 1, failed),
+                CommandStatus::Failed(_) => (running, failed + 1),
+                CommandStatus::Succeeded => (running, failed),
+            })
+    }
+
     pub fn get_destructors(&self, timestamp: &str) -> Result<Vec<CommandState>> {
         match self.destructors.get(timestamp) {
             None => {
@@ -213,6 +243,27 @@ impl TerrainState {
         Ok(())
     }
 
+    pub fn switch_biome(
+        &mut self,
+        from_biome: String,
+        to_biome: String,
+        envs: BTreeMap<String, String>,
+        timestamp: String,
+    ) {
+        debug!(
+            terrain_name = self.terrain_name,
+            session_id = self.session_id,
+            "switching biome from {from_biome} to {to_biome} at {timestamp}",
+        );
+        self.biome_name = to_biome.clone();
+        self.envs = envs;
+        self.biome_switches.push(BiomeSwitch {
+            from_biome,
+            to_biome,
+            timestamp,
+        });
+    }
+
     pub fn update_end_timestamp(&mut self, timestamp: String) {
         debug!(
             terrain_name = self.terrain_name,
@@ -258,7 +309,24 @@ impl Display for TerrainState {
             command_states_to_display(&self.constructors),
             heading(" destructors"),
             command_states_to_display(&self.destructors),
-        )
+        )?;
+
+        if !self.biome_switches.is_empty() {
+            let switches: String = self
+                .biome_switches
+                .iter()
+                .map(|switch| {
+                    format!(
+                        "\n    {} {} -> {}",
+                        sub_heading(&switch.timestamp),
+                        sub_value(&switch.from_biome),
+                        value(&switch.to_biome)
+                    )
+                })
+                .collect();
+            writeln!(f, "{}\n  {}", heading("󰓡 switches"), switches)?;
+        }
+        Ok(())
     }
 }
 
@@ -293,6 +361,14 @@ impl CommandState {
         }
     }
 
+    pub(crate) fn command(&self) -> &Command {
+        &self.command
+    }
+
+    pub(crate) fn status(&self) -> &CommandStatus {
+        &self.status
+    }
+
     pub(crate) fn command_and_log_path(self) -> (Command, String) {
         (self.command, self.log_path)
     }
@@ -414,6 +490,7 @@ impl From<pb::Activate> for TerrainState {
             envs,
             constructors: constructors_state,
             destructors: Default::default(),
+            biome_switches: vec![],
         }
     }
 }
@@ -430,6 +507,8 @@ impl From<pb::Execute> for TerrainState {
             timestamp,
             envs,
             commands,
+            // secrets are only passed to the commands
+            secrets: _,
         } = value;
 
         let non_numeric = remove_non_numeric(&timestamp);
@@ -472,6 +551,7 @@ impl From<pb::Execute> for TerrainState {
             envs,
             constructors,
             destructors,
+            biome_switches: vec![],
         }
     }
 }
@@ -491,6 +571,7 @@ impl TryFrom<Box<pb::StatusResponse>> for TerrainState {
             envs,
             constructors,
             destructors,
+            biome_switches,
         } = *value;
 
         let constructors_state = command_states_from(constructors)?;
@@ -508,6 +589,7 @@ impl TryFrom<Box<pb::StatusResponse>> for TerrainState {
             envs,
             constructors: constructors_state,
             destructors: destructors_state,
+            biome_switches: biome_switches.into_iter().map(Into::into).collect(),
         })
     }
 }
@@ -526,6 +608,7 @@ impl From<TerrainState> for pb::StatusResponse {
             envs,
             constructors,
             destructors,
+            biome_switches,
         } = value;
 
         Self {
@@ -540,6 +623,37 @@ impl From<TerrainState> for pb::StatusResponse {
             envs,
             constructors: command_states_to_proto(constructors),
             destructors: command_states_to_proto(destructors),
+            biome_switches: biome_switches.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<BiomeSwitch> for pb::status_response::BiomeSwitch {
+    fn from(value: BiomeSwitch) -> Self {
+        let BiomeSwitch {
+            from_biome,
+            to_biome,
+            timestamp,
+        } = value;
+        Self {
+            from_biome,
+            to_biome,
+            timestamp,
+        }
+    }
+}
+
+impl From<pb::status_response::BiomeSwitch> for BiomeSwitch {
+    fn from(value: pb::status_response::BiomeSwitch) -> Self {
+        let pb::status_response::BiomeSwitch {
+            from_biome,
+            to_biome,
+            timestamp,
+        } = value;
+        Self {
+            from_biome,
+            to_biome,
+            timestamp,
         }
     }
 }
@@ -635,7 +749,7 @@ pub mod test_utils {
     };
     use crate::client::types::terrain::AutoApply;
     use crate::common::constants::{
-        CONSTRUCTORS, DESTRUCTORS, EXAMPLE_BIOME, TERRAIN_TOML, TEST_TIMESTAMP,
+        CONSTRUCTORS, DESTRUCTORS, EXAMPLE_BIOME, NONE, TERRAIN_TOML, TEST_TIMESTAMP,
     };
     use crate::common::test_utils::{
         TEST_TERRAIN_DIR, TEST_TERRAIN_NAME, TEST_TIMESTAMP_NUMERIC,
@@ -710,6 +824,7 @@ pub mod test_utils {
             envs: expected_envs_with_activate_example_biome(is_auto_apply, auto_apply),
             constructors,
             destructors: Default::default(),
+            biome_switches: vec![],
         }
     }
 
@@ -741,6 +856,21 @@ pub mod test_utils {
         )
     }
 
+    pub fn terrain_state_after_switch(
+        session_id: String,
+        envs: BTreeMap<String, String>,
+    ) -> TerrainState {
+        let mut state = terrain_state_after_construct(session_id, false, &AutoApply::Off);
+        state.biome_name = NONE.to_string();
+        state.envs = envs;
+        state.biome_switches = vec![BiomeSwitch {
+            from_biome: EXAMPLE_BIOME.to_string(),
+            to_biome: NONE.to_string(),
+            timestamp: TEST_TIMESTAMP.to_string(),
+        }];
+        state
+    }
+
     pub fn terrain_state_after_construct_failed(
         session_id: String,
         is_auto_apply: bool,
@@ -893,6 +1023,7 @@ pub mod test_utils {
             envs: expected_env_vars_example_biome(),
             constructors,
             destructors,
+            biome_switches: vec![],
         }
     }
 }
@@ -1,7 +1,8 @@
 use std::fs::create_dir_all;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::Context;
+use home::home_dir;
 use regex::Regex;
 use tokio::fs::File;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
@@ -18,15 +19,19 @@ pub fn timestamp() -> String {
         TEST_TIMESTAMP.to_string()
     } else if let Ok(now) = time::OffsetDateTime::now_local() {
         now.format(
-            &time::format_description::parse("[year]-[month]-[day]_[hour]:[minute]:[second]")
-                .expect("time format to be parsed"),
+            &time::format_description::parse_borrowed::<2>(
+                "[year]-[month]-[day]_[hour]:[minute]:[second]",
+            )
+            .expect("time format to be parsed"),
         )
         .expect("time to be formatted")
     } else {
         time::OffsetDateTime::now_utc()
             .format(
-                &time::format_description::parse("[year]-[month]-[day]_[hour]:[minute]:[second]")
-                    .expect("time format to be parsed"),
+                &time::format_description::parse_borrowed::<2>(
+                    "[year]-[month]-[day]_[hour]:[minute]:[second]",
+                )
+                .expect("time format to be parsed"),
             )
             .expect("time to be formatted")
     }
@@ -95,3 +100,124 @@ const BUILD_MODE: &str = if cfg!(debug_assertions) {
 };
 
 pub const VERSION_INFO: &str = const_str::concat!("v", VERSION, "-", BUILD_MODE, "+", GIT_HASH);
+
+/// expands leading `~` in the path to home directory of the user
+pub fn expand_tilde(path: &str) -> PathBuf {
+    if path == "~" {
+        if let Some(home) = home_dir() {
+            return home;
+        }
+    } else if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = home_dir()
+    {
+        return home.join(rest);
+    }
+    PathBuf::from(path)
+}
+
+/// number of unchanged lines shown around the changes in unified diff
+const DIFF_CONTEXT: usize = 3;
+
+/// unified diff of two texts, returns empty string if texts are same
+pub fn unified_diff(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    // line numbers in old and new text before each line of the diff
+    let mut old_line = 0;
+    let mut new_line = 0;
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let lines: Vec<(usize, usize, diff::Result<&&str>)> = diff::slice(&old_lines, &new_lines)
+        .into_iter()
+        .map(|line| {
+            let (old, new) = (old_line, new_line);
+            match line {
+                diff::Result::Left(_) => old_line += 1,
+                diff::Result::Right(_) => new_line += 1,
+                diff::Result::Both(_, _) => {
+                    old_line += 1;
+                    new_line += 1;
+                }
+            }
+            (old, new, line)
+        })
+        .collect();
+
+    let changes: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, _, line))| !matches!(line, diff::Result::Both(_, _)))
+        .map(|(idx, _)| idx)
+        .collect();
+
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // group changes that are close enough to share their context lines
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    changes.into_iter().for_each(|idx| match hunks.last_mut() {
+        Some((_, end)) if idx <= *end + 2 * DIFF_CONTEXT => *end = idx,
+        _ => hunks.push((idx, idx)),
+    });
+
+    let mut diff = format!("--- {old_name}\n+++ {new_name}\n");
+    hunks.into_iter().for_each(|(first, last)| {
+        let start = first.saturating_sub(DIFF_CONTEXT);
+        let end = (last + DIFF_CONTEXT + 1).min(lines.len());
+        let hunk = &lines[start..end];
+
+        let old_count = hunk
+            .iter()
+            .filter(|(_, _, line)| !matches!(line, diff::Result::Right(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|(_, _, line)| !matches!(line, diff::Result::Left(_)))
+            .count();
+        let (old_start, new_start, _) = hunk[0];
+
+        // empty ranges start at the line before the change
+        let old_start = if old_count == 0 {
+            old_start
+        } else {
+            old_start + 1
+        };
+        let new_start = if new_count == 0 {
+            new_start
+        } else {
+            new_start + 1
+        };
+        diff += &format!("@@ -{old_start},{old_count} +{new_start},{new_count} @@\n");
+
+        hunk.iter().for_each(|(_, _, line)| match line {
+            diff::Result::Left(l) => diff += &format!("-{l}\n"),
+            diff::Result::Right(r) => diff += &format!("+{r}\n"),
+            diff::Result::Both(b, _) => diff += &format!(" {b}\n"),
+        });
+    });
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::unified_diff;
+
+    #[test]
+    fn unified_diff_of_same_text_is_empty() {
+        assert_eq!(unified_diff("a", "b", "one\ntwo\n", "one\ntwo\n"), "");
+    }
+
+    #[test]
+    fn unified_diff_groups_changes_into_hunks() {
+        let old = (1..=20).map(|i| format!("line{i}\n")).collect::<String>();
+        let new = old.replace("line2\n", "line two\n").replace("line18\n", "");
+
+        let expected = "--- old\n+++ new\n\
+            @@ -1,5 +1,5 @@\n line1\n-line2\n+line two\n line3\n line4\n line5\n\
+            @@ -15,6 +15,5 @@\n line15\n line16\n line17\n-line18\n line19\n line20\n";
+
+        assert_eq!(unified_diff("old", "new", &old, &new), expected);
+    }
+}
@@ -85,5 +85,10 @@ pub enum Verbs {
     ///
     /// Status can be: "running(enabled|disabled)",
     /// "not running(enabled|disabled)", "not loaded", "not installed"
-    Status,
+    Status {
+        /// Also ping the running daemon for its version, uptime, pid, state
+        /// directory and number of tracked sessions and running commands
+        #[arg(short, long)]
+        daemon: bool,
+    },
 }
@@ -10,7 +10,10 @@ use crate::common::types::socket::Socket;
 use crate::daemon::handlers::activate::ActivateHandler;
 use crate::daemon::handlers::deactivate::DeactivateHandler;
 use crate::daemon::handlers::execute::ExecuteHandler;
+use crate::daemon::handlers::ping::PingHandler;
 use crate::daemon::handlers::status::StatusHandler;
+use crate::daemon::handlers::subscribe::SubscribeHandler;
+use crate::daemon::handlers::switch::SwitchHandler;
 use crate::daemon::types::context::DaemonContext;
 #[mockall_double::double]
 use crate::daemon::types::daemon_socket::DaemonSocket;
@@ -18,7 +21,10 @@ use crate::daemon::types::daemon_socket::DaemonSocket;
 mod activate;
 mod deactivate;
 mod execute;
+mod ping;
 mod status;
+mod subscribe;
+mod switch;
 
 pub(crate) trait RequestHandler {
     async fn handle(request: Any, context: Arc<DaemonContext>) -> Any;
@@ -40,6 +46,11 @@ pub async fn handle_request(context: Arc<DaemonContext>, mut daemon_socket: Daem
                 "/terrainium.v1.Execute" => ExecuteHandler::handle(request, context).await,
                 "/terrainium.v1.Deactivate" => DeactivateHandler::handle(request, context).await,
                 "/terrainium.v1.StatusRequest" => StatusHandler::handle(request, context).await,
+                "/terrainium.v1.Ping" => PingHandler::handle(request, context).await,
+                "/terrainium.v1.Switch" => SwitchHandler::handle(request, context).await,
+                "/terrainium.v1.Subscribe" => {
+                    return SubscribeHandler::handle(request, context, daemon_socket).await;
+                }
                 _ => {
                     let err = anyhow!("invalid request type: {:?}", request.type_url);
                     Any::from_msg(&error_response(err)).expect("failed to create an error response")
@@ -63,7 +63,11 @@ async fn activate(request: Activate, context: Arc<DaemonContext>) -> Result<Resp
     }
 
     Ok(Response {
-        payload: Some(Body(pb::Body { message: None })),
+        payload: Some(Body(pb::Body {
+            message: None,
+            pong: None,
+            event: None,
+        })),
     })
 }
 
@@ -60,7 +60,11 @@ async fn deactivate(request: Deactivate, context: Arc<DaemonContext>) -> Respons
 
     match result {
         Ok(()) => Response {
-            payload: Some(Body(pb::Body { message: None })),
+            payload: Some(Body(pb::Body {
+                message: None,
+                pong: None,
+                event: None,
+            })),
         },
         Err(err) => error_response(err),
     }
@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 use anyhow::{Context, Result, bail};
 use prost_types::Any;
@@ -16,6 +17,7 @@ use crate::common::types::terrain_state::{CommandState, CommandStatus, TerrainSt
 use crate::common::utils::remove_non_numeric;
 use crate::daemon::handlers::{RequestHandler, error_response};
 use crate::daemon::types::context::DaemonContext;
+use crate::daemon::types::notifier::Notifier;
 use crate::daemon::types::state_manager::{StoredHistory, StoredState};
 
 pub(crate) struct ExecuteHandler;
@@ -37,7 +39,11 @@ impl RequestHandler for ExecuteHandler {
                 } else {
                     debug!("successfully spawned commands");
                     Response {
-                        payload: Some(Body(pb::Body { message: None })),
+                        payload: Some(Body(pb::Body {
+                            message: None,
+                            pong: None,
+                            event: None,
+                        })),
                     }
                 }
             }
@@ -62,62 +68,65 @@ pub(crate) async fn spawn_commands(
 ) -> Result<()> {
     let timestamp = request.timestamp.clone();
     let is_constructor = request.is_constructor;
+    let secrets = request.secrets.clone();
 
-    let (terrain_name, session_id) = if request.session_id.is_none() {
-        // session_id is not provided that means running constructors or destructors
-        // outside terrainium shell so create a new state
+    let (terrain_name, session_id) = match request.session_id.clone() {
+        None => {
+            // session_id is not provided that means running constructors or destructors
+            // outside terrainium shell so create a new state
 
-        let state: TerrainState = request.into();
+            let state: TerrainState = request.into();
 
-        let terrain_name = state.terrain_name().to_string();
-        let session_id = state.session_id().to_string();
+            let terrain_name = state.terrain_name().to_string();
+            let session_id = state.session_id().to_string();
 
-        debug!(
-            terrain_name = terrain_name,
-            timestamp = timestamp,
-            is_constructor = is_constructor,
-            "execute request does not have associated session id"
-        );
-        context.state_manager().create_state(state).await?;
-
-        (terrain_name, session_id)
-    } else {
-        // if session_id is present check if CommandStatus is present for current
-        // timestamp else add new entry
-        let session_id = request.session_id.unwrap();
-        let numeric_timestamp = remove_non_numeric(&timestamp);
-        let terrain_name = request.terrain_name;
-
-        let commands = request
-            .commands
-            .into_iter()
-            .enumerate()
-            .map(|(index, cmd)| {
-                CommandState::from(
-                    context.state_paths().dir_str(),
+            debug!(
+                terrain_name = terrain_name,
+                timestamp = timestamp,
+                is_constructor = is_constructor,
+                "execute request does not have associated session id"
+            );
+            context.state_manager().create_state(state).await?;
+
+            (terrain_name, session_id)
+        }
+        Some(session_id) => {
+            // if session_id is present check if CommandStatus is present for current
+            // timestamp else add new entry
+            let numeric_timestamp = remove_non_numeric(&timestamp);
+            let terrain_name = request.terrain_name;
+
+            let commands = request
+                .commands
+                .into_iter()
+                .enumerate()
+                .map(|(index, cmd)| {
+                    CommandState::from(
+                        context.state_paths().dir_str(),
+                        &terrain_name,
+                        &session_id,
+                        is_constructor,
+                        index,
+                        &numeric_timestamp,
+                        cmd,
+                    )
+                })
+                .collect();
+
+            context
+                .state_manager()
+                .add_commands_if_necessary(
                     &terrain_name,
                     &session_id,
+                    &timestamp,
                     is_constructor,
-                    index,
-                    &numeric_timestamp,
-                    cmd,
+                    commands,
                 )
-            })
-            .collect();
-
-        context
-            .state_manager()
-            .add_commands_if_necessary(
-                &terrain_name,
-                &session_id,
-                &timestamp,
-                is_constructor,
-                commands,
-            )
-            .await
-            .context("failed to add commands to state manager")?;
+                .await
+                .context("failed to add commands to state manager")?;
 
-        (terrain_name, session_id)
+            (terrain_name, session_id)
+        }
     };
 
     let stored_state = context
@@ -126,7 +135,10 @@ pub(crate) async fn spawn_commands(
         .await
         .context("failed to retrieve state from state manager")?;
 
-    let envs = Arc::new(stored_state.clone().read().await.envs());
+    // secrets are passed to the commands but are not stored in the state
+    let mut envs = stored_state.clone().read().await.envs();
+    envs.extend(secrets);
+    let envs = Arc::new(envs);
     let commands = stored_state
         .clone()
         .read()
@@ -149,9 +161,13 @@ pub(crate) async fn spawn_commands(
             let executor = context.executor();
             let envs = envs.clone();
             let (command, log_path) = cmd_state.command_and_log_path();
+            let running_commands = context.running_commands_counter();
+            let notifier = context.notifier();
             tokio::spawn(async move {
+                running_commands.fetch_add(1, Ordering::Relaxed);
                 let res = spawn_command(
                     executor,
+                    notifier,
                     history,
                     stored_state,
                     CommandInfo {
@@ -164,6 +180,7 @@ pub(crate) async fn spawn_commands(
                     },
                 )
                 .await;
+                running_commands.fetch_sub(1, Ordering::Relaxed);
 
                 if let Err(err) = res {
                     error!("failed to spawn command: {:?}", err);
@@ -176,6 +193,7 @@ pub(crate) async fn spawn_commands(
 
 async fn spawn_command(
     executor: Arc<Executor>,
+    notifier: Notifier,
     history: StoredHistory,
     stored_state: StoredState,
     command_info: CommandInfo,
@@ -225,7 +243,7 @@ async fn spawn_command(
     match res {
         Ok(exit_status) => {
             if exit_status.success() {
-                state_mut
+                let event = state_mut
                     .update_command_status(
                         history,
                         is_constructor,
@@ -234,6 +252,7 @@ async fn spawn_command(
                         CommandStatus::Succeeded,
                     )
                     .await?;
+                notifier.notify(event);
                 debug!(
                     terrain_name = terrain_name,
                     session_id = session_id,
@@ -243,7 +262,7 @@ async fn spawn_command(
                     "command {cmd_str} completed successfully"
                 );
             } else {
-                state_mut
+                let event = state_mut
                     .update_command_status(
                         history,
                         is_constructor,
@@ -252,6 +271,7 @@ async fn spawn_command(
                         CommandStatus::Failed(exit_status.code()),
                     )
                     .await?;
+                notifier.notify(event);
 
                 let error = format!(
                     "command: {cmd_str} exited with code {:?}",
@@ -269,7 +289,7 @@ async fn spawn_command(
             }
         }
         Err(err) => {
-            state_mut
+            let event = state_mut
                 .update_command_status(
                     history,
                     is_constructor,
@@ -278,6 +298,7 @@ async fn spawn_command(
                     CommandStatus::Failed(None),
                 )
                 .await?;
+            notifier.notify(event);
             let error = format!("failed to spawn command: {cmd_str} with an error: {err:#?}");
             error!(
                 terrain_name = terrain_name,
@@ -297,12 +318,16 @@ async fn spawn_command(
 #[cfg(test)]
 mod tests {
     use std::fs;
+    use std::os::unix::process::ExitStatusExt;
     use std::path::PathBuf;
+    use std::process::ExitStatus;
     use std::sync::Arc;
+    use std::time::Duration;
 
     use pretty_assertions::assert_eq;
     use tempfile::tempdir;
-    use tokio::sync::RwLock;
+    use tokio::sync::{RwLock, broadcast, mpsc};
+    use tokio::time::timeout;
 
     use crate::client::test_utils::assertions::executor::{AssertExecutor, ExpectedCommand};
     use crate::client::types::terrain::AutoApply;
@@ -324,9 +349,10 @@ mod tests {
     use crate::common::types::terrain_state::{CommandStatus, TerrainState};
     use crate::common::utils::{create_file, write_to_file};
     use crate::daemon::handlers::execute::{CommandInfo, spawn_commands};
-    use crate::daemon::types::config::DaemonConfig;
+    use crate::daemon::types::config::{DaemonConfig, Notifications};
     use crate::daemon::types::context::DaemonContext;
     use crate::daemon::types::history::History;
+    use crate::daemon::types::notifier::Notifier;
     use crate::daemon::types::state::State;
 
     #[tokio::test]
@@ -367,6 +393,51 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn passes_secrets_to_commands_without_storing_them() {
+        let state_directory = tempdir().unwrap();
+
+        let mut request = expected_execute_request_example_biome(None, true);
+        request
+            .secrets
+            .insert("TOKEN".to_string(), "secret-value".to_string());
+
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut executor = MockExecutor::new();
+        executor
+            .expect_async_spawn_with_log()
+            .returning(move |_, envs, _| {
+                sender.send(envs).unwrap();
+                Ok(ExitStatus::from_raw(0))
+            });
+
+        let context = DaemonContext::new(
+            false,
+            DaemonConfig::default(),
+            Arc::new(executor),
+            Default::default(),
+            DaemonPaths::new(state_directory.path().to_str().unwrap()),
+        )
+        .await;
+
+        spawn_commands(request, Arc::new(context)).await.unwrap();
+
+        let envs = timeout(Duration::from_secs(5), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap()
+            .expect("envs to be passed to command");
+        assert_eq!(envs.get("TOKEN").unwrap(), "secret-value");
+        assert_eq!(envs.get("EDITOR").unwrap(), "nvim");
+
+        let terrain_state_file = state_directory.path().join(format!(
+            "{TEST_TERRAIN_NAME}/{TEST_TIMESTAMP_NUMERIC}/{TERRAIN_STATE_FILE_NAME}"
+        ));
+        let state = fs::read_to_string(&terrain_state_file).unwrap();
+        assert!(!state.contains("TOKEN"));
+        assert!(!state.contains("secret-value"));
+    }
+
     #[tokio::test]
     async fn add_construct_with_new_timestamp() {
         let state_directory = tempdir().unwrap();
@@ -686,7 +757,9 @@ mod tests {
             .await
             .unwrap();
 
-        let state = State::read(&state_path).await.unwrap();
+        let state = State::read(&state_path, broadcast::channel(1).0)
+            .await
+            .unwrap();
         let history = History::read(state_dir_path.to_str().unwrap(), TEST_TERRAIN_NAME, 5)
             .await
             .unwrap();
@@ -720,8 +793,10 @@ mod tests {
             )
             .successfully();
 
+        let executor = Arc::new(executor);
         super::spawn_command(
-            Arc::new(executor),
+            executor.clone(),
+            Notifier::new(Notifications::default(), executor),
             Arc::new(RwLock::new(history)),
             Arc::new(RwLock::new(state)),
             CommandInfo {
@@ -778,7 +853,9 @@ mod tests {
             .await
             .unwrap();
 
-        let state = State::read(&state_path).await.unwrap();
+        let state = State::read(&state_path, broadcast::channel(1).0)
+            .await
+            .unwrap();
         let history = History::read(state_dir_path.to_str().unwrap(), TEST_TERRAIN_NAME, 5)
             .await
             .unwrap();
@@ -812,8 +889,10 @@ mod tests {
             )
             .successfully();
 
+        let executor = Arc::new(executor);
         let error = super::spawn_command(
-            Arc::new(executor),
+            executor.clone(),
+            Notifier::new(Notifications::default(), executor),
             Arc::new(RwLock::new(history)),
             Arc::new(RwLock::new(state)),
             CommandInfo {
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prost_types::Any;
+use tracing::trace;
+
+use crate::common::types::daemon_status::DaemonStatus;
+use crate::common::types::pb;
+use crate::common::types::pb::Response;
+use crate::common::types::pb::response::Payload::Body;
+use crate::common::utils::VERSION_INFO;
+use crate::daemon::handlers::{RequestHandler, error_response};
+use crate::daemon::types::context::DaemonContext;
+
+pub struct PingHandler;
+
+impl RequestHandler for PingHandler {
+    async fn handle(request: Any, context: Arc<DaemonContext>) -> Any {
+        trace!("handling Ping request");
+        let request: Result<pb::Ping> = request
+            .to_msg()
+            .context("failed to convert request to Ping");
+
+        let response = match request {
+            Ok(_) => pong(context).await,
+            Err(err) => error_response(err),
+        };
+        Any::from_msg(&response).unwrap()
+    }
+}
+
+async fn pong(context: Arc<DaemonContext>) -> Response {
+    let status = DaemonStatus::new(
+        VERSION_INFO.to_string(),
+        context.uptime(),
+        std::process::id(),
+        context.state_paths().dir_str().to_string(),
+        context.state_manager().sessions().await,
+        context.running_commands(),
+    );
+
+    Response {
+        payload: Some(Body(pb::Body {
+            message: None,
+            pong: Some(status.into()),
+            event: None,
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::common::types::paths::DaemonPaths;
+    use crate::common::types::pb;
+    use crate::common::types::pb::response::Payload;
+    use crate::common::utils::VERSION_INFO;
+    use crate::daemon::handlers::ping::pong;
+    use crate::daemon::types::context::DaemonContext;
+
+    #[tokio::test]
+    async fn responds_with_daemon_status() {
+        let state_dir = tempdir().unwrap();
+        let state_dir = state_dir.path().to_str().unwrap();
+
+        let context = DaemonContext::new(
+            false,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            DaemonPaths::new(state_dir),
+        )
+        .await;
+
+        let response = pong(Arc::new(context)).await;
+        let Some(Payload::Body(pb::Body {
+            message: None,
+            pong: Some(pong),
+            event: None,
+        })) = response.payload
+        else {
+            panic!("expected pong response");
+        };
+
+        assert_eq!(pong.version, VERSION_INFO);
+        assert_eq!(pong.pid, std::process::id());
+        assert_eq!(pong.state_dir, state_dir);
+        assert_eq!(pong.sessions, 0);
+        assert_eq!(pong.running_commands, 0);
+    }
+}
@@ -67,6 +67,8 @@ async fn status(request: StatusRequest, context: Arc<DaemonContext>) -> Result<R
     Ok(Response {
         payload: Some(Body(pb::Body {
             message: Some(state),
+            pong: None,
+            event: None,
         })),
     })
 }
@@ -137,6 +139,7 @@ mod tests {
             envs: expected_envs_with_activate_example_biome(is_auto_apply, auto_apply),
             constructors,
             destructors: Default::default(),
+            biome_switches: vec![],
         }
     }
 
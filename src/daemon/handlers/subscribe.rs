@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prost_types::Any;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, trace, warn};
+
+use crate::common::types::event::Event;
+use crate::common::types::pb;
+use crate::common::types::pb::Response;
+use crate::common::types::pb::response::Payload::Body;
+use crate::common::types::socket::{Socket, socket_write_frame};
+use crate::daemon::handlers::error_response;
+use crate::daemon::types::context::DaemonContext;
+
+/// unlike other requests, connection is kept open after `Subscribe` request and
+/// each matching event is written as a separate frame
+pub(crate) struct SubscribeHandler;
+
+impl SubscribeHandler {
+    pub(crate) async fn handle(request: Any, context: Arc<DaemonContext>, mut socket: impl Socket) {
+        trace!("handling Subscribe request");
+        let subscribe: Result<pb::Subscribe> = request
+            .to_msg()
+            .context("failed to convert request to Subscribe");
+
+        match subscribe {
+            Ok(subscribe) => {
+                let events = context.state_manager().subscribe();
+                stream_events(socket, subscribe, events, context.cancellation_token()).await;
+            }
+            Err(err) => {
+                let response = Any::from_msg(&error_response(err)).expect("error response");
+                if let Err(err) = socket_write_frame(&mut socket, response).await {
+                    error!("error responding to the request: {err:#?}");
+                }
+            }
+        }
+    }
+}
+
+/// writes events matching `subscribe` until subscriber disconnects or daemon
+/// is stopped
+async fn stream_events(
+    mut socket: impl Socket,
+    subscribe: pb::Subscribe,
+    mut events: Receiver<Event>,
+    cancellation_token: CancellationToken,
+) {
+    loop {
+        // events already received are written before stopping
+        let event = tokio::select! {
+            biased;
+            event = events.recv() => event,
+            _ = cancellation_token.cancelled() => break,
+        };
+
+        match event {
+            Ok(event) if event.matches(&subscribe) => {
+                let response = Any::from_msg(&event_response(event)).expect("event response");
+                if let Err(err) = socket_write_frame(&mut socket, response).await {
+                    debug!("subscriber disconnected: {err:#}");
+                    return;
+                }
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(missed)) => warn!("subscriber missed {missed} events"),
+            Err(RecvError::Closed) => break,
+        }
+    }
+
+    if let Err(err) = socket.stop_write().await {
+        debug!("failed to close subscriber stream: {err:#}");
+    }
+}
+
+fn event_response(event: Event) -> Response {
+    Response {
+        payload: Some(Body(pb::Body {
+            message: None,
+            pong: None,
+            event: Some(event.into()),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use tokio::net::UnixStream;
+
+    use super::stream_events;
+    use crate::client::types::terrain::AutoApply;
+    use crate::common::constants::{EXAMPLE_BIOME, TEST_TIMESTAMP};
+    use crate::common::test_utils::{TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::event::{Event, EventKind};
+    use crate::common::types::paths::DaemonPaths;
+    use crate::common::types::pb;
+    use crate::common::types::pb::response::Payload;
+    use crate::common::types::socket::socket_read_frame;
+    use crate::common::types::terrain_state::test_utils::terrain_state_after_activate;
+    use crate::daemon::types::context::DaemonContext;
+    use crate::daemon::types::daemon_socket::DaemonSocket;
+
+    #[tokio::test]
+    async fn streams_matching_events_until_cancelled() {
+        let state_dir = tempdir().unwrap();
+        let context = Arc::new(
+            DaemonContext::new(
+                false,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                DaemonPaths::new(state_dir.path().to_str().unwrap()),
+            )
+            .await,
+        );
+        let events = context.state_manager().subscribe();
+
+        let state =
+            terrain_state_after_activate(TEST_SESSION_ID.to_string(), false, &AutoApply::default());
+        context.state_manager().create_state(state).await.unwrap();
+        context
+            .state_manager()
+            .update_end_time(TEST_TERRAIN_NAME, TEST_SESSION_ID, "end".to_string())
+            .await
+            .unwrap();
+        context.cancellation_token().cancel();
+
+        let (daemon, subscriber) = UnixStream::pair().unwrap();
+        let subscribe = pb::Subscribe {
+            terrain_name: Some(TEST_TERRAIN_NAME.to_string()),
+            session_id: None,
+        };
+        stream_events(
+            DaemonSocket::new(daemon),
+            subscribe,
+            events,
+            context.cancellation_token(),
+        )
+        .await;
+
+        let mut subscriber = DaemonSocket::new(subscriber);
+        let mut received = vec![];
+        while let Some(frame) = socket_read_frame(&mut subscriber).await.unwrap() {
+            let response: pb::Response = frame.to_msg().unwrap();
+            let Some(Payload::Body(pb::Body {
+                event: Some(event), ..
+            })) = response.payload
+            else {
+                panic!("expected event response");
+            };
+            let event: Event = event.try_into().unwrap();
+            received.push(event.kind().clone());
+        }
+
+        assert_eq!(
+            received,
+            vec![
+                EventKind::SessionStarted {
+                    biome_name: EXAMPLE_BIOME.to_string(),
+                    start_timestamp: TEST_TIMESTAMP.to_string(),
+                },
+                EventKind::SessionEnded {
+                    end_timestamp: "end".to_string(),
+                },
+            ]
+        );
+    }
+}
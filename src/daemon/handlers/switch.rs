@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use prost_types::Any;
+use tracing::trace;
+
+use crate::common::types::pb;
+use crate::common::types::pb::response::Payload::Body;
+use crate::common::types::pb::{Response, Switch};
+use crate::daemon::handlers::execute::spawn_commands;
+use crate::daemon::handlers::{RequestHandler, error_response};
+use crate::daemon::types::context::DaemonContext;
+
+pub struct SwitchHandler;
+impl RequestHandler for SwitchHandler {
+    async fn handle(request: Any, context: Arc<DaemonContext>) -> Any {
+        trace!("handling switch request");
+        let request: Result<Switch> = request
+            .to_msg()
+            .context("failed to convert request to Switch");
+
+        let response = match request {
+            Ok(data) => switch(data, context).await,
+            Err(err) => error_response(err),
+        };
+        Any::from_msg(&response).unwrap()
+    }
+}
+
+async fn switch(request: Switch, context: Arc<DaemonContext>) -> Response {
+    let Switch {
+        session_id,
+        terrain_name,
+        from_biome,
+        to_biome,
+        timestamp,
+        destructors,
+        constructors,
+        envs,
+    } = request;
+    trace!(
+        terrain_name = terrain_name,
+        session_id = session_id,
+        from_biome = from_biome,
+        to_biome = to_biome,
+        timestamp = timestamp,
+        "executing switch request"
+    );
+    // destructors are spawned before switching so they get envs of the previous biome
+    let mut result = Ok(());
+    if let Some(destructors) = destructors {
+        trace!("running destructors of previous biome for switch request");
+        result = spawn_commands(destructors, context.clone()).await;
+    }
+
+    if result.is_ok() {
+        result = context
+            .state_manager()
+            .switch_biome(
+                &terrain_name,
+                &session_id,
+                from_biome,
+                to_biome,
+                envs,
+                timestamp,
+            )
+            .await
+            .context("failed to switch biome");
+    }
+
+    if result.is_ok()
+        && let Some(constructors) = constructors
+    {
+        trace!("running constructors of new biome for switch request");
+        result = spawn_commands(constructors, context).await;
+    }
+
+    match result {
+        Ok(()) => Response {
+            payload: Some(Body(pb::Body {
+                message: None,
+                pong: None,
+                event: None,
+            })),
+        },
+        Err(err) => error_response(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use crate::client::types::terrain::AutoApply;
+    use crate::common::constants::{
+        EXAMPLE_BIOME, NONE, TERRAIN_SELECTED_BIOME, TERRAIN_STATE_FILE_NAME, TEST_TIMESTAMP,
+    };
+    use crate::common::execute::MockExecutor;
+    use crate::common::test_utils::{TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::paths::DaemonPaths;
+    use crate::common::types::pb;
+    use crate::common::types::pb::response::Payload::Body;
+    use crate::common::types::terrain_state::TerrainState;
+    use crate::common::types::terrain_state::test_utils::{
+        terrain_state_after_construct, terrain_state_after_switch,
+    };
+    use crate::common::utils::{create_file, write_to_file};
+    use crate::daemon::types::config::DaemonConfig;
+    use crate::daemon::types::context::DaemonContext;
+
+    #[tokio::test]
+    async fn test_switch() {
+        let state_directory = tempdir().unwrap();
+        let is_auto_apply = false;
+        let auto_apply = AutoApply::Off;
+
+        let context = DaemonContext::new(
+            false,
+            DaemonConfig::default(),
+            Arc::new(MockExecutor::new()),
+            Default::default(),
+            DaemonPaths::new(state_directory.path().to_str().unwrap()),
+        )
+        .await;
+
+        // setup previous state with constructors already added
+        let terrain_state_file = state_directory.path().join(format!(
+            "{TEST_TERRAIN_NAME}/{TEST_SESSION_ID}/{TERRAIN_STATE_FILE_NAME}"
+        ));
+        let old_state =
+            terrain_state_after_construct(TEST_SESSION_ID.to_string(), is_auto_apply, &auto_apply);
+
+        let mut state_file = create_file(&terrain_state_file).await.unwrap();
+        write_to_file(
+            &mut state_file,
+            serde_json::to_string_pretty(&old_state).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        let mut envs = BTreeMap::new();
+        envs.insert("EDITOR".to_string(), "vim".to_string());
+        envs.insert(TERRAIN_SELECTED_BIOME.to_string(), NONE.to_string());
+
+        let request = pb::Switch {
+            session_id: TEST_SESSION_ID.to_string(),
+            terrain_name: TEST_TERRAIN_NAME.to_string(),
+            from_biome: EXAMPLE_BIOME.to_string(),
+            to_biome: NONE.to_string(),
+            timestamp: TEST_TIMESTAMP.to_string(),
+            destructors: None,
+            constructors: None,
+            envs: envs.clone(),
+        };
+
+        let response = super::switch(request, Arc::new(context)).await;
+        assert_eq!(
+            response,
+            pb::Response {
+                payload: Some(Body(pb::Body {
+                    message: None,
+                    pong: None,
+                    event: None,
+                })),
+            }
+        );
+
+        let actual_state: TerrainState =
+            serde_json::from_str(&fs::read_to_string(&terrain_state_file).unwrap()).unwrap();
+
+        assert_eq!(
+            actual_state,
+            terrain_state_after_switch(TEST_SESSION_ID.to_string(), envs)
+        );
+    }
+}
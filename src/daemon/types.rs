@@ -2,6 +2,7 @@ pub mod config;
 pub mod context;
 pub mod daemon;
 pub mod daemon_socket;
-pub(super) mod history;
+pub(crate) mod history;
+pub mod notifier;
 pub mod state;
 pub mod state_manager;
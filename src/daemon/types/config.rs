@@ -9,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, trace};
 
 use crate::common::constants::{CONFIG_LOCATION, TERRAINIUMD_CONF};
+use crate::common::types::command::{Command, NotifyOn};
 
 #[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -18,6 +19,59 @@ pub struct DaemonConfig {
 
     is_root_allowed: bool,
     history_size: usize,
+
+    /// notifications when background commands finish
+    #[serde(default)]
+    notifications: Notifications,
+}
+
+#[cfg_attr(feature = "terrain-schema", derive(JsonSchema))]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Notifications {
+    /// transitions that notify for commands that do not set `notify`
+    #[serde(default)]
+    on: NotifyOn,
+
+    /// show desktop notification with `notify-send` if it is installed
+    #[serde(default = "default_desktop")]
+    desktop: bool,
+
+    /// command run with the event as json on stdin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hook: Option<Command>,
+}
+
+fn default_desktop() -> bool {
+    true
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            on: NotifyOn::default(),
+            desktop: default_desktop(),
+            hook: None,
+        }
+    }
+}
+
+impl Notifications {
+    #[cfg(test)]
+    pub(crate) fn new(on: NotifyOn, desktop: bool, hook: Option<Command>) -> Self {
+        Self { on, desktop, hook }
+    }
+
+    pub fn on(&self) -> NotifyOn {
+        self.on
+    }
+
+    pub fn desktop(&self) -> bool {
+        self.desktop
+    }
+
+    pub fn hook(&self) -> Option<&Command> {
+        self.hook.as_ref()
+    }
 }
 
 pub fn schema_url() -> String {
@@ -38,6 +92,7 @@ impl Default for DaemonConfig {
             schema: schema_url(),
             is_root_allowed: false,
             history_size: 5,
+            notifications: Notifications::default(),
         }
     }
 }
@@ -46,10 +101,10 @@ impl DaemonConfig {
     pub fn from_file() -> Result<Self> {
         let path = get_config_path();
         info!("reading config from {path:?}");
-        if path.exists() {
-            if let Ok(toml_str) = read_to_string(&path) {
-                return toml::from_str(&toml_str).context("invalid config");
-            }
+        if path.exists()
+            && let Ok(toml_str) = read_to_string(&path)
+        {
+            return toml::from_str(&toml_str).context("invalid config");
         }
         bail!("failed to read configuration file {path:?}")
     }
@@ -73,4 +128,8 @@ impl DaemonConfig {
     pub fn history_size(&self) -> usize {
         self.history_size
     }
+
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
 }
@@ -1,4 +1,6 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 use tokio_util::sync::CancellationToken;
 
@@ -6,15 +8,19 @@ use tokio_util::sync::CancellationToken;
 use crate::common::execute::Executor;
 use crate::common::types::paths::DaemonPaths;
 use crate::daemon::types::config::DaemonConfig;
+use crate::daemon::types::notifier::Notifier;
 use crate::daemon::types::state_manager::StateManager;
 
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct DaemonContext {
     is_root: bool,
     is_root_allowed: bool,
     executor: Arc<Executor>,
     cancellation_token: CancellationToken,
     state_manager: Arc<StateManager>,
+    notifier: Notifier,
+    started: Instant,
+    running_commands: Arc<AtomicUsize>,
 }
 
 impl DaemonContext {
@@ -26,12 +32,16 @@ impl DaemonContext {
         daemon_paths: DaemonPaths,
     ) -> Self {
         let state_manager = StateManager::init(daemon_paths, config.history_size()).await;
+        let notifier = Notifier::new(config.notifications().clone(), executor.clone());
         DaemonContext {
             is_root,
             is_root_allowed: config.is_root_allowed(),
             executor,
             cancellation_token,
             state_manager: Arc::new(state_manager),
+            notifier,
+            started: Instant::now(),
+            running_commands: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -47,6 +57,10 @@ impl DaemonContext {
         self.executor.clone()
     }
 
+    pub fn notifier(&self) -> Notifier {
+        self.notifier.clone()
+    }
+
     pub fn cancellation_token(&self) -> CancellationToken {
         self.cancellation_token.clone()
     }
@@ -55,6 +69,19 @@ impl DaemonContext {
         self.state_manager.setup_cleanup();
     }
 
+    pub fn uptime(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// background commands spawned by daemon that have not exited yet
+    pub fn running_commands(&self) -> usize {
+        self.running_commands.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn running_commands_counter(&self) -> Arc<AtomicUsize> {
+        self.running_commands.clone()
+    }
+
     pub fn should_exit_early(&self) -> bool {
         self.is_root && !self.is_root_allowed
     }
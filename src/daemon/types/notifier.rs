@@ -0,0 +1,290 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tracing::{debug, error};
+
+use crate::common::constants::{CONSTRUCTORS, DESTRUCTORS};
+use crate::common::execute::Execute;
+#[mockall_double::double]
+use crate::common::execute::Executor;
+use crate::common::types::command::{Command, is_exe_in_path};
+use crate::common::types::event::{Event, EventKind};
+use crate::common::types::terrain_state::CommandStatus;
+use crate::daemon::types::config::Notifications;
+
+const NOTIFY_SEND: &str = "notify-send";
+
+/// notifies user when a background command succeeds or fails, configured by
+/// `notifications` of terrainiumd.toml and `notify` of the command
+#[derive(Clone, Debug)]
+pub struct Notifier {
+    notifications: Notifications,
+    executor: Arc<Executor>,
+    /// set if desktop notifications are enabled and `notify-send` is installed
+    notify_send: Option<PathBuf>,
+}
+
+impl Notifier {
+    pub fn new(notifications: Notifications, executor: Arc<Executor>) -> Self {
+        let notify_send = if notifications.desktop() {
+            is_exe_in_path(NOTIFY_SEND)
+        } else {
+            None
+        };
+        Self {
+            notifications,
+            executor,
+            notify_send,
+        }
+    }
+
+    /// runs hook and `notify-send` in background so command status updates are
+    /// not blocked by them
+    pub fn notify(&self, event: Event) {
+        let commands = match self.commands(&event) {
+            Ok(commands) => commands,
+            Err(err) => {
+                error!("failed to create notification: {err:#}");
+                return;
+            }
+        };
+
+        for (command, input) in commands {
+            let executor = self.executor.clone();
+            tokio::spawn(async move {
+                debug!("notifying with {command}");
+                let result = match input {
+                    Some(input) => executor.async_spawn_with_input(command, input).await,
+                    None => executor.async_spawn(None, command).await,
+                };
+                match result {
+                    Ok(status) if !status.success() => {
+                        error!("notification command exited with {:?}", status.code())
+                    }
+                    Ok(_) => {}
+                    Err(err) => error!("failed to run notification command: {err:#}"),
+                }
+            });
+        }
+    }
+
+    /// commands to run for the event with their input, empty if command state
+    /// transition does not need a notification
+    fn commands(&self, event: &Event) -> Result<Vec<(Command, Option<String>)>> {
+        let EventKind::CommandStateChanged {
+            is_constructor,
+            command_state,
+            ..
+        } = event.kind()
+        else {
+            return Ok(vec![]);
+        };
+
+        let notify_on = command_state
+            .command()
+            .notify()
+            .unwrap_or(self.notifications.on());
+        let (should_notify, result) = match command_state.status() {
+            CommandStatus::Succeeded => (notify_on.on_success(), "succeeded".to_string()),
+            CommandStatus::Failed(Some(code)) => (
+                notify_on.on_failure(),
+                format!("failed with exit code {code}"),
+            ),
+            CommandStatus::Failed(None) => (notify_on.on_failure(), "failed".to_string()),
+            CommandStatus::Starting | CommandStatus::Running => (false, String::new()),
+        };
+        if !should_notify {
+            return Ok(vec![]);
+        }
+
+        let mut commands = vec![];
+        if let Some(hook) = self.notifications.hook() {
+            let mut hook = hook.clone();
+            if hook.cwd().is_none() {
+                hook.set_cwd(Some(std::env::temp_dir()));
+            }
+            let input = serde_json::to_string(event).context("failed to serialize event")?;
+            commands.push((hook, Some(input)));
+        }
+
+        if let Some(notify_send) = &self.notify_send {
+            let commands_type = if *is_constructor {
+                CONSTRUCTORS
+            } else {
+                DESTRUCTORS
+            };
+            let command = command_state.command();
+            let urgency = if matches!(command_state.status(), CommandStatus::Failed(_)) {
+                "critical"
+            } else {
+                "normal"
+            };
+            commands.push((
+                Command::new(
+                    notify_send.to_string_lossy().to_string(),
+                    vec![
+                        format!("--urgency={urgency}"),
+                        format!("terrainium: {}", event.terrain_name()),
+                        format!(
+                            "{commands_type}: `{} {}` {result}",
+                            command.exe(),
+                            command.args().join(" ")
+                        ),
+                    ],
+                    Some(std::env::temp_dir()),
+                ),
+                None,
+            ));
+        }
+        Ok(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use pretty_assertions::assert_eq;
+
+    use super::Notifier;
+    use crate::client::types::terrain::AutoApply;
+    use crate::common::constants::TEST_TIMESTAMP;
+    use crate::common::execute::MockExecutor;
+    use crate::common::test_utils::{TEST_SESSION_ID, TEST_TERRAIN_NAME};
+    use crate::common::types::command::{Command, NotifyOn};
+    use crate::common::types::event::{Event, EventKind};
+    use crate::common::types::pb;
+    use crate::common::types::terrain_state::test_utils::terrain_state_after_activate;
+    use crate::common::types::terrain_state::{CommandState, CommandStatus};
+    use crate::daemon::types::config::Notifications;
+
+    fn event(status: CommandStatus) -> Event {
+        let command_state =
+            terrain_state_after_activate(TEST_SESSION_ID.to_string(), false, &AutoApply::All)
+                .get_constructors(TEST_TIMESTAMP)
+                .unwrap()
+                .remove(0);
+        event_for(command_state, status)
+    }
+
+    fn event_for(mut command_state: CommandState, status: CommandStatus) -> Event {
+        command_state.set_status(status);
+        Event::new(
+            TEST_TERRAIN_NAME.to_string(),
+            TEST_SESSION_ID.to_string(),
+            EventKind::CommandStateChanged {
+                is_constructor: true,
+                timestamp: TEST_TIMESTAMP.to_string(),
+                index: 0,
+                command_state,
+            },
+        )
+    }
+
+    fn with_config(on: NotifyOn, hook: Option<Command>, notify_send: bool) -> Notifier {
+        Notifier {
+            notifications: Notifications::new(on, notify_send, hook),
+            executor: Arc::new(MockExecutor::new()),
+            notify_send: notify_send.then(|| PathBuf::from("/usr/bin/notify-send")),
+        }
+    }
+
+    #[test]
+    fn notifies_only_configured_transitions() {
+        let notifier = with_config(NotifyOn::Failure, None, true);
+        assert!(
+            notifier
+                .commands(&event(CommandStatus::Succeeded))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            notifier
+                .commands(&event(CommandStatus::Running))
+                .unwrap()
+                .is_empty()
+        );
+
+        let commands = notifier
+            .commands(&event(CommandStatus::Failed(Some(1))))
+            .unwrap();
+        assert_eq!(
+            commands,
+            vec![(
+                Command::new(
+                    "/usr/bin/notify-send".to_string(),
+                    vec![
+                        "--urgency=critical".to_string(),
+                        "terrainium: terrainium".to_string(),
+                        "constructors: `/bin/bash -c ${PWD}/tests/scripts/print_num_for_10_sec` failed with exit code 1".to_string(),
+                    ],
+                    Some(std::env::temp_dir()),
+                ),
+                None
+            )]
+        );
+
+        let notifier = with_config(NotifyOn::Never, None, true);
+        assert!(
+            notifier
+                .commands(&event(CommandStatus::Failed(Some(1))))
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn runs_hook_with_event_json() {
+        let hook = Command::new("/path/to/hook".to_string(), vec![], None);
+        let notifier = with_config(NotifyOn::Always, Some(hook), false);
+
+        let event = event(CommandStatus::Succeeded);
+        let commands = notifier.commands(&event).unwrap();
+        assert_eq!(
+            commands,
+            vec![(
+                Command::new(
+                    "/path/to/hook".to_string(),
+                    vec![],
+                    Some(std::env::temp_dir())
+                ),
+                Some(serde_json::to_string(&event).unwrap())
+            )]
+        );
+    }
+
+    #[test]
+    fn command_notify_overrides_config() {
+        let command_state = CommandState::from(
+            "/tmp",
+            TEST_TERRAIN_NAME,
+            TEST_SESSION_ID,
+            true,
+            0,
+            TEST_TIMESTAMP,
+            pb::Command {
+                exe: "/bin/true".to_string(),
+                args: vec![],
+                cwd: "/tmp".to_string(),
+                notify: Some(pb::NotifyOn::Failure.into()),
+            },
+        );
+        let notifier = with_config(NotifyOn::Never, None, true);
+
+        assert!(
+            notifier
+                .commands(&event_for(command_state.clone(), CommandStatus::Succeeded))
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            notifier
+                .commands(&event_for(command_state, CommandStatus::Failed(None)))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
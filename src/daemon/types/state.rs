@@ -5,14 +5,16 @@ use anyhow::{Context, Result, bail};
 use tokio::fs::File;
 use tracing::{debug, instrument};
 
+use crate::common::types::event::{Event, EventKind};
 use crate::common::types::terrain_state::{CommandState, CommandStatus, TerrainState};
 use crate::common::utils;
-use crate::daemon::types::state_manager::StoredHistory;
+use crate::daemon::types::state_manager::{EventSender, StoredHistory};
 
 #[derive(Debug)]
 pub struct State {
     state: TerrainState,
     file: StateFile,
+    events: EventSender,
 }
 
 impl State {
@@ -20,6 +22,7 @@ impl State {
         state_directory: &str,
         history: StoredHistory,
         state: TerrainState,
+        events: EventSender,
     ) -> Result<Self> {
         debug!(
             terrain_name = state.terrain_name(),
@@ -32,10 +35,14 @@ impl State {
         file.write_state(history, &state)
             .await
             .context("failed to write initial state")?;
-        Ok(Self { state, file })
+        Ok(Self {
+            state,
+            file,
+            events,
+        })
     }
 
-    pub async fn read(path: &Path) -> Result<Self> {
+    pub async fn read(path: &Path, events: EventSender) -> Result<Self> {
         if !path.exists() {
             bail!("state file {path:?} does not exist");
         }
@@ -46,7 +53,11 @@ impl State {
             .read_state()
             .await
             .context("failed to read state from the file")?;
-        Ok(Self { state, file })
+        Ok(Self {
+            state,
+            file,
+            events,
+        })
     }
 
     pub async fn add_commands_if_necessary(
@@ -72,7 +83,7 @@ impl State {
         timestamp: &str,
         index: usize,
         status: CommandStatus,
-    ) -> Result<()> {
+    ) -> Result<Event> {
         debug!(
             terrain_name = self.state.terrain_name(),
             session_id = self.state.session_id(),
@@ -84,7 +95,19 @@ impl State {
         self.file
             .write_state(history, &self.state)
             .await
-            .context("failed to update state in the file")
+            .context("failed to update state in the file")?;
+
+        let command_state = self
+            .commands(is_constructor, timestamp)?
+            .get(index)
+            .cloned()
+            .context(format!("command state does not exist for index: {index}"))?;
+        Ok(self.send(EventKind::CommandStateChanged {
+            is_constructor,
+            timestamp: timestamp.to_string(),
+            index,
+            command_state,
+        }))
     }
 
     pub async fn update_end_timestamp(
@@ -92,13 +115,45 @@ impl State {
         history: StoredHistory,
         timestamp: String,
     ) -> Result<()> {
-        self.state.update_end_timestamp(timestamp);
+        self.state.update_end_timestamp(timestamp.clone());
+        self.file
+            .write_state(history, &self.state)
+            .await
+            .context("failed to update state in the file")?;
+
+        self.send(EventKind::SessionEnded {
+            end_timestamp: timestamp,
+        });
+        Ok(())
+    }
+
+    pub async fn switch_biome(
+        &mut self,
+        history: StoredHistory,
+        from_biome: String,
+        to_biome: String,
+        envs: BTreeMap<String, String>,
+        timestamp: String,
+    ) -> Result<()> {
+        self.state
+            .switch_biome(from_biome, to_biome, envs, timestamp);
         self.file
             .write_state(history, &self.state)
             .await
             .context("failed to update state in the file")
     }
 
+    /// notifies subscribers, sending fails only when there are no subscribers
+    fn send(&self, kind: EventKind) -> Event {
+        let event = Event::new(
+            self.terrain_name().to_string(),
+            self.session_id().to_string(),
+            kind,
+        );
+        let _ = self.events.send(event.clone());
+        event
+    }
+
     pub fn terrain_name(&self) -> &str {
         self.state.terrain_name()
     }
@@ -1,13 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use tokio::time;
 use tracing::{debug, error, instrument, trace};
 
 use crate::common::constants::TERRAIN_STATE_FILE_NAME;
+use crate::common::types::event::{Event, EventKind};
 use crate::common::types::paths::DaemonPaths;
 use crate::common::types::terrain_state::{CommandState, TerrainState};
 use crate::daemon::types::history::History;
@@ -15,13 +16,18 @@ use crate::daemon::types::state::State;
 
 pub type StoredState = Arc<RwLock<State>>;
 pub type StoredHistory = Arc<RwLock<History>>;
+pub type EventSender = broadcast::Sender<Event>;
 
-#[derive(Default, Clone, Debug)]
+/// events buffered for each subscriber, slower subscribers miss older events
+const EVENTS_CAPACITY: usize = 256;
+
+#[derive(Clone, Debug)]
 pub struct StateManager {
     state_paths: DaemonPaths,
     states: Arc<RwLock<HashMap<String, StoredState>>>,
     histories: Arc<RwLock<HashMap<String, StoredHistory>>>,
     history_size: usize,
+    events: EventSender,
 }
 
 fn state_key(terrain_name: &str, session_id: &str) -> String {
@@ -34,11 +40,13 @@ impl StateManager {
         trace!("initializing state manager");
         let states = Arc::new(RwLock::new(HashMap::<String, StoredState>::new()));
         let histories = Arc::new(RwLock::new(HashMap::<String, StoredHistory>::new()));
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
         Self {
             state_paths: state_directory,
             states,
             histories,
             history_size,
+            events,
         }
     }
 
@@ -46,6 +54,11 @@ impl StateManager {
         &self.state_paths
     }
 
+    /// receives events for sessions and commands updated after subscribing
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<Event> {
+        self.events.subscribe()
+    }
+
     #[instrument(skip(self))]
     pub(crate) async fn get_or_create_history(&self, terrain_name: &str) -> Result<StoredHistory> {
         trace!("getting history");
@@ -80,16 +93,32 @@ impl StateManager {
             .await
             .context(format!("failed to create history file {terrain_name}"))?;
 
+        let started = Event::new(
+            terrain_name.clone(),
+            session_id.clone(),
+            EventKind::SessionStarted {
+                biome_name: terrain_state.biome_name().to_string(),
+                start_timestamp: terrain_state.start_timestamp().to_string(),
+            },
+        );
+
         let state = Arc::new(RwLock::new(
-            State::new(self.state_paths.dir_str(), history, terrain_state)
-                .await
-                .context("failed to create state")?,
+            State::new(
+                self.state_paths.dir_str(),
+                history,
+                terrain_state,
+                self.events.clone(),
+            )
+            .await
+            .context("failed to create state")?,
         ));
 
         self.states
             .write()
             .await
             .insert(state_key(&terrain_name, &session_id), state.clone());
+        // sending fails only when there are no subscribers
+        let _ = self.events.send(started);
 
         trace!(
             terrain_name = terrain_name,
@@ -115,7 +144,7 @@ impl StateManager {
                 .join(TERRAIN_STATE_FILE_NAME);
 
         let state = Arc::new(RwLock::new(
-            State::read(&state_file)
+            State::read(&state_file, self.events.clone())
                 .await
                 .context("failed to create state")?,
         ));
@@ -178,6 +207,35 @@ impl StateManager {
             .context("failed to update end_timestamp")
     }
 
+    #[instrument(skip(self))]
+    pub(crate) async fn switch_biome(
+        &self,
+        terrain_name: &str,
+        session_id: &str,
+        from_biome: String,
+        to_biome: String,
+        envs: BTreeMap<String, String>,
+        timestamp: String,
+    ) -> Result<()> {
+        let stored_state = self.refreshed_state(terrain_name, session_id).await?;
+        let mut state = stored_state.write().await;
+
+        trace!("switching biome");
+        let history = self
+            .get_or_create_history(terrain_name)
+            .await
+            .context(format!("failed to create history file {terrain_name}"))?;
+        state
+            .switch_biome(history, from_biome, to_biome, envs, timestamp)
+            .await
+            .context("failed to switch biome")
+    }
+
+    /// number of sessions whose state is loaded in daemon
+    pub(crate) async fn sessions(&self) -> usize {
+        self.states.read().await.len()
+    }
+
     #[instrument(skip(self))]
     pub(crate) async fn refreshed_state(
         &self,